@@ -33,11 +33,21 @@ fn main() -> io::Result<()> {
 }
 
 fn run(input: &str, env: &mut nara::Env) -> Result<Option<nara::Val>, String> {
-    let parse = nara::parse(input).map_err(|msg| format!("Parse error: {}", msg))?;
+    // type errors are reported before anything runs, like syntax errors
+    let parse = nara::check_in(input, env).map_err(|errors| {
+        errors
+            .iter()
+            .map(|err| match err.kind() {
+                "syntax" => format!("Parse error: {}", err),
+                _ => format!("Check error: {}", err),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    })?;
 
     let evaluated = parse
         .eval(env)
-        .map_err(|msg| format!("Evaluation error: {}", msg))?;
+        .map_err(|err| format!("Evaluation error: {}", err))?;
 
     if evaluated == nara::Val::Unit {
         Ok(None)
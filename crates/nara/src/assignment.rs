@@ -0,0 +1,256 @@
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    expression::{Expression, Op, index},
+    lexer::{Span, Token, TokenKind},
+    utils,
+};
+
+// `x = 1`, `xs[i][j] = 1`, or a compound assignment like `x += 1` that
+// combines the current value with `val` by `op`. the indexes are evaluated
+// once, so `xs[f()] += 1` calls `f` a single time
+#[derive(Debug, Clone)]
+pub(crate) struct Assignment {
+    pub(crate) name: String,
+    pub(crate) indices: Vec<Expression>,
+    pub(crate) op: Option<Op>,
+    pub(crate) val: Expression,
+    pub(crate) span: Span,
+}
+
+// spans are not part of an assignment's identity, see `Expression`
+impl PartialEq for Assignment {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.indices == other.indices
+            && self.op == other.op
+            && self.val == other.val
+    }
+}
+
+impl Assignment {
+    // whether the token after a name makes the statement an assignment
+    pub(crate) fn is_assign_op(kind: &TokenKind) -> bool {
+        matches!(
+            kind,
+            TokenKind::Assign
+                | TokenKind::PlusAssign
+                | TokenKind::MinusAssign
+                | TokenKind::StarAssign
+                | TokenKind::SlashAssign
+        )
+    }
+
+    // whether the tokens start an assignment, a name followed by indexes in
+    // brackets and an assignment operator
+    pub(crate) fn starts_at(tokens: &[Token]) -> bool {
+        if !matches!(utils::peek(tokens), TokenKind::Ident(_)) {
+            return false;
+        }
+
+        let mut depth = 0;
+        for token in &tokens[1..] {
+            match &token.kind {
+                TokenKind::LBracket => depth += 1,
+                TokenKind::RBracket if depth > 0 => depth -= 1,
+                TokenKind::Eof => return false,
+                kind if depth == 0 => return Self::is_assign_op(kind),
+                _ => {}
+            }
+        }
+
+        false
+    }
+
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let (mut tokens, name) = utils::extract_ident(tokens)?;
+
+        let mut indices = Vec::new();
+        while let Ok(rest) = utils::tag(&TokenKind::LBracket, tokens) {
+            let (rest, index) = Expression::new(rest)?;
+            tokens = utils::tag(&TokenKind::RBracket, rest)?;
+            indices.push(index);
+        }
+
+        let op = match utils::peek(tokens) {
+            TokenKind::Assign => None,
+            TokenKind::PlusAssign => Some(Op::Add),
+            TokenKind::MinusAssign => Some(Op::Sub),
+            TokenKind::StarAssign => Some(Op::Mul),
+            TokenKind::SlashAssign => Some(Op::Div),
+            _ => return Err(utils::expected("assignment operator", tokens)),
+        };
+
+        let (tokens, val) = Expression::new_standalone(&tokens[1..])?;
+
+        let tokens = utils::extract_semicolon(tokens);
+
+        Ok((
+            tokens,
+            Self {
+                name,
+                indices,
+                op,
+                val,
+                span: utils::consumed_span(start, tokens),
+            },
+        ))
+    }
+
+    pub(crate) fn eval(&self, env: &Env) -> Result<(), Unwind> {
+        let mut path = Vec::new();
+        for index in &self.indices {
+            path.push((index.eval(env)?, index.span.clone()));
+        }
+        let mut val = self.val.eval(env)?;
+
+        if self.op.is_none() && path.is_empty() {
+            return env
+                .assign(&self.name, val)
+                .map_err(|err| err.or_span(&self.span).into());
+        }
+
+        // read after the value, which may have changed the binding
+        let mut target = env
+            .get_binding_value_restrict(&self.name)
+            .map_err(|err| err.or_span(&self.span))?;
+
+        if let Some(op) = &self.op {
+            let mut current = target.clone();
+            for (index, span) in &path {
+                current = index::get(&current, index, span)?;
+            }
+            val = op.apply(current, val, env, &self.span)?;
+        }
+
+        // lists are values, so an element is replaced by storing the updated
+        // list back into the binding
+        index::set(&mut target, &path, val)?;
+        env.assign(&self.name, target)
+            .map_err(|err| err.or_span(&self.span).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        expression::{ExpressionKind, Number},
+        val::Val,
+    };
+
+    #[test]
+    fn parse_assignment() {
+        assert_eq!(
+            utils::parse_str(Assignment::new, "x = 5;"),
+            Ok(Assignment {
+                name: "x".to_string(),
+                indices: Vec::new(),
+                op: None,
+                val: Expression::from(ExpressionKind::Number(Number(5))),
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_compound_assignment() {
+        assert_eq!(
+            utils::parse_str(Assignment::new, "x -= 2"),
+            Ok(Assignment {
+                name: "x".to_string(),
+                indices: Vec::new(),
+                op: Some(Op::Sub),
+                val: Expression::from(ExpressionKind::Number(Number(2))),
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn assign_updates_binding_in_parent_frame() {
+        let mut parent = Env::default();
+        parent.define("x".to_string(), Some(Val::Number(1)), true);
+        let child = parent.create_child();
+
+        let assignment = utils::parse_str(Assignment::new, "x *= 10").unwrap();
+        assert_eq!(assignment.eval(&child), Ok(()));
+        assert_eq!(parent.get_binding_value_restrict("x"), Ok(Val::Number(10)));
+    }
+
+    #[test]
+    fn cannot_assign_to_val_binding() {
+        let mut env = Env::default();
+        env.store_binding("x".to_string(), Val::Number(1));
+
+        let assignment = utils::parse_str(Assignment::new, "x = 2").unwrap();
+
+        assert!(matches!(
+            assignment.eval(&env),
+            Err(Unwind::Error(Error::AssignToImmutable { ref name, .. })) if name == "x"
+        ));
+    }
+
+    #[test]
+    fn cannot_assign_to_unbound_name() {
+        let assignment = utils::parse_str(Assignment::new, "nope = 2").unwrap();
+
+        assert!(matches!(
+            assignment.eval(&Env::default()),
+            Err(Unwind::Error(Error::UnboundName { .. }))
+        ));
+    }
+
+    #[test]
+    fn detect_index_assignment() {
+        let starts = |s: &str| Assignment::starts_at(&crate::lexer::lex(s).unwrap());
+
+        assert!(starts("xs[0] = 1"));
+        assert!(starts("grid[i][j - 1] += 1"));
+        assert!(!starts("xs[0] == 1"));
+        assert!(!starts("xs[0]"));
+        assert!(!starts("f(x) = 1"));
+    }
+
+    #[test]
+    fn assign_nested_element() {
+        let mut env = Env::default();
+        env.define(
+            "grid".to_string(),
+            Some(Val::List(vec![Val::List(vec![
+                Val::Number(1),
+                Val::Number(2),
+            ])])),
+            true,
+        );
+
+        let assignment = utils::parse_str(Assignment::new, "grid[0][-1] *= 10").unwrap();
+        assert_eq!(assignment.indices.len(), 2);
+        assert_eq!(assignment.eval(&env), Ok(()));
+        assert_eq!(
+            env.get_binding_value_restrict("grid"),
+            Ok(Val::List(vec![Val::List(vec![
+                Val::Number(1),
+                Val::Number(20)
+            ])]))
+        );
+    }
+
+    #[test]
+    fn cannot_assign_element_of_val_binding() {
+        let mut env = Env::default();
+        env.store_binding("xs".to_string(), Val::List(vec![Val::Number(1)]));
+
+        let assignment = utils::parse_str(Assignment::new, "xs[0] = 2").unwrap();
+
+        assert!(matches!(
+            assignment.eval(&env),
+            Err(Unwind::Error(Error::AssignToImmutable { .. }))
+        ));
+        assert_eq!(
+            env.get_binding_value_restrict("xs"),
+            Ok(Val::List(vec![Val::Number(1)]))
+        );
+    }
+}
@@ -1,43 +1,92 @@
 use crate::{
     env::Env,
+    error::{Error, Unwind},
     expression::Expression,
-    utils::{self, tag},
+    lexer::{Span, Token, TokenKind},
+    pattern::Pattern,
+    type_expr::TypeExpr,
+    utils,
 };
 
-#[derive(Debug, PartialEq, Clone)]
+// `val x = 1`, `mut x: int = 1` or a declaration without a value like
+// `val x: int` and `x: int`, which has to be assigned before it is read.
+// `val (a, b) = pair` binds each name of a pattern
+#[derive(Debug, Clone)]
 pub(crate) struct BindingDef {
-    pub(crate) name: String,
-    pub(crate) val: Expression,
+    pub(crate) pattern: Pattern,
+    pub(crate) mutable: bool,
+    pub(crate) ty: Option<TypeExpr>,
+    pub(crate) val: Option<Expression>,
+    pub(crate) span: Span,
+}
+
+// spans are not part of a binding's identity, see `Expression`
+impl PartialEq for BindingDef {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+            && self.mutable == other.mutable
+            && self.ty == other.ty
+            && self.val == other.val
+    }
 }
 
 impl BindingDef {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        let s = utils::tag("val", s)?;
-        let (s, _) = utils::extract_whitespace_restrict(s)?;
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+
+        // without `val` or `mut` only a declaration like `x: int` is a binding
+        let (tokens, mutable) = match utils::peek(tokens) {
+            TokenKind::Val => (&tokens[1..], false),
+            TokenKind::Mut => (&tokens[1..], true),
+            TokenKind::Ident(_) if tokens[1].kind == TokenKind::Colon => (tokens, false),
+            _ => return Err(utils::expected("`val` or `mut`", tokens)),
+        };
 
-        let (s, name) = utils::extract_ident(s)?;
-        let (s, _) = utils::extract_whitespace(s);
+        let (tokens, pattern) = Pattern::new(tokens)?;
 
-        let s = tag("=", s)?;
-        let (s, _) = utils::extract_whitespace(s);
+        let (tokens, ty) = match utils::tag(&TokenKind::Colon, tokens) {
+            Ok(tokens) => TypeExpr::new(tokens).map(|(tokens, ty)| (tokens, Some(ty)))?,
+            Err(_) => (tokens, None),
+        };
 
-        let (s, val) = Expression::new(s)?;
+        let (tokens, val) = match utils::tag(&TokenKind::Assign, tokens) {
+            Ok(tokens) => {
+                Expression::new_standalone(tokens).map(|(tokens, val)| (tokens, Some(val)))?
+            }
+            // there is nothing to take apart without a value
+            Err(_) if !matches!(pattern, Pattern::Name(_)) => {
+                return Err(utils::expected("`=`", tokens));
+            }
+            Err(_) if ty.is_some() => (tokens, None),
+            Err(_) => return Err(utils::expected("`:` or `=`", tokens)),
+        };
 
-        let (s, _) = utils::extract_semicolon(s);
-        let (s, _) = utils::extract_whitespace(s);
+        let tokens = utils::extract_semicolon(tokens);
 
         Ok((
-            s,
+            tokens,
             Self {
-                name: name.to_string(),
+                pattern,
+                mutable,
+                ty,
                 val,
+                span: utils::consumed_span(start, tokens),
             },
         ))
     }
 
     // Store binding to hashmap
-    pub(crate) fn eval(&self, env: &mut Env) -> Result<(), String> {
-        env.store_binding(self.name.clone(), self.val.eval(env)?);
+    pub(crate) fn eval(&self, env: &mut Env) -> Result<(), Unwind> {
+        match (&self.val, &self.pattern) {
+            (Some(val), pattern) => {
+                let val = val.eval(env).map_err(|err| err.or_span(&self.span))?;
+                pattern
+                    .bind(val, env, self.mutable)
+                    .map_err(|err| err.or_span(&self.span))?;
+            }
+            (None, Pattern::Name(name)) => env.define(name.clone(), None, self.mutable),
+            (None, _) => unreachable!("destructuring always has a value, see `new`"),
+        }
         Ok(())
     }
 }
@@ -45,31 +94,105 @@ impl BindingDef {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::expression::{Number, Op};
+    use crate::expression::{ExpressionKind, Number, Op};
 
     #[test]
     fn parse_binding_def() {
         assert_eq!(
-            BindingDef::new("val x = 10 / 5;    "),
-            Ok((
-                "",
-                BindingDef {
-                    name: "x".to_string(),
-                    val: Expression::Operation {
-                        lhs: Box::new(Expression::Number(Number(10))),
-                        rhs: Box::new(Expression::Number(Number(5))),
-                        op: Op::Div,
-                    }
-                }
-            ))
+            utils::parse_str(BindingDef::new, "val x = 10 / 5;    "),
+            Ok(BindingDef {
+                pattern: Pattern::from("x"),
+                mutable: false,
+                ty: None,
+                val: Some(Expression::from(ExpressionKind::Operation {
+                    lhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
+                    rhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
+                    op: Op::Div,
+                })),
+                span: Span::default(),
+            })
         )
     }
 
     #[test]
     fn cannot_parse_binding_def_without_space_after_val() {
         assert_eq!(
-            BindingDef::new("valaaa=1+2"),
-            Err("expected whitespace".to_string()),
+            utils::parse_str(BindingDef::new, "valaaa=1+2"),
+            Err(Error::Syntax {
+                message: "expected `val` or `mut`, found identifier `valaaa`".to_string(),
+                span: Span {
+                    start: 0,
+                    end: 6,
+                    line: 1,
+                    column: 1,
+                    ..Span::default()
+                },
+            }),
         );
     }
+
+    #[test]
+    fn binding_def_span_covers_semicolon() {
+        let binding_def = utils::parse_str(BindingDef::new, "val x = 1 + 2;").unwrap();
+
+        assert_eq!(
+            binding_def.span,
+            Span {
+                start: 0,
+                end: 14,
+                line: 1,
+                column: 1,
+                ..Span::default()
+            }
+        );
+        let val = binding_def.val.unwrap();
+        assert_eq!(val.span.start, 8);
+        assert_eq!(val.span.end, 13);
+    }
+
+    #[test]
+    fn parse_mut_binding_def() {
+        assert_eq!(
+            utils::parse_str(BindingDef::new, "mut count = 0"),
+            Ok(BindingDef {
+                pattern: Pattern::from("count"),
+                mutable: true,
+                ty: None,
+                val: Some(Expression::from(ExpressionKind::Number(Number(0)))),
+                span: Span::default(),
+            })
+        )
+    }
+
+    #[test]
+    fn parse_annotated_binding_def() {
+        let binding_def = utils::parse_str(BindingDef::new, "val x: int = 1;").unwrap();
+
+        assert_eq!(
+            binding_def.ty,
+            Some(TypeExpr {
+                name: "int".to_string(),
+                args: Vec::new(),
+                span: Span::default(),
+            })
+        );
+        assert!(binding_def.val.is_some());
+    }
+
+    #[test]
+    fn parse_declaration_without_value() {
+        for src in ["my_var: int;", "val my_var: int", "mut my_var: int"] {
+            let binding_def = utils::parse_str(BindingDef::new, src).unwrap();
+
+            assert_eq!(binding_def.pattern, Pattern::from("my_var"));
+            assert_eq!(binding_def.val, None);
+        }
+    }
+
+    #[test]
+    fn cannot_parse_binding_def_without_type_or_value() {
+        let err = utils::parse_str(BindingDef::new, "val x;").unwrap_err();
+
+        assert_eq!(err.message(), "expected `:` or `=`, found `;`");
+    }
 }
@@ -0,0 +1,1008 @@
+mod ty;
+
+use crate::{
+    env::Env,
+    error::Error,
+    expression::{
+        Expression, ExpressionKind, FStringPart, FunctionCall, IfExpr, NamedArg, Op, UnaryOp,
+    },
+    function_def::Param,
+    lexer::Span,
+    pattern::Pattern,
+    statement::Statement,
+    type_def::TypeDef,
+    type_expr::TypeExpr,
+    val::Val,
+};
+use std::{collections::HashMap, rc::Rc};
+use ty::{FnTy, ParamTy, Ty};
+
+// checking pass over a parsed program, run by `nara::check` before anything
+// is evaluated. the types of annotations, literals and operators are followed
+// through bindings, calls and returns. whatever is only known when running,
+// like a name bound by an earlier input, is `Ty::Any` and never reported.
+// the errors are the ones evaluating the program would raise
+pub(crate) fn check(statements: &[Statement], env: &Env) -> Vec<Error> {
+    let mut checker = Checker {
+        scopes: vec![Scope::default()],
+        functions: Vec::new(),
+        errors: Vec::new(),
+        env: env.clone(),
+    };
+    checker.statements(statements);
+
+    checker.errors
+}
+
+// `names` are the protocols and associated types an annotation can name,
+// with the type they stand for
+#[derive(Default)]
+struct Scope {
+    vals: HashMap<String, Binding>,
+    types: HashMap<String, Rc<TypeDef>>,
+    names: HashMap<String, Ty>,
+}
+
+// `annotated` bindings keep their declared type when assigned to, the others
+// take whatever they are given like they do when running
+struct Binding {
+    ty: Ty,
+    annotated: bool,
+}
+
+// function whose body is being checked. `ret` is the annotated return type,
+// `returns` what its `return` statements gave so far
+struct FunctionFrame {
+    ret: Option<Ty>,
+    returns: Ty,
+}
+
+// `env` holds what the checked code runs in, like the types and protocols
+// declared by earlier inputs of a REPL
+struct Checker {
+    scopes: Vec<Scope>,
+    functions: Vec<FunctionFrame>,
+    errors: Vec<Error>,
+    env: Env,
+}
+
+impl Checker {
+    fn statements(&mut self, statements: &[Statement]) -> Ty {
+        let mut ty = Ty::Nil;
+        for statement in statements {
+            ty = self.statement(statement);
+        }
+
+        ty
+    }
+
+    // type of the value the statement evaluates to
+    fn statement(&mut self, statement: &Statement) -> Ty {
+        match statement {
+            Statement::BindingDef(def) => {
+                let found = def.val.as_ref().map(|val| (self.expr(val), &val.span));
+                let declared = def.ty.as_ref().map(|ty| self.annotation(ty));
+
+                let ty = match (declared, found) {
+                    (Some(declared), Some((found, span))) => {
+                        self.expect(&declared, &found, span);
+                        declared
+                    }
+                    (Some(ty), None) | (None, Some((ty, _))) => ty,
+                    (None, None) => Ty::Any,
+                };
+                self.bind_as(&def.pattern, ty, def.ty.is_some());
+                Ty::Nil
+            }
+            Statement::Assignment(assignment) => {
+                let annotated = self
+                    .binding(&assignment.name)
+                    .is_some_and(|binding| binding.annotated);
+                let mut target = self.lookup(&assignment.name);
+                for index in &assignment.indices {
+                    let index = self.expr(index);
+                    target = index_ty(target, &index);
+                }
+
+                let mut found = self.expr(&assignment.val);
+                if let Some(op) = &assignment.op {
+                    found = self.operation(&target, &found, op, &assignment.span);
+                }
+
+                // only annotated bindings keep their type
+                if annotated {
+                    self.expect(&target, &found, &assignment.val.span);
+                }
+                Ty::Nil
+            }
+            Statement::FunctionDef(def) => {
+                let mut func = self.fn_ty(&def.params, def.ret.as_ref());
+                // the body may call the function itself
+                self.define(&def.name, Ty::Fn(Some(Rc::new(func.clone()))));
+
+                func.ret = self.function(&def.params, &func, &def.body);
+                self.define(&def.name, Ty::Fn(Some(Rc::new(func))));
+                Ty::Nil
+            }
+            Statement::TypeDef(def) => {
+                let def = Rc::new(def.clone());
+                self.scope().types.insert(def.name.clone(), def.clone());
+                self.define(&def.name, Ty::Any);
+
+                for variant in &def.variants {
+                    for ty in &variant.payload {
+                        self.annotation(ty);
+                    }
+                }
+
+                for field in &def.fields {
+                    let expected = field.ty.as_ref().map(|ty| self.annotation(ty));
+                    if let Some(default) = &field.default {
+                        let found = self.expr(default);
+                        if let Some(expected) = expected {
+                            self.expect(&expected, &found, &default.span);
+                        }
+                    }
+                }
+                Ty::Nil
+            }
+            Statement::ProtoDef(def) => {
+                self.define(&def.name, Ty::Any);
+                self.scope().names.insert(def.name.clone(), Ty::Any);
+
+                // the associated types are only known in the impls
+                self.scoped(|checker| {
+                    for name in &def.types {
+                        checker.scope().names.insert(name.clone(), Ty::Any);
+                    }
+                    for sig in &def.methods {
+                        checker.fn_ty(&sig.params, sig.ret.as_ref());
+                    }
+                });
+                Ty::Nil
+            }
+            Statement::ImplDef(def) => self.scoped(|checker| {
+                for (name, ty) in &def.types {
+                    let ty = checker.annotation(ty);
+                    checker.scope().names.insert(name.clone(), ty);
+                }
+
+                // the kind an impl is for is checked when it runs
+                let target = checker.resolve(&def.target);
+                for method in &def.methods {
+                    let mut func = checker.fn_ty(&method.params, method.ret.as_ref());
+                    // the value the method is called on
+                    if let Some(first) = func.params.first_mut()
+                        && first.ty == Ty::Any
+                    {
+                        first.ty = target.clone();
+                    }
+                    checker.function(&method.params, &func, &method.body);
+                }
+                Ty::Nil
+            }),
+            Statement::Return(ret) => {
+                let found = match &ret.val {
+                    Some(val) => self.expr(val),
+                    None => Ty::Nil,
+                };
+                let span = ret.val.as_ref().map_or(&ret.span, |val| &val.span);
+
+                // a `return` outside a function fails when running
+                let Some(frame) = self.functions.last_mut() else {
+                    return Ty::Never;
+                };
+                let returns = std::mem::replace(&mut frame.returns, Ty::Never);
+                frame.returns = returns.merge(found.clone());
+
+                if let Some(expected) = frame.ret.clone() {
+                    self.expect(&expected, &found, span);
+                }
+                Ty::Never
+            }
+            Statement::Break(brk) => {
+                if let Some(val) = &brk.val {
+                    self.expr(val);
+                }
+                Ty::Never
+            }
+            Statement::Continue(_) => Ty::Never,
+            Statement::Expression(expr) => self.expr(expr),
+            Statement::Error(_) => Ty::Any,
+        }
+    }
+
+    fn expr(&mut self, expr: &Expression) -> Ty {
+        match &expr.kind {
+            ExpressionKind::Number(_) => Ty::Int,
+            ExpressionKind::Float(_) => Ty::Float,
+            ExpressionKind::String(_) => Ty::Str,
+            ExpressionKind::Char(_) => Ty::Char,
+            ExpressionKind::Bool(_) => Ty::Bool,
+            ExpressionKind::Unit => Ty::Nil,
+            ExpressionKind::None => Ty::Opt(Box::new(Ty::Never)),
+            ExpressionKind::FString(parts) => {
+                for part in parts {
+                    if let FStringPart::Interpolation(inner) = part {
+                        self.expr(inner);
+                    }
+                }
+                Ty::Str
+            }
+            ExpressionKind::List(list) => Ty::List(Box::new(self.merged(&list.elements))),
+            ExpressionKind::Tuple(tuple) => {
+                Ty::Tuple(tuple.elements.iter().map(|item| self.expr(item)).collect())
+            }
+            ExpressionKind::Map(map) => {
+                let (mut keys, mut vals) = (Ty::Never, Ty::Never);
+                for (key, val) in &map.entries {
+                    keys = keys.merge(self.expr(key));
+                    vals = vals.merge(self.expr(val));
+                }
+                Ty::Map(Box::new(keys), Box::new(vals))
+            }
+            ExpressionKind::Operation { lhs, rhs, op } => {
+                let (lhs, rhs) = (self.expr(lhs), self.expr(rhs));
+                self.operation(&lhs, &rhs, op, &expr.span)
+            }
+            ExpressionKind::UnaryOp { operand, op } => match (op, self.expr(operand)) {
+                (UnaryOp::Not, Ty::Bool | Ty::Any | Ty::Never) => Ty::Bool,
+                (UnaryOp::Neg, ty @ (Ty::Int | Ty::Float | Ty::Any | Ty::Never)) => ty,
+                (op, operand) => {
+                    self.errors.push(Error::InvalidOperand {
+                        op: op.to_string(),
+                        operand: operand.to_string(),
+                        span: expr.span.clone(),
+                    });
+                    Ty::Any
+                }
+            },
+            ExpressionKind::If(if_expr) => self.if_expr(if_expr),
+            ExpressionKind::While(while_loop) => {
+                self.expr(&while_loop.condition);
+                self.expr(&while_loop.body);
+                Ty::Any
+            }
+            ExpressionKind::Loop(loop_expr) => {
+                self.expr(&loop_expr.body);
+                Ty::Any
+            }
+            ExpressionKind::For(for_loop) => {
+                let item = match self.expr(&for_loop.iterable) {
+                    Ty::List(item) => *item,
+                    Ty::Range => Ty::Int,
+                    Ty::Str => Ty::Char,
+                    _ => Ty::Any,
+                };
+
+                self.scoped(|checker| {
+                    checker.bind(&for_loop.var, item);
+                    checker.expr(&for_loop.body);
+                });
+                Ty::Any
+            }
+            ExpressionKind::TryCatch(try_catch) => {
+                // an `ok` the body evaluates to is unwrapped
+                let body = match self.expr(&try_catch.body) {
+                    Ty::Res(ok, _) => *ok,
+                    other => other,
+                };
+                let handler = self.scoped(|checker| {
+                    checker.define(&try_catch.name, Ty::Any);
+                    checker.expr(&try_catch.handler)
+                });
+
+                body.merge(handler)
+            }
+            ExpressionKind::Match(match_expr) => {
+                let subject = self.expr(&match_expr.subject);
+
+                let mut ty = Ty::Never;
+                for arm in &match_expr.arms {
+                    let body = self.scoped(|checker| {
+                        checker.bind(&arm.pattern, subject.clone());
+                        if let Some(guard) = &arm.guard {
+                            checker.expr(guard);
+                        }
+                        checker.expr(&arm.body)
+                    });
+                    ty = ty.merge(body);
+                }
+                ty
+            }
+            ExpressionKind::Construct(construct) => {
+                let def = self.type_def(&construct.ty);
+                let payload = def
+                    .as_ref()
+                    .and_then(|def| match &construct.variant {
+                        Some(name) => def.variant(name),
+                        None => def.default_variant.map(|i| &def.variants[i]),
+                    })
+                    .map(|variant| variant.payload.clone())
+                    .unwrap_or_default();
+
+                for (i, item) in construct.payload.iter().enumerate() {
+                    let found = self.expr(item);
+                    if let Some(ty) = payload.get(i) {
+                        let expected = self.resolve(ty);
+                        self.expect(&expected, &found, &item.span);
+                    }
+                }
+
+                for field in &construct.fields {
+                    let found = self.expr(&field.val);
+                    if let Some(ty) = def
+                        .as_ref()
+                        .and_then(|def| def.field(&field.name)?.ty.clone())
+                    {
+                        let expected = self.resolve(&ty);
+                        self.expect(&expected, &found, &field.val.span);
+                    }
+                }
+
+                match def {
+                    Some(_) => Ty::Named(construct.ty.clone()),
+                    None => Ty::Any,
+                }
+            }
+            ExpressionKind::FunctionCall(call) => {
+                let args = self.args(&call.args);
+                let named = self.named_args(&call.named);
+
+                if let Some(ty) = self.builtin_call(call, &args, &expr.span) {
+                    return ty;
+                }
+
+                let callee = self.lookup(&call.name);
+                self.call(callee, &call.name, &args, &named, &expr.span)
+            }
+            ExpressionKind::Call(call) => {
+                let callee = self.expr(&call.callee);
+                let args = self.args(&call.args);
+                let named = self.named_args(&call.named);
+
+                self.call(callee, &call.name(), &args, &named, &expr.span)
+            }
+            ExpressionKind::Lambda(lambda) => {
+                let mut func = self.fn_ty(&lambda.params, lambda.ret.as_ref());
+                func.ret = self.function(&lambda.params, &func, &lambda.body);
+                Ty::Fn(Some(Rc::new(func)))
+            }
+            ExpressionKind::Range(range) => {
+                let bounds = [Some(&range.start), Some(&range.end), range.step.as_ref()];
+                for bound in bounds.into_iter().flatten() {
+                    let found = self.expr(bound);
+                    self.expect(&Ty::Int, &found, &bound.span);
+                }
+                Ty::Range
+            }
+            ExpressionKind::Index(index) => {
+                let target = self.expr(&index.target);
+                let index = self.expr(&index.index);
+                index_ty(target, &index)
+            }
+            ExpressionKind::MethodCall(call) => {
+                let target = self.expr(&call.target);
+                for arg in &call.args {
+                    self.expr(arg);
+                }
+                for arg in &call.named {
+                    self.expr(&arg.val);
+                }
+
+                match (target, call.name.as_str()) {
+                    (Ty::Str | Ty::List(_) | Ty::Map(..) | Ty::Range, "len") => Ty::Int,
+                    (Ty::Str, "upper" | "lower" | "trim") => Ty::Str,
+                    (Ty::Str, "contains" | "starts_with" | "ends_with") => Ty::Bool,
+                    _ => Ty::Any,
+                }
+            }
+            ExpressionKind::Field(field) => match (self.expr(&field.target), field.name.as_str()) {
+                (Ty::Tuple(items), name) => name
+                    .parse()
+                    .ok()
+                    .and_then(|i: usize| items.get(i).cloned())
+                    .unwrap_or(Ty::Any),
+                (Ty::Res(ok, _), "ok") => Ty::Opt(ok),
+                (Ty::Res(_, err), "err") => Ty::Opt(err),
+                (Ty::Named(ty), name) => self
+                    .type_def(&ty)
+                    .and_then(|def| def.field(name)?.ty.clone())
+                    .map_or(Ty::Any, |ty| self.resolve(&ty)),
+                _ => Ty::Any,
+            },
+            ExpressionKind::BindingUsage(usage) => self.lookup(&usage.name),
+            ExpressionKind::Block(block) => {
+                self.scoped(|checker| checker.statements(&block.statements))
+            }
+            ExpressionKind::Group(inner) => self.expr(inner),
+            ExpressionKind::Try(inner) => match self.expr(inner) {
+                Ty::Opt(item) | Ty::Res(item, _) => *item,
+                _ => Ty::Any,
+            },
+        }
+    }
+
+    fn operation(&mut self, lhs: &Ty, rhs: &Ty, op: &Op, span: &Span) -> Ty {
+        Ty::operation(lhs, rhs, op).unwrap_or_else(|| {
+            self.errors.push(Error::InvalidOperands {
+                op: op.to_string(),
+                lhs: lhs.to_string(),
+                rhs: rhs.to_string(),
+                span: span.clone(),
+            });
+            Ty::Any
+        })
+    }
+
+    fn if_expr(&mut self, if_expr: &IfExpr) -> Ty {
+        self.expr(&if_expr.condition);
+        let then = self.expr(&if_expr.then_branch);
+
+        match &if_expr.else_branch {
+            Some(else_branch) => {
+                let otherwise = self.expr(else_branch);
+                then.merge(otherwise)
+            }
+            None => Ty::Opt(Box::new(then)),
+        }
+    }
+
+    // `print`, `len`, `some`, `ok`, `err` and `range`, which are found before
+    // any binding of the same name
+    fn builtin_call(
+        &mut self,
+        call: &FunctionCall,
+        args: &[(Ty, &Expression)],
+        span: &Span,
+    ) -> Option<Ty> {
+        let arity = |checker: &mut Self, allowed: &[usize]| {
+            if allowed.contains(&args.len()) {
+                return true;
+            }
+
+            checker.errors.push(Error::ArityMismatch {
+                name: call.name.clone(),
+                expected: args.len().clamp(allowed[0], allowed[allowed.len() - 1]),
+                found: args.len(),
+                span: span.clone(),
+            });
+            false
+        };
+
+        if matches!(
+            call.name.as_str(),
+            "print" | "len" | "some" | "ok" | "err" | "range"
+        ) && let Some(arg) = call.named.first()
+        {
+            self.errors.push(Error::UnknownParam {
+                name: call.name.clone(),
+                param: arg.name.clone(),
+                span: span.clone(),
+            });
+            return Some(Ty::Any);
+        }
+
+        let ty = match call.name.as_str() {
+            "print" => Ty::Nil,
+            "len" => {
+                if arity(self, &[1])
+                    && !matches!(
+                        args[0].0,
+                        Ty::Str | Ty::List(_) | Ty::Range | Ty::Map(..) | Ty::Any | Ty::Never
+                    )
+                {
+                    self.errors.push(Error::TypeMismatch {
+                        expected: "str, list, range or map".to_string(),
+                        found: args[0].0.to_string(),
+                        span: call.args[0].span.clone(),
+                    });
+                }
+                Ty::Int
+            }
+            "some" | "ok" | "err" => {
+                if !arity(self, &[1]) {
+                    return Some(Ty::Any);
+                }
+
+                let item = Box::new(args[0].0.clone());
+                match call.name.as_str() {
+                    "some" => Ty::Opt(item),
+                    "ok" => Ty::Res(item, Box::new(Ty::Never)),
+                    _ => Ty::Res(Box::new(Ty::Never), item),
+                }
+            }
+            "range" => {
+                if arity(self, &[1, 2]) {
+                    for (found, arg) in args {
+                        self.expect(&Ty::Int, found, &arg.span);
+                    }
+                }
+                Ty::Range
+            }
+            _ => return None,
+        };
+
+        Some(ty)
+    }
+
+    fn args<'a>(&mut self, args: &'a [Expression]) -> Vec<(Ty, &'a Expression)> {
+        args.iter().map(|arg| (self.expr(arg), arg)).collect()
+    }
+
+    fn named_args<'a>(&mut self, named: &'a [NamedArg]) -> Vec<(Ty, &'a NamedArg)> {
+        named.iter().map(|arg| (self.expr(&arg.val), arg)).collect()
+    }
+
+    // type a call returns, `callee` is the type of the function called
+    fn call(
+        &mut self,
+        callee: Ty,
+        name: &str,
+        args: &[(Ty, &Expression)],
+        named: &[(Ty, &NamedArg)],
+        span: &Span,
+    ) -> Ty {
+        match callee {
+            Ty::Fn(Some(func)) => {
+                self.call_args(name, &func, args, named, span);
+                func.ret.clone()
+            }
+            Ty::Fn(None) | Ty::Any | Ty::Never => Ty::Any,
+            _ => {
+                self.errors.push(Error::NotCallable {
+                    name: name.to_string(),
+                    span: span.clone(),
+                });
+                Ty::Any
+            }
+        }
+    }
+
+    // arguments of a call to a function with a known signature, checked in
+    // the order `Function::call` binds them
+    fn call_args(
+        &mut self,
+        name: &str,
+        func: &FnTy,
+        args: &[(Ty, &Expression)],
+        named: &[(Ty, &NamedArg)],
+        span: &Span,
+    ) {
+        let params = &func.params;
+        if named.is_empty() || args.len() > params.len() {
+            let required = params
+                .iter()
+                .rposition(|param| !param.default)
+                .map_or(0, |last| last + 1);
+
+            if !(required..=params.len()).contains(&args.len()) {
+                self.errors.push(Error::ArityMismatch {
+                    name: name.to_string(),
+                    expected: if args.len() < required {
+                        required
+                    } else {
+                        params.len()
+                    },
+                    found: args.len(),
+                    span: span.clone(),
+                });
+                return;
+            }
+        }
+
+        let mut given = vec![false; params.len()];
+        for (i, (found, arg)) in args.iter().enumerate() {
+            given[i] = true;
+            self.expect(&params[i].ty, found, &arg.span);
+        }
+
+        for (found, arg) in named {
+            let position = params
+                .iter()
+                .position(|param| param.name.as_deref() == Some(&arg.name));
+            let error = match position {
+                None => Error::UnknownParam {
+                    name: name.to_string(),
+                    param: arg.name.clone(),
+                    span: span.clone(),
+                },
+                Some(i) if given[i] => Error::DuplicateArg {
+                    name: name.to_string(),
+                    param: arg.name.clone(),
+                    span: span.clone(),
+                },
+                Some(i) => {
+                    given[i] = true;
+                    self.expect(&params[i].ty, found, &arg.val.span);
+                    continue;
+                }
+            };
+            self.errors.push(error);
+            return;
+        }
+
+        if let Some(param) = params
+            .iter()
+            .zip(&given)
+            .find_map(|(param, given)| (!given && !param.default).then_some(param))
+        {
+            self.errors.push(Error::MissingArg {
+                name: name.to_string(),
+                param: param.name.clone().unwrap_or_else(|| "_".to_string()),
+                span: span.clone(),
+            });
+        }
+    }
+
+    // signature from the annotations, unannotated parameters and return
+    // types are `Ty::Any`
+    fn fn_ty(&mut self, params: &[Param], ret: Option<&TypeExpr>) -> FnTy {
+        FnTy {
+            params: params
+                .iter()
+                .map(|param| ParamTy {
+                    name: param.name().map(str::to_string),
+                    ty: param.ty.as_ref().map_or(Ty::Any, |ty| self.annotation(ty)),
+                    default: param.default.is_some(),
+                })
+                .collect(),
+            ret: ret.map_or(Ty::Any, |ret| self.annotation(ret)),
+        }
+    }
+
+    // check the body of a function with the signature `func`, returns what
+    // the function returns. the annotated return type if there is one,
+    // otherwise what the body and its `return` statements give
+    fn function(&mut self, params: &[Param], func: &FnTy, body: &Statement) -> Ty {
+        let annotated = func.ret != Ty::Any;
+        self.functions.push(FunctionFrame {
+            ret: annotated.then(|| func.ret.clone()),
+            returns: Ty::Never,
+        });
+
+        let found = self.scoped(|checker| {
+            for (param, param_ty) in params.iter().zip(&func.params) {
+                if let Some(default) = &param.default {
+                    let found = checker.expr(default);
+                    checker.expect(&param_ty.ty, &found, &default.span);
+                }
+                checker.bind(&param.pattern, param_ty.ty.clone());
+            }
+            checker.statement(body)
+        });
+
+        let frame = self.functions.pop().expect("pushed above");
+        if annotated {
+            if let Some(span) = value_span(body) {
+                self.expect(&func.ret, &found, span);
+            }
+            func.ret.clone()
+        } else {
+            found.merge(frame.returns)
+        }
+    }
+
+    // give the names of a pattern the types of the parts of `ty` they match
+    fn bind(&mut self, pattern: &Pattern, ty: Ty) {
+        self.bind_as(pattern, ty, false);
+    }
+
+    fn bind_as(&mut self, pattern: &Pattern, ty: Ty, annotated: bool) {
+        match pattern {
+            Pattern::Name(name) => {
+                let binding = Binding { ty, annotated };
+                self.scope().vals.insert(name.clone(), binding);
+            }
+            Pattern::Wildcard | Pattern::Literal(_) => {}
+            Pattern::Some(inner) => self.bind_as(
+                inner,
+                match ty {
+                    Ty::Opt(item) => *item,
+                    _ => Ty::Any,
+                },
+                annotated,
+            ),
+            Pattern::Ok(inner) => self.bind_as(
+                inner,
+                match ty {
+                    Ty::Res(ok, _) => *ok,
+                    _ => Ty::Any,
+                },
+                annotated,
+            ),
+            Pattern::Err(inner) => self.bind_as(
+                inner,
+                match ty {
+                    Ty::Res(_, err) => *err,
+                    _ => Ty::Any,
+                },
+                annotated,
+            ),
+            Pattern::Variant(name, items) => {
+                let payload = match &ty {
+                    Ty::Named(ty) => self
+                        .type_def(ty)
+                        .and_then(|def| Some(def.variant(name)?.payload.clone()))
+                        .unwrap_or_default(),
+                    _ => Vec::new(),
+                };
+
+                for (i, item) in items.iter().enumerate() {
+                    let ty = payload.get(i).map_or(Ty::Any, |ty| self.resolve(ty));
+                    self.bind_as(item, ty, annotated);
+                }
+            }
+            Pattern::Tuple(items) => {
+                let tys = match ty {
+                    Ty::Tuple(tys) if tys.len() == items.len() => tys,
+                    _ => vec![Ty::Any; items.len()],
+                };
+                for (item, ty) in items.iter().zip(tys) {
+                    self.bind_as(item, ty, annotated);
+                }
+            }
+            Pattern::List(items, rest) => {
+                let item_ty = match ty {
+                    Ty::List(item) => *item,
+                    _ => Ty::Any,
+                };
+                for item in items {
+                    self.bind_as(item, item_ty.clone(), annotated);
+                }
+                if let Some(rest) = rest {
+                    self.bind_as(rest, Ty::List(Box::new(item_ty)), annotated);
+                }
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: &Ty, found: &Ty, span: &Span) {
+        if !expected.fits(found) {
+            self.errors.push(Error::TypeMismatch {
+                expected: expected.to_string(),
+                found: found.to_string(),
+                span: span.clone(),
+            });
+        }
+    }
+
+    fn merged(&mut self, items: &[Expression]) -> Ty {
+        items
+            .iter()
+            .fold(Ty::Never, |ty, item| ty.merge(self.expr(item)))
+    }
+
+    // type of an annotation where it is written, reporting unknown names
+    fn annotation(&mut self, ty: &TypeExpr) -> Ty {
+        let mut errors = Vec::new();
+        let ty = Ty::from_annotation(ty, &|name| self.declared(name), &mut errors);
+        self.errors.append(&mut errors);
+
+        ty
+    }
+
+    // type of an annotation already reported where it is written, like the
+    // type of a field when it is read
+    fn resolve(&self, ty: &TypeExpr) -> Ty {
+        Ty::from_annotation(ty, &|name| self.declared(name), &mut Vec::new())
+    }
+
+    fn declared(&self, name: &str) -> Option<Ty> {
+        if self.type_def(name).is_some() {
+            return Some(Ty::Named(name.to_string()));
+        }
+
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.names.get(name))
+            .cloned()
+            .or_else(|| match self.env.get_binding_value_restrict(name) {
+                Ok(Val::Proto(_)) => Some(Ty::Any),
+                _ => None,
+            })
+    }
+
+    fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.scopes.push(Scope::default());
+        let result = f(self);
+        self.scopes.pop();
+
+        result
+    }
+
+    fn scope(&mut self) -> &mut Scope {
+        self.scopes
+            .last_mut()
+            .expect("the outermost scope is never left")
+    }
+
+    fn define(&mut self, name: &str, ty: Ty) {
+        let binding = Binding {
+            ty,
+            annotated: false,
+        };
+        self.scope().vals.insert(name.to_string(), binding);
+    }
+
+    // names not bound in the checked code may still be bound when it runs
+    fn lookup(&self, name: &str) -> Ty {
+        self.binding(name)
+            .map_or(Ty::Any, |binding| binding.ty.clone())
+    }
+
+    fn binding(&self, name: &str) -> Option<&Binding> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.vals.get(name))
+    }
+
+    fn type_def(&self, name: &str) -> Option<Rc<TypeDef>> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.types.get(name))
+            .cloned()
+            .or_else(|| match self.env.get_binding_value_restrict(name) {
+                Ok(Val::Type(ty)) => Some(ty.def),
+                _ => None,
+            })
+    }
+}
+
+// type of `target[index]`, or of the slice when the index is a range
+fn index_ty(target: Ty, index: &Ty) -> Ty {
+    match (target, index) {
+        (Ty::List(item), Ty::Range) => Ty::List(item),
+        (Ty::List(item), _) => *item,
+        (Ty::Str, Ty::Range) => Ty::Str,
+        (Ty::Str, _) => Ty::Char,
+        (Ty::Range, Ty::Range) => Ty::Range,
+        (Ty::Range, _) => Ty::Int,
+        (Ty::Map(_, val), _) => *val,
+        _ => Ty::Any,
+    }
+}
+
+// where the value of a function body comes from, the last expression of a
+// block. none when the body ends in a statement without a span
+fn value_span(body: &Statement) -> Option<&Span> {
+    match body {
+        Statement::Expression(expr) => match &expr.kind {
+            ExpressionKind::Block(block) => match block.statements.last() {
+                Some(last) => value_span(last),
+                None => Some(&expr.span),
+            },
+            _ => Some(&expr.span),
+        },
+        Statement::BindingDef(def) => Some(&def.span),
+        Statement::Assignment(assignment) => Some(&assignment.span),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(s: &str) -> Vec<String> {
+        let (parse, errors) = crate::parse_partial(s);
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        check(&parse.0, &Env::default())
+            .iter()
+            .map(Error::message)
+            .collect()
+    }
+
+    #[test]
+    fn operands() {
+        assert_eq!(
+            errors("val x = 1\nval y = x + \"a\""),
+            ["Type error: cannot apply operator `+` to int and str"]
+        );
+        assert_eq!(
+            errors("val half = 1.5\nval n = half // 2.0"),
+            ["Type error: cannot apply operator `//` to float and float"]
+        );
+        assert_eq!(
+            errors("!\"yes\""),
+            ["Type error: cannot apply unary operator `!` to str"]
+        );
+        assert_eq!(
+            errors("mut ns = [1]\nns[0] += \"a\""),
+            ["Type error: cannot apply operator `+` to int and str"]
+        );
+    }
+
+    #[test]
+    fn calls() {
+        let src = "fn add(a: int, b: int = 1): int a + b\n";
+
+        assert!(errors(&format!("{}add(1); add(1, b = 2)", src)).is_empty());
+        assert_eq!(
+            errors(&format!("{}add(1, 2, 3)", src)),
+            ["Function 'add' expects 2 arguments, got 3"]
+        );
+        assert_eq!(
+            errors(&format!("{}add(\"1\")", src)),
+            ["Type error: expected int, found str"]
+        );
+        assert_eq!(
+            errors(&format!("{}add(b = 2)", src)),
+            ["Function 'add' is missing argument 'a'"]
+        );
+        assert_eq!(errors("val n = 1\nn(2)"), ["'n' is not a function"]);
+        assert_eq!(
+            errors("fn adder(a: int) (b: int) -> a + b\nadder(1)(\"2\")"),
+            ["Type error: expected int, found str"]
+        );
+        assert_eq!(
+            errors("val ns = [1]\nns[0](2)"),
+            ["'ns[..]' is not a function"]
+        );
+    }
+
+    #[test]
+    fn returns() {
+        assert_eq!(
+            errors("fn name(n: int): str {\n  if n > 0 { return n }\n  \"none\"\n}"),
+            ["Type error: expected str, found int"]
+        );
+        assert_eq!(
+            errors("fn half(n: int): float n / 2"),
+            ["Type error: expected float, found int"]
+        );
+        assert_eq!(
+            errors("fn first(n: int): int { if n > 0 { n } }"),
+            ["Type error: expected int, found opt(int)"]
+        );
+        assert_eq!(
+            errors("fn half(n: int) n / 2\nval s: str = half(4)"),
+            ["Type error: expected str, found int"]
+        );
+    }
+
+    #[test]
+    fn annotations_and_assignments() {
+        assert_eq!(
+            errors("val xs: list(int) = [\"a\", \"b\"]"),
+            ["Type error: expected list(int), found list(str)"]
+        );
+        assert_eq!(
+            errors("mut total: int = 0\ntotal = \"zero\""),
+            ["Type error: expected int, found str"]
+        );
+        assert!(errors("mut best = none\nmut seen = []\nbest = some(1)\nseen = [2]").is_empty());
+        assert!(errors("mut x = 1\nx = \"s\"").is_empty());
+        assert!(errors("mut best = ()\nfor v in [3, 1] { best = v }").is_empty());
+    }
+
+    #[test]
+    fn patterns_and_types() {
+        assert_eq!(
+            errors(
+                "type Shape { @Circle(float) @Square(float) }
+                fn area(shape: Shape): float {
+                    match shape {
+                        @Circle(r): 3.14 * r * r
+                        @Square(side): side * 2
+                    }
+                }"
+            ),
+            ["Type error: cannot apply operator `*` to float and int"]
+        );
+        assert_eq!(
+            errors("type Point { x: int, y: int }\nval p = Point { x = 1, y = \"2\" }"),
+            ["Type error: expected int, found str"]
+        );
+    }
+
+    #[test]
+    fn unknown_names_are_left_alone() {
+        assert!(errors("print(count + 1); greet(\"Kiah\", loud = true)").is_empty());
+        assert!(errors("fn first(xs) xs[0]\nfirst([1]) + first([\"a\"])").is_empty());
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{error::Error, expression::Op, type_expr::TypeExpr};
+use std::{fmt, rc::Rc};
+
+// type of an expression as far as it is known before running. `Any` stands
+// for everything only known when running and fits every type. `Never` is the
+// type of expressions that leave instead of producing a value, like `return`,
+// and of the items of empty collections and `none`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Ty {
+    Any,
+    Never,
+    Nil,
+    Bool,
+    Int,
+    Float,
+    Str,
+    Char,
+    Range,
+    List(Box<Ty>),
+    Tuple(Vec<Ty>),
+    Opt(Box<Ty>),
+    Res(Box<Ty>, Box<Ty>),
+    Map(Box<Ty>, Box<Ty>),
+    // the signature is unknown for values annotated as just `fn`
+    Fn(Option<Rc<FnTy>>),
+    // instance of a type declared with `type`
+    Named(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FnTy {
+    pub(crate) params: Vec<ParamTy>,
+    pub(crate) ret: Ty,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParamTy {
+    pub(crate) name: Option<String>,
+    pub(crate) ty: Ty,
+    pub(crate) default: bool,
+}
+
+impl Ty {
+    // type an annotation stands for. `declared` gives the type a name
+    // declared in the program stands for, a type declared with `type`, a
+    // protocol or an associated type. unknown names and wrong numbers of
+    // arguments are pushed to `errors` and stand for any type
+    pub(crate) fn from_annotation(
+        expr: &TypeExpr,
+        declared: &dyn Fn(&str) -> Option<Ty>,
+        errors: &mut Vec<Error>,
+    ) -> Self {
+        let args: Vec<Ty> = expr
+            .args
+            .iter()
+            .map(|arg| Self::from_annotation(arg, declared, errors))
+            .collect();
+        let arg = |i: usize| Box::new(args.get(i).cloned().unwrap_or(Self::Any));
+
+        let expected = match (expr.name.as_str(), args.len()) {
+            ("nil", 0) => return Self::Nil,
+            ("bool", 0) => return Self::Bool,
+            ("int", 0) => return Self::Int,
+            ("float", 0) => return Self::Float,
+            ("str", 0) => return Self::Str,
+            ("char", 0) => return Self::Char,
+            ("range", 0) => return Self::Range,
+            ("fn", 0) => return Self::Fn(None),
+            ("list", 0 | 1) => return Self::List(arg(0)),
+            ("opt", 0 | 1) => return Self::Opt(arg(0)),
+            ("res", 0..=2) => return Self::Res(arg(0), arg(1)),
+            ("map", 0..=2) => return Self::Map(arg(0), arg(1)),
+            ("tuple", 1..) => return Self::Tuple(args.clone()),
+            ("nil" | "bool" | "int" | "float" | "str" | "char" | "range" | "fn", _) => {
+                "no arguments"
+            }
+            ("list" | "opt", _) => "at most 1 argument",
+            ("res" | "map", _) => "at most 2 arguments",
+            ("tuple", _) => "at least 1 argument",
+            (name, found) => match declared(name) {
+                Some(ty) if found == 0 => return ty,
+                Some(_) => "no arguments",
+                None => {
+                    errors.push(Error::UnknownType {
+                        name: name.to_string(),
+                        span: expr.span.clone(),
+                    });
+                    return Self::Any;
+                }
+            },
+        };
+
+        errors.push(Error::TypeArgsMismatch {
+            name: expr.name.clone(),
+            expected: expected.to_string(),
+            found: args.len(),
+            span: expr.span.clone(),
+        });
+        Self::Any
+    }
+
+    // whether a value of type `found` can be used where `self` is expected.
+    // anything fits the items of `mut xs = []`
+    pub(crate) fn fits(&self, found: &Ty) -> bool {
+        match (self, found) {
+            (Self::Any | Self::Never, _) | (_, Self::Any | Self::Never) => true,
+            (Self::List(expected), Self::List(found)) | (Self::Opt(expected), Self::Opt(found)) => {
+                expected.fits(found)
+            }
+            (Self::Res(ok, err), Self::Res(found_ok, found_err))
+            | (Self::Map(ok, err), Self::Map(found_ok, found_err)) => {
+                ok.fits(found_ok) && err.fits(found_err)
+            }
+            (Self::Tuple(expected), Self::Tuple(found)) => {
+                expected.len() == found.len()
+                    && expected
+                        .iter()
+                        .zip(found)
+                        .all(|(expected, found)| expected.fits(found))
+            }
+            (Self::Fn(_), Self::Fn(_)) => true,
+            (expected, found) => expected == found,
+        }
+    }
+
+    // type of a value that is either of `self` or `other`, like the result of
+    // an `if` with two branches. `none` and `some(1)` make an `opt(int)`
+    pub(crate) fn merge(self, other: Ty) -> Ty {
+        match (self, other) {
+            (Self::Never, ty) | (ty, Self::Never) => ty,
+            (Self::List(a), Self::List(b)) => Self::List(Box::new(a.merge(*b))),
+            (Self::Opt(a), Self::Opt(b)) => Self::Opt(Box::new(a.merge(*b))),
+            (Self::Res(a, e), Self::Res(b, f)) => {
+                Self::Res(Box::new(a.merge(*b)), Box::new(e.merge(*f)))
+            }
+            (Self::Map(a, e), Self::Map(b, f)) => {
+                Self::Map(Box::new(a.merge(*b)), Box::new(e.merge(*f)))
+            }
+            (Self::Tuple(a), Self::Tuple(b)) if a.len() == b.len() => {
+                Self::Tuple(a.into_iter().zip(b).map(|(a, b)| a.merge(b)).collect())
+            }
+            (a, b) if a == b => a,
+            _ => Self::Any,
+        }
+    }
+
+    // type of `lhs op rhs`, none when the operator doesn't work on the
+    // operands. follows the operations `Expression::eval` knows
+    pub(crate) fn operation(lhs: &Ty, rhs: &Ty, op: &Op) -> Option<Ty> {
+        let compares = matches!(
+            op,
+            Op::Eq | Op::NotEq | Op::Lt | Op::LtEq | Op::Gt | Op::GtEq
+        );
+
+        match (lhs, rhs, op) {
+            (Self::Any | Self::Never, _, _) | (_, Self::Any | Self::Never, _) => {
+                Some(if compares || matches!(op, Op::In | Op::And | Op::Or) {
+                    Self::Bool
+                } else {
+                    Self::Any
+                })
+            }
+
+            (Self::Int, Self::Int, Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Floor) => {
+                Some(Self::Int)
+            }
+            (Self::Float, Self::Float, Op::Add | Op::Sub | Op::Mul | Op::Div) => Some(Self::Float),
+            (Self::Str, Self::Str | Self::Char, Op::Add) | (Self::Char, Self::Str, Op::Add) => {
+                Some(Self::Str)
+            }
+
+            (Self::Int, Self::Int, _)
+            | (Self::Float, Self::Float, _)
+            | (Self::Char, Self::Char, _)
+                if compares =>
+            {
+                Some(Self::Bool)
+            }
+            (Self::Str, Self::Str, Op::Eq | Op::NotEq)
+            | (Self::Bool, Self::Bool, Op::Eq | Op::NotEq) => Some(Self::Bool),
+
+            (Self::Int, Self::Range, Op::In)
+            | (_, Self::List(_) | Self::Map(..), Op::In)
+            | (Self::Str | Self::Char, Self::Str, Op::In) => Some(Self::Bool),
+
+            (Self::Bool, Self::Bool, Op::And | Op::Or) => Some(Self::Bool),
+
+            _ => None,
+        }
+    }
+}
+
+// written like an annotation, `_` for types only known when running
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any | Self::Never => write!(f, "_"),
+            Self::Nil => write!(f, "nil"),
+            Self::Bool => write!(f, "bool"),
+            Self::Int => write!(f, "int"),
+            Self::Float => write!(f, "float"),
+            Self::Str => write!(f, "str"),
+            Self::Char => write!(f, "char"),
+            Self::Range => write!(f, "range"),
+            Self::List(item) => write!(f, "list({})", item),
+            Self::Tuple(items) => {
+                write!(f, "tuple(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, ")")
+            }
+            Self::Opt(item) => write!(f, "opt({})", item),
+            Self::Res(ok, err) => write!(f, "res({}, {})", ok, err),
+            Self::Map(key, val) => write!(f, "map({}, {})", key, val),
+            Self::Fn(_) => write!(f, "fn"),
+            Self::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+
+    fn annotation(s: &str) -> (Ty, Vec<String>) {
+        let declared = |name: &str| match name {
+            "Point" => Some(Ty::Named(name.to_string())),
+            "Iterator" => Some(Ty::Any),
+            _ => None,
+        };
+        let mut errors = Vec::new();
+        let ty = Ty::from_annotation(
+            &utils::parse_str(TypeExpr::new, s).unwrap(),
+            &declared,
+            &mut errors,
+        );
+
+        (ty, errors.iter().map(Error::message).collect())
+    }
+
+    #[test]
+    fn annotations() {
+        assert_eq!(
+            annotation("map(str, list(int))").0,
+            Ty::Map(Box::new(Ty::Str), Box::new(Ty::List(Box::new(Ty::Int))))
+        );
+        assert_eq!(annotation("Point").0, Ty::Named("Point".to_string()));
+        assert_eq!(annotation("Iterator").0, Ty::Any);
+        assert_eq!(annotation("list").0.to_string(), "list(_)");
+    }
+
+    #[test]
+    fn unknown_annotations() {
+        assert_eq!(
+            annotation("list(itn)"),
+            (
+                Ty::List(Box::new(Ty::Any)),
+                vec!["Unknown type 'itn'".to_string()]
+            )
+        );
+        assert_eq!(
+            annotation("list(int, str)"),
+            (
+                Ty::Any,
+                vec!["Type 'list' takes at most 1 argument, got 2".to_string()]
+            )
+        );
+        assert_eq!(
+            annotation("Point(int)").1,
+            ["Type 'Point' takes no arguments, got 1"]
+        );
+    }
+
+    #[test]
+    fn any_fits_both_ways() {
+        let ints = Ty::List(Box::new(Ty::Int));
+        let anything = Ty::List(Box::new(Ty::Any));
+
+        assert!(ints.fits(&anything));
+        assert!(anything.fits(&ints));
+        assert!(!ints.fits(&Ty::List(Box::new(Ty::Str))));
+        assert!(!Ty::Float.fits(&Ty::Int));
+    }
+
+    #[test]
+    fn merge_keeps_what_is_known() {
+        assert_eq!(
+            Ty::Opt(Box::new(Ty::Never)).merge(Ty::Opt(Box::new(Ty::Int))),
+            Ty::Opt(Box::new(Ty::Int))
+        );
+        assert_eq!(Ty::Never.merge(Ty::Str), Ty::Str);
+        assert_eq!(Ty::Int.merge(Ty::Str), Ty::Any);
+        assert_eq!(Ty::Any.merge(Ty::Int), Ty::Any);
+    }
+
+    #[test]
+    fn operations() {
+        assert_eq!(Ty::operation(&Ty::Int, &Ty::Int, &Op::Add), Some(Ty::Int));
+        assert_eq!(Ty::operation(&Ty::Str, &Ty::Char, &Op::Add), Some(Ty::Str));
+        assert_eq!(Ty::operation(&Ty::Any, &Ty::Int, &Op::Lt), Some(Ty::Bool));
+        assert_eq!(Ty::operation(&Ty::Float, &Ty::Float, &Op::Floor), None);
+        assert_eq!(Ty::operation(&Ty::Int, &Ty::Str, &Op::Add), None);
+    }
+}
@@ -1,59 +1,160 @@
+use crate::error::Error;
 use crate::interner::StringInterner;
-use crate::val::Val;
+use crate::lexer::Span;
+use crate::val::{Function, Val};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-#[derive(Debug)]
-pub struct Env<'parent> {
-    bindings: HashMap<String, Val>,
-    parent: Option<&'parent Self>,
-    interner: RefCell<StringInterner>,
+// a binding without a value was declared but has not been assigned yet
+#[derive(Debug, Clone)]
+struct Binding {
+    val: Option<Val>,
+    mutable: bool,
 }
 
-impl<'parent> Default for Env<'parent> {
-    fn default() -> Self {
-        Self {
-            bindings: HashMap::new(),
-            parent: None,
-            interner: RefCell::new(StringInterner::new()),
+// one scope. frames are reference counted so closures can keep the frame
+// they were defined in alive after the code that created it has returned.
+// bindings sit behind a `RefCell` because frames are shared, and assignments
+// update bindings owned by outer frames. methods attached by `impl` blocks
+// are kept by the kind of value they are for, then by name
+#[derive(Debug, Default)]
+struct Frame {
+    bindings: RefCell<HashMap<String, Binding>>,
+    methods: Rc<RefCell<Methods>>,
+    parent: Option<Rc<Frame>>,
+}
+
+type Methods = HashMap<String, HashMap<String, Function>>;
+
+impl Frame {
+    fn get_binding(&self, name: &str) -> Option<Binding> {
+        self.bindings
+            .borrow()
+            .get(name)
+            .cloned()
+            .or_else(|| self.parent.as_ref()?.get_binding(name))
+    }
+
+    fn get_method(&self, kind: &str, name: &str) -> Option<Function> {
+        self.methods
+            .borrow()
+            .get(kind)
+            .and_then(|methods| methods.get(name))
+            .cloned()
+            .or_else(|| self.parent.as_ref()?.get_method(kind, name))
+    }
+
+    fn assign(&self, name: &str, val: Val) -> Result<(), Error> {
+        let mut bindings = self.bindings.borrow_mut();
+
+        match bindings.get_mut(name) {
+            Some(binding) if binding.mutable || binding.val.is_none() => {
+                binding.val = Some(val);
+                Ok(())
+            }
+            Some(_) => Err(Error::AssignToImmutable {
+                name: name.to_string(),
+                span: Span::default(),
+            }),
+            None => match &self.parent {
+                Some(parent) => parent.assign(name, val),
+                None => Err(Error::UnboundName {
+                    name: name.to_string(),
+                    span: Span::default(),
+                }),
+            },
         }
     }
 }
 
-impl<'parent> Env<'parent> {
+// handle to the innermost frame of a scope chain. cloning it is cheap and the
+// clone sees the same bindings, which is how closures capture their scope.
+// all frames of one chain share the interner of the root
+#[derive(Debug, Clone, Default)]
+pub struct Env {
+    frame: Rc<Frame>,
+    interner: Rc<RefCell<StringInterner>>,
+}
+
+impl Env {
+    // immutable binding in the current frame
     pub(crate) fn store_binding(&mut self, name: String, val: Val) {
-        self.bindings.insert(name, val);
+        self.define(name, Some(val), false);
     }
 
-    pub(crate) fn get_binding_value_restrict(&self, name: &str) -> Result<Val, String> {
-        self.get_binding_value(name)
-            .ok_or_else(|| format!("binding with name '{}' does not exist", name))
+    // new binding in the current frame, shadowing any binding of the same name.
+    // a name already bound in this frame gets a new frame on top, so closures
+    // that captured the old binding keep seeing it. the new frame belongs to
+    // the same scope and shares its methods
+    pub(crate) fn define(&mut self, name: String, val: Option<Val>, mutable: bool) {
+        if self.frame.bindings.borrow().contains_key(&name) {
+            self.frame = Rc::new(Frame {
+                bindings: RefCell::default(),
+                methods: self.frame.methods.clone(),
+                parent: Some(self.frame.clone()),
+            });
+        }
+
+        self.frame
+            .bindings
+            .borrow_mut()
+            .insert(name, Binding { val, mutable });
     }
 
-    fn get_binding_value(&self, name: &str) -> Option<Val> {
-        self.bindings.get(name).cloned().or_else(|| {
-            self.parent
-                .and_then(|parent| parent.get_binding_value(name))
-        })
+    // update the nearest binding called `name`. immutable bindings can only be
+    // assigned once, when they were declared without a value
+    pub(crate) fn assign(&self, name: &str, val: Val) -> Result<(), Error> {
+        self.frame.assign(name, val)
     }
 
-    pub(crate) fn create_child(&'parent self) -> Self {
-        Self {
-            bindings: HashMap::new(),
-            parent: Some(self),
-            interner: RefCell::new(StringInterner::new()),
+    // the error has no span yet, the expression doing the lookup fills it in
+    pub(crate) fn get_binding_value_restrict(&self, name: &str) -> Result<Val, Error> {
+        match self.frame.get_binding(name) {
+            Some(Binding { val: Some(val), .. }) => Ok(val),
+            Some(Binding { val: None, .. }) => Err(Error::Uninitialized {
+                name: name.to_string(),
+                span: Span::default(),
+            }),
+            None => Err(Error::UnboundName {
+                name: name.to_string(),
+                span: Span::default(),
+            }),
         }
     }
 
-    fn get_root_interner(&self) -> &RefCell<StringInterner> {
-        match self.parent {
-            None => &self.interner,
-            Some(parent) => parent.get_root_interner(),
+    // method for values of `kind` in the current frame, replacing one of the
+    // same name
+    pub(crate) fn define_method(&mut self, kind: &str, name: String, func: Function) {
+        self.frame
+            .methods
+            .borrow_mut()
+            .entry(kind.to_string())
+            .or_default()
+            .insert(name, func);
+    }
+
+    pub(crate) fn get_method(&self, kind: &str, name: &str) -> Option<Function> {
+        self.frame.get_method(kind, name)
+    }
+
+    pub(crate) fn create_child(&self) -> Self {
+        Self {
+            frame: Rc::new(Frame {
+                bindings: RefCell::default(),
+                methods: Rc::default(),
+                parent: Some(self.frame.clone()),
+            }),
+            interner: self.interner.clone(),
         }
     }
 
+    // whether both handles point at the same frame
+    pub(crate) fn same_frame(&self, other: &Env) -> bool {
+        Rc::ptr_eq(&self.frame, &other.frame)
+    }
+
     pub(crate) fn intern(&self, s: &str) -> Rc<str> {
-        self.get_root_interner().borrow_mut().intern(s)
+        self.interner.borrow_mut().intern(s)
     }
 }
@@ -0,0 +1,616 @@
+use crate::{lexer::Span, val::Val};
+use std::fmt;
+
+// everything that can go wrong while lexing, parsing or evaluating nara code.
+// every variant carries the span of the code at fault, `Display` renders the
+// message followed by the offending source line with the span underlined
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    // the source could not be tokenized or parsed
+    Syntax {
+        message: String,
+        span: Span,
+    },
+    UnboundName {
+        name: String,
+        span: Span,
+    },
+    // binding declared without a value read before it was assigned
+    Uninitialized {
+        name: String,
+        span: Span,
+    },
+    AssignToImmutable {
+        name: String,
+        span: Span,
+    },
+    // method changing a value read from an immutable binding
+    MutateImmutable {
+        name: String,
+        span: Span,
+    },
+    // binary operator applied to values of types it does not support
+    InvalidOperands {
+        op: String,
+        lhs: String,
+        rhs: String,
+        span: Span,
+    },
+    // unary operator applied to a value of a type it does not support
+    InvalidOperand {
+        op: String,
+        operand: String,
+        span: Span,
+    },
+    TypeMismatch {
+        expected: String,
+        found: String,
+        span: Span,
+    },
+    ArityMismatch {
+        name: String,
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    // named argument matching no parameter of the function
+    UnknownParam {
+        name: String,
+        param: String,
+        span: Span,
+    },
+    // argument given by position and by name, or by name twice
+    DuplicateArg {
+        name: String,
+        param: String,
+        span: Span,
+    },
+    // parameter without a default value that no argument was given for
+    MissingArg {
+        name: String,
+        param: String,
+        span: Span,
+    },
+    NotCallable {
+        name: String,
+        span: Span,
+    },
+    NoMethod {
+        name: String,
+        ty: String,
+        span: Span,
+    },
+    NoField {
+        name: String,
+        ty: String,
+        span: Span,
+    },
+    // field without a default value left out when constructing a type
+    MissingField {
+        name: String,
+        ty: String,
+        span: Span,
+    },
+    NoVariant {
+        name: String,
+        ty: String,
+        span: Span,
+    },
+    // variant constructed with a different number of values than it declares
+    PayloadMismatch {
+        variant: String,
+        ty: String,
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    // `Type$` or `Type { ... }` on a type none of whose variants is marked `$`
+    NoDefaultVariant {
+        ty: String,
+        span: Span,
+    },
+    // `impl` leaving out a method or an associated type of its protocol.
+    // `of` is the protocol and kind, `Iterator(list)`
+    MissingMethod {
+        name: String,
+        of: String,
+        span: Span,
+    },
+    MissingAssocType {
+        name: String,
+        of: String,
+        span: Span,
+    },
+    // `impl` giving a method or type its protocol doesn't declare
+    NotInProto {
+        name: String,
+        proto: String,
+        span: Span,
+    },
+    BuiltInMethod {
+        name: String,
+        of: String,
+        span: Span,
+    },
+    DivisionByZero {
+        span: Span,
+    },
+    ZeroRangeStep {
+        span: Span,
+    },
+    // annotation naming no built-in type and nothing declared in the program
+    UnknownType {
+        name: String,
+        span: Span,
+    },
+    // annotation giving a type arguments it does not take, `expected` says
+    // how many it does, like "at most 1 argument"
+    TypeArgsMismatch {
+        name: String,
+        expected: String,
+        found: usize,
+        span: Span,
+    },
+    RangeTooLong {
+        span: Span,
+    },
+    IndexOutOfBounds {
+        index: i64,
+        len: usize,
+        span: Span,
+    },
+    MissingKey {
+        key: String,
+        span: Span,
+    },
+    DestructureMismatch {
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    UnwrapFailed {
+        val: String,
+        span: Span,
+    },
+    // no arm of a `match` (or refutable binding pattern) fits the value
+    NoMatch {
+        val: String,
+        span: Span,
+    },
+    ReturnOutsideFunction {
+        span: Span,
+    },
+    // `none` or `err` left by `value?` that no function returns
+    QuestionOutsideFunction {
+        span: Span,
+    },
+    BreakOutsideLoop {
+        span: Span,
+    },
+    ContinueOutsideLoop {
+        span: Span,
+    },
+}
+
+impl Error {
+    pub fn span(&self) -> &Span {
+        match self {
+            Self::Syntax { span, .. }
+            | Self::UnboundName { span, .. }
+            | Self::Uninitialized { span, .. }
+            | Self::AssignToImmutable { span, .. }
+            | Self::MutateImmutable { span, .. }
+            | Self::InvalidOperands { span, .. }
+            | Self::InvalidOperand { span, .. }
+            | Self::TypeMismatch { span, .. }
+            | Self::ArityMismatch { span, .. }
+            | Self::UnknownParam { span, .. }
+            | Self::DuplicateArg { span, .. }
+            | Self::MissingArg { span, .. }
+            | Self::NotCallable { span, .. }
+            | Self::NoMethod { span, .. }
+            | Self::NoField { span, .. }
+            | Self::MissingField { span, .. }
+            | Self::NoVariant { span, .. }
+            | Self::PayloadMismatch { span, .. }
+            | Self::NoDefaultVariant { span, .. }
+            | Self::MissingMethod { span, .. }
+            | Self::MissingAssocType { span, .. }
+            | Self::NotInProto { span, .. }
+            | Self::BuiltInMethod { span, .. }
+            | Self::DivisionByZero { span }
+            | Self::ZeroRangeStep { span }
+            | Self::UnknownType { span, .. }
+            | Self::TypeArgsMismatch { span, .. }
+            | Self::RangeTooLong { span }
+            | Self::IndexOutOfBounds { span, .. }
+            | Self::MissingKey { span, .. }
+            | Self::DestructureMismatch { span, .. }
+            | Self::UnwrapFailed { span, .. }
+            | Self::NoMatch { span, .. }
+            | Self::ReturnOutsideFunction { span }
+            | Self::QuestionOutsideFunction { span }
+            | Self::BreakOutsideLoop { span }
+            | Self::ContinueOutsideLoop { span } => span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Self::Syntax { span, .. }
+            | Self::UnboundName { span, .. }
+            | Self::Uninitialized { span, .. }
+            | Self::AssignToImmutable { span, .. }
+            | Self::MutateImmutable { span, .. }
+            | Self::InvalidOperands { span, .. }
+            | Self::InvalidOperand { span, .. }
+            | Self::TypeMismatch { span, .. }
+            | Self::ArityMismatch { span, .. }
+            | Self::UnknownParam { span, .. }
+            | Self::DuplicateArg { span, .. }
+            | Self::MissingArg { span, .. }
+            | Self::NotCallable { span, .. }
+            | Self::NoMethod { span, .. }
+            | Self::NoField { span, .. }
+            | Self::MissingField { span, .. }
+            | Self::NoVariant { span, .. }
+            | Self::PayloadMismatch { span, .. }
+            | Self::NoDefaultVariant { span, .. }
+            | Self::MissingMethod { span, .. }
+            | Self::MissingAssocType { span, .. }
+            | Self::NotInProto { span, .. }
+            | Self::BuiltInMethod { span, .. }
+            | Self::DivisionByZero { span }
+            | Self::ZeroRangeStep { span }
+            | Self::UnknownType { span, .. }
+            | Self::TypeArgsMismatch { span, .. }
+            | Self::RangeTooLong { span }
+            | Self::IndexOutOfBounds { span, .. }
+            | Self::MissingKey { span, .. }
+            | Self::DestructureMismatch { span, .. }
+            | Self::UnwrapFailed { span, .. }
+            | Self::NoMatch { span, .. }
+            | Self::ReturnOutsideFunction { span }
+            | Self::QuestionOutsideFunction { span }
+            | Self::BreakOutsideLoop { span }
+            | Self::ContinueOutsideLoop { span } => span,
+        }
+    }
+
+    // name of the variant as nara code sees it, the `kind` of an error bound
+    // by `catch`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Syntax { .. } => "syntax",
+            Self::UnboundName { .. } => "unbound_name",
+            Self::Uninitialized { .. } => "uninitialized",
+            Self::AssignToImmutable { .. } => "assign_to_immutable",
+            Self::MutateImmutable { .. } => "mutate_immutable",
+            Self::InvalidOperands { .. } => "invalid_operands",
+            Self::InvalidOperand { .. } => "invalid_operand",
+            Self::TypeMismatch { .. } => "type_mismatch",
+            Self::ArityMismatch { .. } => "arity_mismatch",
+            Self::UnknownParam { .. } => "unknown_param",
+            Self::DuplicateArg { .. } => "duplicate_arg",
+            Self::MissingArg { .. } => "missing_arg",
+            Self::NotCallable { .. } => "not_callable",
+            Self::NoMethod { .. } => "no_method",
+            Self::NoField { .. } => "no_field",
+            Self::MissingField { .. } => "missing_field",
+            Self::NoVariant { .. } => "no_variant",
+            Self::PayloadMismatch { .. } => "payload_mismatch",
+            Self::NoDefaultVariant { .. } => "no_default_variant",
+            Self::MissingMethod { .. } => "missing_method",
+            Self::MissingAssocType { .. } => "missing_assoc_type",
+            Self::NotInProto { .. } => "not_in_proto",
+            Self::BuiltInMethod { .. } => "built_in_method",
+            Self::DivisionByZero { .. } => "division_by_zero",
+            Self::ZeroRangeStep { .. } => "zero_range_step",
+            Self::UnknownType { .. } => "unknown_type",
+            Self::TypeArgsMismatch { .. } => "type_args_mismatch",
+            Self::RangeTooLong { .. } => "range_too_long",
+            Self::IndexOutOfBounds { .. } => "index_out_of_bounds",
+            Self::MissingKey { .. } => "missing_key",
+            Self::DestructureMismatch { .. } => "destructure_mismatch",
+            Self::UnwrapFailed { .. } => "unwrap_failed",
+            Self::NoMatch { .. } => "no_match",
+            Self::ReturnOutsideFunction { .. } => "return_outside_function",
+            Self::QuestionOutsideFunction { .. } => "question_outside_function",
+            Self::BreakOutsideLoop { .. } => "break_outside_loop",
+            Self::ContinueOutsideLoop { .. } => "continue_outside_loop",
+        }
+    }
+
+    // errors raised where no span is at hand (eg. env lookups) get the span
+    // of the closest enclosing node that has one
+    pub(crate) fn or_span(mut self, span: &Span) -> Self {
+        if self.span().is_unknown() {
+            *self.span_mut() = span.clone();
+        }
+        self
+    }
+
+    // the error without its location
+    pub fn message(&self) -> String {
+        match self {
+            Self::Syntax { message, .. } => message.clone(),
+            Self::UnboundName { name, .. } => {
+                format!("binding with name '{}' does not exist", name)
+            }
+            Self::Uninitialized { name, .. } => {
+                format!("binding '{}' is used before being initialized", name)
+            }
+            Self::AssignToImmutable { name, .. } => {
+                format!("cannot assign twice to immutable binding '{}'", name)
+            }
+            Self::MutateImmutable { name, .. } => {
+                format!("cannot mutate immutable binding '{}'", name)
+            }
+            Self::InvalidOperands { op, lhs, rhs, .. } => format!(
+                "Type error: cannot apply operator `{}` to {} and {}",
+                op, lhs, rhs
+            ),
+            Self::InvalidOperand { op, operand, .. } => format!(
+                "Type error: cannot apply unary operator `{}` to {}",
+                op, operand
+            ),
+            Self::TypeMismatch {
+                expected, found, ..
+            } => format!("Type error: expected {}, found {}", expected, found),
+            Self::ArityMismatch {
+                name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "Function '{}' expects {} argument{}, got {}",
+                name,
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                found
+            ),
+            Self::UnknownParam { name, param, .. } => {
+                format!("Function '{}' has no parameter '{}'", name, param)
+            }
+            Self::DuplicateArg { name, param, .. } => {
+                format!("Argument '{}' of function '{}' is given twice", param, name)
+            }
+            Self::MissingArg { name, param, .. } => {
+                format!("Function '{}' is missing argument '{}'", name, param)
+            }
+            Self::NotCallable { name, .. } => format!("'{}' is not a function", name),
+            Self::NoMethod { name, ty, .. } => format!("No method '{}' on type {}", name, ty),
+            Self::NoField { name, ty, .. } => format!("No field '{}' on type {}", name, ty),
+            Self::MissingField { name, ty, .. } => {
+                format!("Missing field '{}' of type {}", name, ty)
+            }
+            Self::NoVariant { name, ty, .. } => format!("No variant '@{}' on type {}", name, ty),
+            Self::PayloadMismatch {
+                variant,
+                ty,
+                expected,
+                found,
+                ..
+            } => format!(
+                "Variant '@{}' of type {} holds {} value{}, got {}",
+                variant,
+                ty,
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                found
+            ),
+            Self::NoDefaultVariant { ty, .. } => format!("Type {} has no default variant", ty),
+            Self::MissingMethod { name, of, .. } => {
+                format!("Impl {} is missing method '{}'", of, name)
+            }
+            Self::MissingAssocType { name, of, .. } => {
+                format!("Impl {} is missing type '{}'", of, name)
+            }
+            Self::NotInProto { name, proto, .. } => {
+                format!("Protocol {} has no member '{}'", proto, name)
+            }
+            Self::BuiltInMethod { name, of, .. } => {
+                format!("Impl {} cannot replace built-in method '{}'", of, name)
+            }
+            Self::DivisionByZero { .. } => "Division by zero".to_string(),
+            Self::ZeroRangeStep { .. } => "Range step cannot be zero".to_string(),
+            Self::UnknownType { name, .. } => format!("Unknown type '{}'", name),
+            Self::TypeArgsMismatch {
+                name,
+                expected,
+                found,
+                ..
+            } => format!("Type '{}' takes {}, got {}", name, expected, found),
+            Self::RangeTooLong { .. } => "Range has more values than an int can count".to_string(),
+            Self::IndexOutOfBounds { index, len, .. } => {
+                format!("Index {} is out of bounds for length {}", index, len)
+            }
+            Self::MissingKey { key, .. } => format!("Key {} not found in map", key),
+            Self::DestructureMismatch {
+                expected, found, ..
+            } => format!(
+                "Pattern expects {} item{}, got {}",
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                found
+            ),
+            Self::UnwrapFailed { val, .. } => format!("Called `unwrap` on {}", val),
+            Self::NoMatch { val, .. } => format!("No pattern matches {}", val),
+            Self::ReturnOutsideFunction { .. } => "`return` outside of a function".to_string(),
+            Self::QuestionOutsideFunction { .. } => "`?` outside of a function".to_string(),
+            Self::BreakOutsideLoop { .. } => "`break` outside of a loop".to_string(),
+            Self::ContinueOutsideLoop { .. } => "`continue` outside of a loop".to_string(),
+        }
+    }
+}
+
+// renders as
+//
+// Type error: cannot apply operator `+` to int and str
+//  --> 1:9
+//   |
+// 1 | val x = 1 + "a";
+//   |         ^^^^^^^
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())?;
+
+        let span = self.span();
+        if span.is_unknown() {
+            return Ok(());
+        }
+
+        let Some(source) = span.source() else {
+            return write!(f, " at {}:{}", span.line, span.column);
+        };
+
+        let line = source.lines().nth(span.line - 1).unwrap_or("");
+        let gutter = " ".repeat(span.line.to_string().len());
+
+        // keep tabs so the carets line up with the quoted line
+        let indent: String = line
+            .chars()
+            .take(span.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        // spans over several lines are underlined up to the end of the first
+        let rest_of_line = line.chars().count().saturating_sub(span.column - 1);
+        let spanned = source
+            .get(span.start..span.end)
+            .unwrap_or("")
+            .chars()
+            .take_while(|c| *c != '\n')
+            .count();
+        let width = spanned.min(rest_of_line).max(1);
+
+        writeln!(f)?;
+        writeln!(f, "{}--> {}:{}", gutter, span.line, span.column)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", span.line, line)?;
+        write!(f, "{} | {}{}", gutter, indent, "^".repeat(width))
+    }
+}
+
+impl std::error::Error for Error {}
+
+// why evaluation stopped before producing a value. besides errors this carries
+// control flow out of nested expressions: a `return` travels up to the call
+// of the function it leaves, `break` and `continue` up to the innermost loop
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Unwind {
+    Error(Error),
+    // returned value and the span of the `return` statement
+    Return(Val, Span),
+    // `none` or `err` left by `value?` and the span of the `?` expression.
+    // returned like a `return` unless a `try` around the `?` catches the `err`
+    Propagate(Val, Span),
+    // value the loop ends with and the span of the `break` statement
+    Break(Val, Span),
+    Continue(Span),
+}
+
+impl Unwind {
+    // see `Error::or_span`
+    pub(crate) fn or_span(self, span: &Span) -> Self {
+        match self {
+            Self::Error(err) => Self::Error(err.or_span(span)),
+            other => other,
+        }
+    }
+
+    // unwinding that escaped the construct it is meant for, to the top of a
+    // program or, for `break` and `continue`, out of a function body
+    pub(crate) fn into_error(self) -> Error {
+        match self {
+            Self::Error(err) => err,
+            Self::Return(_, span) => Error::ReturnOutsideFunction { span },
+            Self::Propagate(_, span) => Error::QuestionOutsideFunction { span },
+            Self::Break(_, span) => Error::BreakOutsideLoop { span },
+            Self::Continue(span) => Error::ContinueOutsideLoop { span },
+        }
+    }
+}
+
+impl From<Error> for Unwind {
+    fn from(err: Error) -> Self {
+        Self::Error(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_can_be_sent_between_threads() {
+        fn send_sync<T: Send + Sync>() {}
+        send_sync::<Error>();
+    }
+
+    #[test]
+    fn display_underlines_span_in_source() {
+        let err = crate::parse("val x = 1 + \"a\";")
+            .unwrap()
+            .eval(&mut crate::Env::default())
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Type error: cannot apply operator `+` to int and str\n \
+             --> 1:9\n  \
+             |\n\
+             1 | val x = 1 + \"a\";\n  \
+             |         ^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn display_points_at_the_right_line() {
+        let err = &crate::parse("val a = 1;\nval b = a +;").err().unwrap()[0];
+
+        assert_eq!(
+            err.to_string(),
+            "expected expression, found `;`\n \
+             --> 2:12\n  \
+             |\n\
+             2 | val b = a +;\n  \
+             |            ^"
+        );
+    }
+
+    #[test]
+    fn display_without_source_falls_back_to_position() {
+        let err = Error::DivisionByZero {
+            span: Span {
+                line: 3,
+                column: 7,
+                ..Span::default()
+            },
+        };
+
+        assert_eq!(err.to_string(), "Division by zero at 3:7");
+    }
+
+    #[test]
+    fn or_span_only_fills_unknown_spans() {
+        let span = Span {
+            line: 1,
+            column: 1,
+            ..Span::default()
+        };
+        let other = Span {
+            line: 2,
+            column: 5,
+            ..Span::default()
+        };
+
+        let err = Error::UnboundName {
+            name: "x".to_string(),
+            span: Span::default(),
+        }
+        .or_span(&span);
+        assert_eq!(err.span(), &span);
+        assert_eq!(err.or_span(&other).span(), &span);
+    }
+}
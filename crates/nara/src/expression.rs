@@ -1,20 +1,42 @@
 mod binding_usage;
 mod block;
-
-use crate::{env::Env, utils, val::Val};
+mod call;
+mod construct;
+pub(crate) mod index;
+mod lambda;
+mod match_expr;
+mod method;
+mod try_catch;
+
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    lexer::{FStringSegment, Span, Token, TokenKind},
+    pattern::Pattern,
+    utils,
+    val::{Map, Range, Val},
+};
 pub(crate) use binding_usage::BindingUsage;
 pub(crate) use block::Block;
+pub(crate) use call::Call;
+pub(crate) use construct::Construct;
+pub(crate) use index::Index;
+pub(crate) use lambda::Lambda;
+pub(crate) use match_expr::Match;
+pub(crate) use method::{Field, MethodCall, is_built_in};
+use std::{fmt, ops::ControlFlow};
+pub(crate) use try_catch::TryCatch;
 
 // Number struct that holds 64-bit integer
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) struct Number(pub i64);
 
 impl Number {
-    fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
-        let (s, number) = utils::extract_digits(s)?;
-
-        Ok((s, Self(number.parse().unwrap())))
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        match utils::peek(tokens) {
+            TokenKind::Number(n) => Ok((&tokens[1..], Self(*n))),
+            _ => Err(utils::expected("number", tokens)),
+        }
     }
 }
 
@@ -23,12 +45,12 @@ impl Number {
 pub(crate) struct BoolLiteral(pub bool);
 
 impl BoolLiteral {
-    fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
-
-        utils::tag("true", s)
-            .map(|s| (s, Self(true)))
-            .or_else(|_| utils::tag("false", s).map(|s| (s, Self(false))))
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        match utils::peek(tokens) {
+            TokenKind::True => Ok((&tokens[1..], Self(true))),
+            TokenKind::False => Ok((&tokens[1..], Self(false))),
+            _ => Err(utils::expected("boolean", tokens)),
+        }
     }
 }
 
@@ -37,11 +59,11 @@ impl BoolLiteral {
 pub(crate) struct Float(pub f64);
 
 impl Float {
-    fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
-        let (s, float) = utils::extract_float(s)?;
-
-        Ok((s, Self(float.parse().unwrap())))
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        match utils::peek(tokens) {
+            TokenKind::Float(n) => Ok((&tokens[1..], Self(*n))),
+            _ => Err(utils::expected("float", tokens)),
+        }
     }
 }
 
@@ -50,15 +72,33 @@ impl Float {
 pub(crate) struct StringLiteral(pub String);
 
 impl StringLiteral {
-    fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
-        let (s, string_content) = utils::extract_string_literal(s)?;
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        match utils::peek(tokens) {
+            TokenKind::String(s) => Ok((&tokens[1..], Self(s.clone()))),
+            _ => Err(utils::expected("string", tokens)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct CharLiteral(pub(crate) char);
 
-        Ok((s, Self(string_content)))
+impl CharLiteral {
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        match utils::peek(tokens) {
+            TokenKind::Char(c) => Ok((&tokens[1..], Self(*c))),
+            _ => Err(utils::expected("character", tokens)),
+        }
     }
 }
 
-// operator enum with methods to create an operator from a string
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum FStringPart {
+    Text(String),
+    Interpolation(Expression),
+}
+
+// operator enum with methods to create an operator from a token
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) enum Op {
     // Arithmetic
@@ -74,35 +114,164 @@ pub(crate) enum Op {
     LtEq,
     Gt,
     GtEq,
+    // Membership
+    In,
     // Logical
     And,
     Or,
 }
 
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Floor => "//",
+            Self::Eq => "==",
+            Self::NotEq => "!=",
+            Self::Lt => "<",
+            Self::LtEq => "<=",
+            Self::Gt => ">",
+            Self::GtEq => ">=",
+            Self::In => "in",
+            Self::And => "&&",
+            Self::Or => "||",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
 impl Op {
-    fn new(s: &str) -> Result<(&str, Self), String> {
-        // Try multi-character operators first
-        utils::tag("==", s)
-            .map(|s| (s, Self::Eq))
-            .or_else(|_| utils::tag("!=", s).map(|s| (s, Self::NotEq)))
-            .or_else(|_| utils::tag("<=", s).map(|s| (s, Self::LtEq)))
-            .or_else(|_| utils::tag(">=", s).map(|s| (s, Self::GtEq)))
-            .or_else(|_| utils::tag("&&", s).map(|s| (s, Self::And)))
-            .or_else(|_| utils::tag("||", s).map(|s| (s, Self::Or)))
-            .or_else(|_| utils::tag("//", s).map(|s| (s, Self::Floor)))
-            // Then single-character operators
-            .or_else(|_| utils::tag("+", s).map(|s| (s, Self::Add)))
-            .or_else(|_| utils::tag("-", s).map(|s| (s, Self::Sub)))
-            .or_else(|_| utils::tag("*", s).map(|s| (s, Self::Mul)))
-            .or_else(|_| utils::tag("/", s).map(|s| (s, Self::Div)))
-            .or_else(|_| utils::tag("<", s).map(|s| (s, Self::Lt)))
-            .or_else(|_| utils::tag(">", s).map(|s| (s, Self::Gt)))
-            .map_err(|_| {
-                format!(
-                    "Expected an operator, but found '{}'",
-                    s.chars().take(10).collect::<String>()
-                )
-            })
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let op = match utils::peek(tokens) {
+            TokenKind::Plus => Self::Add,
+            TokenKind::Minus => Self::Sub,
+            TokenKind::Star => Self::Mul,
+            TokenKind::Slash => Self::Div,
+            TokenKind::DoubleSlash => Self::Floor,
+            TokenKind::EqEq => Self::Eq,
+            TokenKind::NotEq => Self::NotEq,
+            TokenKind::Lt => Self::Lt,
+            TokenKind::LtEq => Self::LtEq,
+            TokenKind::Gt => Self::Gt,
+            TokenKind::GtEq => Self::GtEq,
+            TokenKind::In => Self::In,
+            TokenKind::AndAnd => Self::And,
+            TokenKind::OrOr => Self::Or,
+            _ => return Err(utils::expected("an operator", tokens)),
+        };
+
+        Ok((&tokens[1..], op))
+    }
+
+    // binding power of the operator, higher binds tighter
+    // all binary operators are left associative. ranges sit between
+    // comparisons and arithmetic, see `RANGE_PRECEDENCE`
+    pub(crate) fn precedence(&self) -> u8 {
+        match self {
+            Self::Or => 1,
+            Self::And => 2,
+            Self::Eq | Self::NotEq => 3,
+            Self::Lt | Self::LtEq | Self::Gt | Self::GtEq | Self::In => 4,
+            Self::Add | Self::Sub => 6,
+            Self::Mul | Self::Div | Self::Floor => 7,
+        }
+    }
+
+    // result of the operator on evaluated operands, `span` is the whole
+    // operation
+    pub(crate) fn apply(&self, lhs: Val, rhs: Val, env: &Env, span: &Span) -> Result<Val, Error> {
+        match (lhs, rhs, self) {
+            // Arithmetic: Number operations
+            (Val::Number(l), Val::Number(r), Op::Add) => Ok(Val::Number(l + r)),
+            (Val::Number(l), Val::Number(r), Op::Sub) => Ok(Val::Number(l - r)),
+            (Val::Number(l), Val::Number(r), Op::Mul) => Ok(Val::Number(l * r)),
+            (Val::Number(l), Val::Number(r), Op::Div) => {
+                if r == 0 {
+                    Err(Error::DivisionByZero { span: span.clone() })
+                } else {
+                    Ok(Val::Number(l / r))
+                }
+            }
+            (Val::Number(l), Val::Number(r), Op::Floor) => {
+                if r == 0 {
+                    Err(Error::DivisionByZero { span: span.clone() })
+                } else {
+                    Ok(Val::Number(l / r))
+                }
+            }
+
+            // Arithmetic: Float operations
+            (Val::Float(l), Val::Float(r), Op::Add) => Ok(Val::Float(l + r)),
+            (Val::Float(l), Val::Float(r), Op::Sub) => Ok(Val::Float(l - r)),
+            (Val::Float(l), Val::Float(r), Op::Mul) => Ok(Val::Float(l * r)),
+            (Val::Float(l), Val::Float(r), Op::Div) => Ok(Val::Float(l / r)),
+
+            // String concatenation
+            (Val::String(l), Val::String(r), Op::Add) => {
+                let concatenated = format!("{}{}", l, r);
+                Ok(Val::String(env.intern(&concatenated)))
+            }
+            (Val::String(l), Val::Char(r), Op::Add) => {
+                Ok(Val::String(env.intern(&format!("{}{}", l, r))))
+            }
+            (Val::Char(l), Val::String(r), Op::Add) => {
+                Ok(Val::String(env.intern(&format!("{}{}", l, r))))
+            }
+
+            // Comparison: Numbers
+            (Val::Number(l), Val::Number(r), Op::Eq) => Ok(Val::Bool(l == r)),
+            (Val::Number(l), Val::Number(r), Op::NotEq) => Ok(Val::Bool(l != r)),
+            (Val::Number(l), Val::Number(r), Op::Lt) => Ok(Val::Bool(l < r)),
+            (Val::Number(l), Val::Number(r), Op::LtEq) => Ok(Val::Bool(l <= r)),
+            (Val::Number(l), Val::Number(r), Op::Gt) => Ok(Val::Bool(l > r)),
+            (Val::Number(l), Val::Number(r), Op::GtEq) => Ok(Val::Bool(l >= r)),
+
+            // Comparison: Floats
+            (Val::Float(l), Val::Float(r), Op::Eq) => Ok(Val::Bool(l == r)),
+            (Val::Float(l), Val::Float(r), Op::NotEq) => Ok(Val::Bool(l != r)),
+            (Val::Float(l), Val::Float(r), Op::Lt) => Ok(Val::Bool(l < r)),
+            (Val::Float(l), Val::Float(r), Op::LtEq) => Ok(Val::Bool(l <= r)),
+            (Val::Float(l), Val::Float(r), Op::Gt) => Ok(Val::Bool(l > r)),
+            (Val::Float(l), Val::Float(r), Op::GtEq) => Ok(Val::Bool(l >= r)),
+
+            // Comparison: Strings
+            (Val::String(l), Val::String(r), Op::Eq) => Ok(Val::Bool(l == r)),
+            (Val::String(l), Val::String(r), Op::NotEq) => Ok(Val::Bool(l != r)),
+
+            // Comparison: Chars, ordered by code point
+            (Val::Char(l), Val::Char(r), Op::Eq) => Ok(Val::Bool(l == r)),
+            (Val::Char(l), Val::Char(r), Op::NotEq) => Ok(Val::Bool(l != r)),
+            (Val::Char(l), Val::Char(r), Op::Lt) => Ok(Val::Bool(l < r)),
+            (Val::Char(l), Val::Char(r), Op::LtEq) => Ok(Val::Bool(l <= r)),
+            (Val::Char(l), Val::Char(r), Op::Gt) => Ok(Val::Bool(l > r)),
+            (Val::Char(l), Val::Char(r), Op::GtEq) => Ok(Val::Bool(l >= r)),
+
+            // Comparison: Bools
+            (Val::Bool(l), Val::Bool(r), Op::Eq) => Ok(Val::Bool(l == r)),
+            (Val::Bool(l), Val::Bool(r), Op::NotEq) => Ok(Val::Bool(l != r)),
+
+            // Membership
+            (Val::Number(n), Val::Range(range), Op::In) => Ok(Val::Bool(range.contains(n))),
+            (item, Val::List(items), Op::In) => Ok(Val::Bool(items.contains(&item))),
+            (key, Val::Map(map), Op::In) => Ok(Val::Bool(map.contains_key(&key))),
+            (Val::String(l), Val::String(r), Op::In) => Ok(Val::Bool(r.contains(&*l))),
+            (Val::Char(c), Val::String(s), Op::In) => Ok(Val::Bool(s.contains(c))),
+
+            // Logical operators
+            (Val::Bool(l), Val::Bool(r), Op::And) => Ok(Val::Bool(l && r)),
+            (Val::Bool(l), Val::Bool(r), Op::Or) => Ok(Val::Bool(l || r)),
+
+            // Type errors
+            (l, r, op) => Err(Error::InvalidOperands {
+                op: op.to_string(),
+                lhs: l.type_name().to_string(),
+                rhs: r.type_name().to_string(),
+                span: span.clone(),
+            }),
+        }
     }
 }
 
@@ -112,6 +281,17 @@ pub(crate) enum UnaryOp {
     Neg,
 }
 
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Not => write!(f, "!"),
+            Self::Neg => write!(f, "-"),
+        }
+    }
+}
+
+// `if c { a } else { b }`, or inline `if c ? a : b`. without an else it
+// evaluates to `some(a)` or `none`
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) struct IfExpr {
     pub(crate) condition: Box<Expression>,
@@ -119,10 +299,23 @@ pub(crate) struct IfExpr {
     pub(crate) else_branch: Option<Box<Expression>>,
 }
 
+// `name(args)`, arguments by position come before the ones given by name,
+// `join(parts, sep = ", ")`
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) struct FunctionCall {
     pub(crate) name: String,
     pub(crate) args: Vec<Expression>,
+    pub(crate) named: Vec<NamedArg>,
+}
+
+// arguments of a call, by position and by name
+pub(crate) type CallArgs = (Vec<Expression>, Vec<NamedArg>);
+
+// `name = value` in the arguments of a call
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct NamedArg {
+    pub(crate) name: String,
+    pub(crate) val: Expression,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -131,100 +324,288 @@ pub(crate) struct WhileLoop {
     pub(crate) body: Box<Expression>,
 }
 
+// `loop(expr)` or `loop { ... }`, runs until a `break`
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Loop {
+    pub(crate) body: Box<Expression>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) struct ForLoop {
-    pub(crate) var: String,
+    pub(crate) var: Pattern,
     pub(crate) iterable: Box<Expression>,
     pub(crate) body: Box<Expression>,
 }
 
 impl ForLoop {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
-        let s = utils::tag("for", s)?;
-        let (s, _) = utils::extract_whitespace_restrict(s)?;
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::For, tokens)?;
 
-        // Parse variable name
-        let (s, var) = utils::extract_ident(s)?;
-        let (s, _) = utils::extract_whitespace(s);
+        // Parse variable name, or a pattern taking each item apart
+        let (tokens, var) = Pattern::new(tokens)?;
 
         // Parse 'in' keyword
-        let s = utils::tag("in", s)?;
-        let (s, _) = utils::extract_whitespace_restrict(s)?;
+        let tokens = utils::tag(&TokenKind::In, tokens)?;
 
         // Parse iterable
-        let (s, iterable) = Expression::new_operand(s)?;
-        let (s, _) = utils::extract_whitespace(s);
+        let (tokens, iterable) = Expression::new_condition(tokens)?;
 
         // Parse body (must be a block)
-        let (s, body_block) = Block::new(s)?;
-        let body = Box::new(Expression::Block(body_block));
+        let (tokens, body) = Expression::new_block(tokens)?;
 
         Ok((
-            s,
+            tokens,
             Self {
-                var: var.to_string(),
+                var,
                 iterable: Box::new(iterable),
-                body,
+                body: Box::new(body),
             },
         ))
     }
 }
 
+// `x in 0..n` is `x in (0..n)`, `0..n + 1` is `0..(n + 1)`
+const RANGE_PRECEDENCE: u8 = 5;
+
+// `start..end` or `start..=end`, optionally counting by `step n`. `step` is
+// only a keyword right after the end of a range
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct RangeExpr {
+    pub(crate) start: Box<Expression>,
+    pub(crate) end: Box<Expression>,
+    pub(crate) step: Option<Box<Expression>>,
+    pub(crate) inclusive: bool,
+}
+
+impl RangeExpr {
+    // `tokens` start at the `..` following `start`
+    fn new(
+        start: Expression,
+        tokens: &[Token],
+        field_lists: bool,
+    ) -> Result<(&[Token], Self), Error> {
+        let inclusive = match utils::peek(tokens) {
+            TokenKind::DotDot => false,
+            TokenKind::DotDotEq => true,
+            _ => return Err(utils::expected("`..` or `..=`", tokens)),
+        };
+
+        let (tokens, end) =
+            Expression::new_operation(&tokens[1..], RANGE_PRECEDENCE + 1, field_lists)?;
+
+        let (tokens, step) = match utils::peek(tokens) {
+            TokenKind::Ident(name) if name == "step" => {
+                let (tokens, step) =
+                    Expression::new_operation(&tokens[1..], RANGE_PRECEDENCE + 1, field_lists)?;
+                (tokens, Some(Box::new(step)))
+            }
+            _ => (tokens, None),
+        };
+
+        Ok((
+            tokens,
+            Self {
+                start: Box::new(start),
+                end: Box::new(end),
+                step,
+                inclusive,
+            },
+        ))
+    }
+
+    fn eval(&self, env: &Env) -> Result<Val, Unwind> {
+        let int = |expr: &Expression| match expr.eval(env)? {
+            Val::Number(n) => Ok(n),
+            other => Err(Unwind::from(expected_int(&other, expr))),
+        };
+
+        let start = int(&self.start)?;
+        let end = int(&self.end)?;
+        let step = match &self.step {
+            Some(step_expr) => match int(step_expr)? {
+                0 => {
+                    return Err(Error::ZeroRangeStep {
+                        span: step_expr.span.clone(),
+                    }
+                    .into());
+                }
+                step => step,
+            },
+            None => 1,
+        };
+
+        Ok(Val::Range(Range::new(start, end, step, self.inclusive)))
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) struct ListLiteral {
     pub(crate) elements: Vec<Expression>,
 }
 
-impl ListLiteral {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
+// `$(a, b)`, trailing comma allowed
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct TupleLiteral {
+    pub(crate) elements: Vec<Expression>,
+}
+
+impl TupleLiteral {
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::Dollar, tokens)?;
+        let mut tokens = utils::tag(&TokenKind::LParen, tokens)?;
+        let mut elements = Vec::new();
+
+        loop {
+            if let Ok(rest) = utils::tag(&TokenKind::RParen, tokens) {
+                return Ok((rest, Self { elements }));
+            }
+
+            let (rest, element) = Expression::new(tokens)?;
+            elements.push(element);
 
-        if !s.starts_with('[') {
-            return Err("expected '[' for list literal".to_string());
+            match utils::peek(rest) {
+                TokenKind::Comma => tokens = &rest[1..],
+                TokenKind::RParen => return Ok((&rest[1..], Self { elements })),
+                _ => return Err(utils::expected("',' or ')' in tuple", rest)),
+            }
         }
+    }
+}
 
-        let s = &s[1..];
-        let mut elements = Vec::new();
-        let mut remaining = s;
+// `[k: v, ...]`, with `[:]` for the empty map
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct MapLiteral {
+    pub(crate) entries: Vec<(Expression, Expression)>,
+}
+
+// `[` starts a list or a map, which one is only known after the first element
+fn new_collection(tokens: &[Token]) -> Result<(&[Token], ExpressionKind), Error> {
+    let tokens = utils::tag(&TokenKind::LBracket, tokens)?;
+
+    if let Ok(rest) = utils::tag(&TokenKind::Colon, tokens) {
+        let rest = utils::tag(&TokenKind::RBracket, rest)?;
+        let map = MapLiteral {
+            entries: Vec::new(),
+        };
+        return Ok((rest, ExpressionKind::Map(map)));
+    }
+    if let Ok(rest) = utils::tag(&TokenKind::RBracket, tokens) {
+        let list = ListLiteral {
+            elements: Vec::new(),
+        };
+        return Ok((rest, ExpressionKind::List(list)));
+    }
+
+    let (tokens, first) = Expression::new(tokens)?;
+    if let Ok(tokens) = utils::tag(&TokenKind::Colon, tokens) {
+        MapLiteral::new(first, tokens).map(|(t, map)| (t, ExpressionKind::Map(map)))
+    } else {
+        ListLiteral::new(first, tokens).map(|(t, list)| (t, ExpressionKind::List(list)))
+    }
+}
+
+impl ListLiteral {
+    // `tokens` start right after the first element
+    fn new(first: Expression, mut tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let mut elements = vec![first];
 
         loop {
-            let (rest, _) = utils::extract_whitespace(remaining);
+            // Check for comma or closing bracket
+            match utils::peek(tokens) {
+                TokenKind::Comma => tokens = &tokens[1..],
+                TokenKind::RBracket => return Ok((&tokens[1..], Self { elements })),
+                _ => return Err(utils::expected("',' or ']' in list literal", tokens)),
+            }
 
-            // Check for closing bracket
-            if let Some(stripped) = rest.strip_prefix(']') {
-                return Ok((stripped, Self { elements }));
+            // a trailing comma is allowed
+            if let Ok(rest) = utils::tag(&TokenKind::RBracket, tokens) {
+                return Ok((rest, Self { elements }));
             }
 
-            // Parse element
-            let (rest, element) = Expression::new(rest)?;
+            let (rest, element) = Expression::new(tokens)?;
             elements.push(element);
+            tokens = rest;
+        }
+    }
+}
 
-            let (rest, _) = utils::extract_whitespace(rest);
+impl MapLiteral {
+    // `tokens` start right after the first key's `:`
+    fn new(first: Expression, mut tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let mut entries = Vec::new();
+        let mut key = first;
 
-            // Check for comma or closing bracket
-            if let Some(stripped) = rest.strip_prefix(',') {
-                remaining = stripped;
-            } else if let Some(stripped) = rest.strip_prefix(']') {
-                return Ok((stripped, Self { elements }));
-            } else {
-                return Err(format!(
-                    "expected ',' or ']' in list literal, got '{}'",
-                    rest.chars().take(10).collect::<String>()
-                ));
+        loop {
+            let (rest, val) = Expression::new(tokens)?;
+            entries.push((key, val));
+
+            match utils::peek(rest) {
+                TokenKind::Comma => tokens = &rest[1..],
+                TokenKind::RBracket => return Ok((&rest[1..], Self { entries })),
+                _ => return Err(utils::expected("',' or ']' in map literal", rest)),
+            }
+
+            if let Ok(rest) = utils::tag(&TokenKind::RBracket, tokens) {
+                return Ok((rest, Self { entries }));
             }
+
+            let (rest, next) = Expression::new(tokens)?;
+            tokens = utils::tag(&TokenKind::Colon, rest)?;
+            key = next;
+        }
+    }
+
+    fn eval(&self, env: &Env) -> Result<Val, Unwind> {
+        let mut map = Map::default();
+
+        for (key_expr, val_expr) in &self.entries {
+            let key = key_expr.eval(env)?;
+            let val = val_expr.eval(env)?;
+
+            map.insert(key, val)
+                .map_err(|key| index::key_mismatch(&key, &key_expr.span))?;
+        }
+
+        Ok(Val::Map(map))
+    }
+}
+
+// every expression knows where it came from in the source
+#[derive(Debug, Clone)]
+pub(crate) struct Expression {
+    pub(crate) kind: ExpressionKind,
+    pub(crate) span: Span,
+}
+
+// equality is structural, spans are ignored so that trees built by hand
+// compare equal to parsed ones
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
+
+// expression without a position in the source, used for trees built in code
+impl From<ExpressionKind> for Expression {
+    fn from(kind: ExpressionKind) -> Self {
+        Self {
+            kind,
+            span: Span::default(),
         }
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
-pub(crate) enum Expression {
+pub(crate) enum ExpressionKind {
     Number(Number),
     Float(Float),
     String(StringLiteral),
+    Char(CharLiteral),
     Bool(BoolLiteral),
-    FString(Vec<utils::FStringPart>),
+    FString(Vec<FStringPart>),
     List(ListLiteral),
+    Tuple(TupleLiteral),
+    Map(MapLiteral),
     Operation {
         lhs: Box<Expression>,
         rhs: Box<Expression>,
@@ -236,49 +617,88 @@ pub(crate) enum Expression {
     },
     If(IfExpr),
     While(WhileLoop),
+    Loop(Loop),
     For(ForLoop),
+    TryCatch(TryCatch),
+    Match(Match),
+    Construct(Construct),
     FunctionCall(FunctionCall),
+    Call(Call),
+    Lambda(Lambda),
+    Range(RangeExpr),
+    Index(Index),
+    MethodCall(MethodCall),
+    Field(Field),
     BindingUsage(BindingUsage),
     Block(Block),
+    Group(Box<Expression>),
+    // `value?`, the item of `some` or `ok`, otherwise returns the `none` or
+    // `err` from the enclosing function. an enclosing `try` catches the `err`
+    Try(Box<Expression>),
+    None,
+    Unit,
 }
 
 impl IfExpr {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
-        let s = utils::tag("if", s)?;
-        let (s, _) = utils::extract_whitespace_restrict(s)?;
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::If, tokens)?;
 
-        // Parse condition (any expression that's not a block)
-        let (s, condition) = Expression::new_operand(s)?;
-        let (s, _) = utils::extract_whitespace(s);
+        // Parse condition, the opening brace of the body or `?` ends it
+        let (tokens, condition) = Expression::new_condition(tokens)?;
 
-        // Parse then branch (must be a block)
-        let (s, then_block) = Block::new(s)?;
-        let then_branch = Box::new(Expression::Block(then_block));
+        if let Ok(tokens) = utils::tag(&TokenKind::Question, tokens) {
+            return Self::new_inline(condition, tokens);
+        }
 
-        let (s, _) = utils::extract_whitespace(s);
+        // Parse then branch (must be a block)
+        let (tokens, then_branch) = Expression::new_block(tokens)?;
 
         // Check for else branch
-        let (s, else_branch) = if let Ok(s) = utils::tag("else", s) {
-            let (s, _) = utils::extract_whitespace(s);
-
+        let (tokens, else_branch) = if let Ok(rest) = utils::tag(&TokenKind::Else, tokens) {
             // Check if it's another if (else if)
-            if let Ok((s, if_expr)) = Self::new(s) {
-                (s, Some(Box::new(Expression::If(if_expr))))
+            if let TokenKind::If = utils::peek(rest) {
+                let (after, if_expr) = Self::new(rest)?;
+                let else_if = Expression {
+                    kind: ExpressionKind::If(if_expr),
+                    span: utils::consumed_span(rest, after),
+                };
+                (after, Some(Box::new(else_if)))
             } else {
                 // Otherwise it should be a block
-                let (s, else_block) = Block::new(s)?;
-                (s, Some(Box::new(Expression::Block(else_block))))
+                let (after, else_block) = Expression::new_block(rest)?;
+                (after, Some(Box::new(else_block)))
             }
         } else {
-            (s, None)
+            (tokens, None)
         };
 
         Ok((
-            s,
+            tokens,
             Self {
                 condition: Box::new(condition),
-                then_branch,
+                then_branch: Box::new(then_branch),
+                else_branch,
+            },
+        ))
+    }
+
+    // the branches after `?`, `a : b` or just `a`
+    fn new_inline(condition: Expression, tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (tokens, then_branch) = Expression::new(tokens)?;
+
+        let (tokens, else_branch) = match utils::tag(&TokenKind::Colon, tokens) {
+            Ok(tokens) => {
+                let (tokens, else_branch) = Expression::new(tokens)?;
+                (tokens, Some(Box::new(else_branch)))
+            }
+            Err(_) => (tokens, None),
+        };
+
+        Ok((
+            tokens,
+            Self {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
                 else_branch,
             },
         ))
@@ -286,179 +706,426 @@ impl IfExpr {
 }
 
 impl FunctionCall {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
-        let (s, name) = utils::extract_ident(s)?;
-        let (s, _) = utils::extract_whitespace(s);
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (tokens, name) = utils::extract_ident(tokens)?;
+        let (tokens, (args, named)) = Self::new_call_args(tokens)?;
 
-        // Must have parentheses for function call
-        if !s.starts_with('(') {
-            return Err("expected '(' for function call".to_string());
-        }
+        Ok((tokens, Self { name, args, named }))
+    }
 
-        let (s, args_str) = utils::extract_paranthesis(s)?;
+    // arguments of a function or method call, positional ones followed by
+    // `name = value` ones
+    pub(super) fn new_call_args(tokens: &[Token]) -> Result<(&[Token], CallArgs), Error> {
+        let mut tokens = utils::tag(&TokenKind::LParen, tokens)?;
+        let mut args = Vec::new();
+        let mut named: Vec<NamedArg> = Vec::new();
 
-        // Parse comma-separated arguments
-        let args = if args_str.trim().is_empty() {
-            Vec::new()
-        } else {
-            let mut args = Vec::new();
-            let mut remaining = args_str;
-
-            loop {
-                let (rest, arg) = Expression::new(remaining)?;
-                args.push(arg);
-
-                let (rest, _) = utils::extract_whitespace(rest);
-                if let Some(stripped) = rest.strip_prefix(',') {
-                    remaining = stripped;
-                    let (rest, _) = utils::extract_whitespace(remaining);
-                    remaining = rest;
-                } else if rest.is_empty() {
-                    break;
-                } else {
-                    return Err(format!("expected ',' or end of arguments, got '{}'", rest));
+        let tokens = loop {
+            if let Ok(rest) = utils::tag(&TokenKind::RParen, tokens) {
+                break rest;
+            }
+
+            let rest = match utils::peek(tokens) {
+                TokenKind::Ident(arg) if tokens[1].kind == TokenKind::Assign => {
+                    if named.iter().any(|named| named.name == *arg) {
+                        return Err(Error::Syntax {
+                            message: format!("argument `{}` is given twice", arg),
+                            span: tokens[0].span.clone(),
+                        });
+                    }
+
+                    let (rest, val) = Expression::new(&tokens[2..])?;
+                    named.push(NamedArg {
+                        name: arg.clone(),
+                        val,
+                    });
+                    rest
+                }
+                _ if !named.is_empty() => {
+                    return Err(Error::Syntax {
+                        message: "positional argument after named arguments".to_string(),
+                        span: tokens[0].span.clone(),
+                    });
+                }
+                _ => {
+                    let (rest, arg) = Expression::new(tokens)?;
+                    args.push(arg);
+                    rest
                 }
+            };
+
+            match utils::peek(rest) {
+                TokenKind::Comma => tokens = &rest[1..],
+                TokenKind::RParen => break &rest[1..],
+                _ => return Err(utils::expected("',' or ')' in arguments", rest)),
+            }
+        };
+
+        Ok((tokens, (args, named)))
+    }
+
+    // parenthesized, comma separated arguments without names, like the
+    // payload of a variant
+    pub(super) fn new_args(tokens: &[Token]) -> Result<(&[Token], Vec<Expression>), Error> {
+        // Must have parentheses for function call
+        let mut tokens = utils::tag(&TokenKind::LParen, tokens)?;
+        let mut args = Vec::new();
+
+        // Parse comma-separated arguments
+        let tokens = loop {
+            if let Ok(rest) = utils::tag(&TokenKind::RParen, tokens) {
+                break rest;
             }
 
-            args
+            let (rest, arg) = Expression::new(tokens)?;
+            args.push(arg);
+
+            match utils::peek(rest) {
+                TokenKind::Comma => tokens = &rest[1..],
+                TokenKind::RParen => break &rest[1..],
+                _ => return Err(utils::expected("',' or ')' in arguments", rest)),
+            }
         };
 
-        Ok((
-            s,
-            Self {
-                name: name.to_string(),
-                args,
-            },
-        ))
+        Ok((tokens, args))
     }
 }
 
 impl WhileLoop {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
-        let s = utils::tag("while", s)?;
-        let (s, _) = utils::extract_whitespace_restrict(s)?;
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::While, tokens)?;
 
         // Parse condition
-        let (s, condition) = Expression::new_operand(s)?;
-        let (s, _) = utils::extract_whitespace(s);
+        let (tokens, condition) = Expression::new_condition(tokens)?;
 
         // Parse body (must be a block)
-        let (s, body_block) = Block::new(s)?;
-        let body = Box::new(Expression::Block(body_block));
+        let (tokens, body) = Expression::new_block(tokens)?;
 
         Ok((
-            s,
+            tokens,
             Self {
                 condition: Box::new(condition),
-                body,
+                body: Box::new(body),
+            },
+        ))
+    }
+}
+
+impl Loop {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::Loop, tokens)?;
+
+        // the body is a block, or any expression in parentheses
+        let (tokens, body) = match utils::tag(&TokenKind::LParen, tokens) {
+            Ok(tokens) => {
+                let (tokens, body) = Expression::new(tokens)?;
+                (utils::tag(&TokenKind::RParen, tokens)?, body)
+            }
+            Err(_) => Expression::new_block(tokens)?,
+        };
+
+        Ok((
+            tokens,
+            Self {
+                body: Box::new(body),
             },
         ))
     }
 }
 
 impl Expression {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        Self::new_operation(s)
-            .or_else(|_| Self::new_unary_op(s))
-            .or_else(|_| IfExpr::new(s).map(|(s, if_expr)| (s, Self::If(if_expr))))
-            .or_else(|_| WhileLoop::new(s).map(|(s, while_loop)| (s, Self::While(while_loop))))
-            .or_else(|_| ForLoop::new(s).map(|(s, for_loop)| (s, Self::For(for_loop))))
-            .or_else(|_| FunctionCall::new(s).map(|(s, call)| (s, Self::FunctionCall(call))))
-            .or_else(|_| ListLiteral::new(s).map(|(s, list)| (s, Self::List(list))))
-            .or_else(|_| Self::new_bool(s))
-            .or_else(|_| Self::new_float(s))
-            .or_else(|_| Self::new_number(s))
-            .or_else(|_| Self::new_fstring(s))
-            .or_else(|_| Self::new_string(s))
-            .or_else(|_| {
-                BindingUsage::new(s)
-                    .map(|(s, binding_usage)| (s, Self::BindingUsage(binding_usage)))
-            })
-            .or_else(|_| Block::new(s).map(|(s, block)| (s, Self::Block(block))))
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        Self::new_operation(tokens, 0, true)
     }
 
-    fn new_operation(s: &str) -> Result<(&str, Self), String> {
-        let (s, lhs) = Self::new_operand(s)?;
-        let (s, _) = utils::extract_whitespace(s);
+    // expression followed by a block, like the condition of an `if`. a brace
+    // after a type name opens the block instead of the fields of a value
+    // being constructed, `if worker == Worker@NoJob { ... }`
+    pub(crate) fn new_condition(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        Self::new_operation(tokens, 0, false)
+    }
 
-        let (s, op) = Op::new(s)?;
-        let (s, _) = utils::extract_whitespace(s);
+    // expression that is not an item of a comma separated list, so a lambda
+    // may list several parameters without parentheses, `a, b -> a + b`
+    pub(crate) fn new_standalone(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        if !Lambda::starts_at(tokens, true) {
+            return Self::new(tokens);
+        }
 
-        let (s, rhs) = Self::new_operand(s)?;
+        let (rest, lambda) = Lambda::new(tokens, true)?;
 
         Ok((
-            s,
-            Self::Operation {
-                lhs: Box::new(lhs),
-                rhs: Box::new(rhs),
-                op,
+            rest,
+            Self {
+                kind: ExpressionKind::Lambda(lambda),
+                span: utils::consumed_span(tokens, rest),
             },
         ))
     }
 
-    fn new_operand(s: &str) -> Result<(&str, Self), String> {
-        Self::new_bool(s)
-            .or_else(|_| Self::new_float(s))
-            .or_else(|_| Self::new_number(s))
-            .or_else(|_| Self::new_string(s))
-            .or_else(|_| ListLiteral::new(s).map(|(s, list)| (s, Self::List(list))))
-            .or_else(|_| {
-                BindingUsage::new(s)
-                    .map(|(s, binding_usage)| (s, Self::BindingUsage(binding_usage)))
-            })
-            .or_else(|_| Block::new(s).map(|(s, block)| (s, Self::Block(block))))
-    }
+    // precedence climbing: parse a unary operand, then keep folding binary
+    // operators binding at least as tightly as `min_prec` into the left side.
+    // the right side is parsed one level higher, which makes operators of the
+    // same precedence left associative (`a - b - c` is `(a - b) - c`)
+    fn new_operation(
+        tokens: &[Token],
+        min_prec: u8,
+        field_lists: bool,
+    ) -> Result<(&[Token], Self), Error> {
+        let (mut tokens, mut lhs) = Self::new_unary_op(tokens, field_lists)?;
 
-    fn new_number(s: &str) -> Result<(&str, Self), String> {
-        Number::new(s).map(|(s, number)| (s, Self::Number(number)))
+        loop {
+            if min_prec <= RANGE_PRECEDENCE
+                && matches!(utils::peek(tokens), TokenKind::DotDot | TokenKind::DotDotEq)
+            {
+                let (rest, range) = RangeExpr::new(lhs, tokens, field_lists)?;
+                let span = range.start.span.to(&utils::consumed_span(tokens, rest));
+
+                tokens = rest;
+                lhs = Self {
+                    kind: ExpressionKind::Range(range),
+                    span,
+                };
+                continue;
+            }
+
+            let (rest, op) = match Op::new(tokens) {
+                Ok((rest, op)) if op.precedence() >= min_prec => (rest, op),
+                _ => break,
+            };
+
+            let (rest, rhs) = Self::new_operation(rest, op.precedence() + 1, field_lists)?;
+
+            tokens = rest;
+            let span = lhs.span.to(&rhs.span);
+            lhs = Self {
+                kind: ExpressionKind::Operation {
+                    lhs: Box::new(lhs),
+                    rhs: Box::new(rhs),
+                    op,
+                },
+                span,
+            };
+        }
+
+        Ok((tokens, lhs))
     }
 
-    fn new_float(s: &str) -> Result<(&str, Self), String> {
-        Float::new(s).map(|(s, float)| (s, Self::Float(float)))
+    // unary operators bind tighter than any binary operator
+    fn new_unary_op(tokens: &[Token], field_lists: bool) -> Result<(&[Token], Self), Error> {
+        let op = match utils::peek(tokens) {
+            TokenKind::Bang => UnaryOp::Not,
+            TokenKind::Minus => UnaryOp::Neg,
+            _ => return Self::new_postfix(tokens, field_lists),
+        };
+
+        let (rest, operand) = Self::new_unary_op(&tokens[1..], field_lists)?;
+        let span = tokens[0].span.to(&operand.span);
+
+        Ok((
+            rest,
+            Self {
+                kind: ExpressionKind::UnaryOp {
+                    operand: Box::new(operand),
+                    op,
+                },
+                span,
+            },
+        ))
     }
 
-    fn new_bool(s: &str) -> Result<(&str, Self), String> {
-        BoolLiteral::new(s).map(|(s, bool_lit)| (s, Self::Bool(bool_lit)))
+    // operand followed by any number of indexes, fields, method calls and
+    // calls of what they return, `xs[0].name.len()` or `handlers[0](event)`
+    fn new_postfix(tokens: &[Token], field_lists: bool) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let (mut tokens, mut expr) = Self::new_operand(tokens, field_lists)?;
+
+        loop {
+            let (rest, kind) = if Index::follows(tokens, &expr) {
+                Index::new(expr, tokens).map(|(t, index)| (t, ExpressionKind::Index(index)))?
+            } else if Call::follows(tokens, &expr) {
+                Call::new(expr, tokens).map(|(t, call)| (t, ExpressionKind::Call(call)))?
+            } else if *utils::peek(tokens) == TokenKind::Dot {
+                method::new(expr, tokens)?
+            } else if Self::try_follows(tokens, field_lists) {
+                (&tokens[1..], ExpressionKind::Try(Box::new(expr)))
+            } else {
+                return Ok((tokens, expr));
+            };
+
+            tokens = rest;
+            expr = Self {
+                kind,
+                span: utils::consumed_span(start, rest),
+            };
+        }
     }
 
-    fn new_unary_op(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
+    // `?` also starts the branches of an inline `if`, it propagates only when
+    // no operand follows it on the same line. `(x?) - 1` needs the parentheses.
+    // in a condition a `{` after it is the body, `if a? { 1 }`, unless the
+    // block is followed by `:` as in `if a ? { 1 } : { 2 }`
+    fn try_follows(tokens: &[Token], field_lists: bool) -> bool {
+        if *utils::peek(tokens) != TokenKind::Question {
+            return false;
+        }
+
+        let next = &tokens[1];
+        if !field_lists && next.kind == TokenKind::LBrace {
+            return *utils::peek(utils::skip_group(&tokens[1..])) != TokenKind::Colon;
+        }
+
+        let starts_operand = matches!(
+            next.kind,
+            TokenKind::Number(_)
+                | TokenKind::Float(_)
+                | TokenKind::String(_)
+                | TokenKind::Char(_)
+                | TokenKind::FString(_)
+                | TokenKind::Ident(_)
+                | TokenKind::True
+                | TokenKind::False
+                | TokenKind::None
+                | TokenKind::If
+                | TokenKind::While
+                | TokenKind::Loop
+                | TokenKind::For
+                | TokenKind::Try
+                | TokenKind::Match
+                | TokenKind::LParen
+                | TokenKind::LBracket
+                | TokenKind::LBrace
+                | TokenKind::Dollar
+                | TokenKind::Bang
+                | TokenKind::Minus
+        );
+
+        !starts_operand || next.span.line > tokens[0].span.line
+    }
+
+    fn new_operand(tokens: &[Token], field_lists: bool) -> Result<(&[Token], Self), Error> {
+        let (rest, kind) = match utils::peek(tokens) {
+            TokenKind::If => {
+                IfExpr::new(tokens).map(|(t, if_expr)| (t, ExpressionKind::If(if_expr)))
+            }
+            TokenKind::While => {
+                WhileLoop::new(tokens).map(|(t, while_loop)| (t, ExpressionKind::While(while_loop)))
+            }
+            TokenKind::Loop => Loop::new(tokens).map(|(t, loop_)| (t, ExpressionKind::Loop(loop_))),
+            TokenKind::For => {
+                ForLoop::new(tokens).map(|(t, for_loop)| (t, ExpressionKind::For(for_loop)))
+            }
+            TokenKind::Try => {
+                TryCatch::new(tokens).map(|(t, try_catch)| (t, ExpressionKind::TryCatch(try_catch)))
+            }
+            TokenKind::Match => {
+                Match::new(tokens).map(|(t, match_expr)| (t, ExpressionKind::Match(match_expr)))
+            }
+            TokenKind::LBracket => new_collection(tokens),
+            TokenKind::Dollar => {
+                TupleLiteral::new(tokens).map(|(t, tuple)| (t, ExpressionKind::Tuple(tuple)))
+            }
+            _ if Lambda::starts_at(tokens, false) => {
+                Lambda::new(tokens, false).map(|(t, lambda)| (t, ExpressionKind::Lambda(lambda)))
+            }
+            TokenKind::LParen => Self::new_group(tokens),
+            TokenKind::LBrace => {
+                Block::new(tokens).map(|(t, block)| (t, ExpressionKind::Block(block)))
+            }
+            TokenKind::None => Ok((&tokens[1..], ExpressionKind::None)),
+            TokenKind::True | TokenKind::False => {
+                BoolLiteral::new(tokens).map(|(t, bool_lit)| (t, ExpressionKind::Bool(bool_lit)))
+            }
+            TokenKind::Float(_) => {
+                Float::new(tokens).map(|(t, float)| (t, ExpressionKind::Float(float)))
+            }
+            TokenKind::Number(_) => {
+                Number::new(tokens).map(|(t, number)| (t, ExpressionKind::Number(number)))
+            }
+            TokenKind::FString(_) => Self::new_fstring(tokens),
+            TokenKind::String(_) => {
+                StringLiteral::new(tokens).map(|(t, string)| (t, ExpressionKind::String(string)))
+            }
+            TokenKind::Char(_) => {
+                CharLiteral::new(tokens).map(|(t, c)| (t, ExpressionKind::Char(c)))
+            }
+            _ if Construct::starts_at(tokens, field_lists) => Construct::new(tokens, field_lists)
+                .map(|(t, construct)| (t, ExpressionKind::Construct(construct))),
+            TokenKind::Ident(_) if tokens[1].kind == TokenKind::LParen => {
+                FunctionCall::new(tokens).map(|(t, call)| (t, ExpressionKind::FunctionCall(call)))
+            }
+            TokenKind::Ident(_) => BindingUsage::new(tokens)
+                .map(|(t, binding_usage)| (t, ExpressionKind::BindingUsage(binding_usage))),
+            _ => Err(utils::expected("expression", tokens)),
+        }?;
 
-        let (s, op) = utils::tag("!", s)
-            .map(|s| (s, UnaryOp::Not))
-            .or_else(|_| utils::tag("-", s).map(|s| (s, UnaryOp::Neg)))?;
+        Ok((
+            rest,
+            Self {
+                kind,
+                span: utils::consumed_span(tokens, rest),
+            },
+        ))
+    }
 
-        let (s, _) = utils::extract_whitespace(s);
-        let (s, operand) = Self::new_operand(s)?;
+    // block used as the body of `if`, `while` and `for`
+    fn new_block(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (rest, block) = Block::new(tokens)?;
 
         Ok((
-            s,
-            Self::UnaryOp {
-                operand: Box::new(operand),
-                op,
+            rest,
+            Self {
+                kind: ExpressionKind::Block(block),
+                span: utils::consumed_span(tokens, rest),
             },
         ))
     }
 
-    fn new_string(s: &str) -> Result<(&str, Self), String> {
-        StringLiteral::new(s).map(|(s, string)| (s, Self::String(string)))
+    // `()` is the unit literal, anything else in parentheses is a grouping
+    fn new_group(tokens: &[Token]) -> Result<(&[Token], ExpressionKind), Error> {
+        let tokens = utils::tag(&TokenKind::LParen, tokens)?;
+
+        if let Ok(tokens) = utils::tag(&TokenKind::RParen, tokens) {
+            return Ok((tokens, ExpressionKind::Unit));
+        }
+
+        let (tokens, inner) = Self::new(tokens)?;
+        let tokens = utils::tag(&TokenKind::RParen, tokens)?;
+
+        Ok((tokens, ExpressionKind::Group(Box::new(inner))))
     }
 
-    fn new_fstring(s: &str) -> Result<(&str, Self), String> {
-        let (s, _) = utils::extract_whitespace(s);
-        let (s, parts) = utils::extract_fstring(s)?;
-        Ok((s, Self::FString(parts)))
+    // interpolations were tokenized by the lexer, here they become expressions
+    fn new_fstring(tokens: &[Token]) -> Result<(&[Token], ExpressionKind), Error> {
+        let TokenKind::FString(segments) = utils::peek(tokens) else {
+            return Err(utils::expected("f-string", tokens));
+        };
+
+        let mut parts = Vec::new();
+        for segment in segments {
+            parts.push(match segment {
+                FStringSegment::Text(text) => FStringPart::Text(text.clone()),
+                FStringSegment::Interpolation(inner) => {
+                    FStringPart::Interpolation(utils::all_consuming(Self::new, inner)?)
+                }
+            });
+        }
+
+        Ok((&tokens[1..], ExpressionKind::FString(parts)))
     }
 
-    pub(crate) fn eval(&self, env: &Env) -> Result<Val, String> {
-        match self {
-            Self::Float(Float(n)) => Ok(Val::Float(*n)),
-            Self::Number(Number(n)) => Ok(Val::Number(*n)),
-            Self::String(StringLiteral(s)) => Ok(Val::String(env.intern(s))),
-            Self::Bool(BoolLiteral(b)) => Ok(Val::Bool(*b)),
-            Self::FString(parts) => {
+    pub(crate) fn eval(&self, env: &Env) -> Result<Val, Unwind> {
+        self.eval_kind(env).map_err(|err| err.or_span(&self.span))
+    }
+
+    fn eval_kind(&self, env: &Env) -> Result<Val, Unwind> {
+        match &self.kind {
+            ExpressionKind::Float(Float(n)) => Ok(Val::Float(*n)),
+            ExpressionKind::Number(Number(n)) => Ok(Val::Number(*n)),
+            ExpressionKind::String(StringLiteral(s)) => Ok(Val::String(env.intern(s))),
+            ExpressionKind::Char(CharLiteral(c)) => Ok(Val::Char(*c)),
+            ExpressionKind::Bool(BoolLiteral(b)) => Ok(Val::Bool(*b)),
+            ExpressionKind::FString(parts) => {
                 if parts.is_empty() {
                     return Ok(Val::String(env.intern("")));
                 }
@@ -467,151 +1134,68 @@ impl Expression {
 
                 for part in parts {
                     match part {
-                        utils::FStringPart::Text(text) => result.push_str(text),
-                        utils::FStringPart::Interpolation(expr_str) => {
-                            let (remaining, expr) = Expression::new(expr_str)?;
-                            if !remaining.is_empty() {
-                                return Err(format!(
-                                    "f-string interpolation didn't consume all input: '{}'",
-                                    remaining
-                                ));
-                            }
-
-                            let val = expr.eval(env)?;
-
-                            let str_repr = match val {
-                                Val::Number(n) => n.to_string(),
-                                Val::Float(f) => f.to_string(),
-                                Val::String(s) => s.to_string(),
-                                Val::Bool(b) => b.to_string(),
-                                Val::Function(_) => String::from("<function>"),
-                                Val::List(items) => {
-                                    let strs: Vec<String> = items
-                                        .iter()
-                                        .map(|v| match v {
-                                            Val::Number(n) => n.to_string(),
-                                            Val::Float(f) => f.to_string(),
-                                            Val::String(s) => format!("\"{}\"", s),
-                                            Val::Bool(b) => b.to_string(),
-                                            Val::Function(_) => String::from("<function>"),
-                                            Val::List(_) => String::from("[...]"),
-                                            Val::Unit => String::from("()"),
-                                        })
-                                        .collect();
-                                    format!("[{}]", strs.join(", "))
-                                }
-                                Val::Unit => String::from("()"),
-                            };
-
-                            result.push_str(&str_repr);
+                        FStringPart::Text(text) => result.push_str(text),
+                        FStringPart::Interpolation(expr) => {
+                            result.push_str(&expr.eval(env)?.to_string());
                         }
                     }
                 }
 
                 Ok(Val::String(env.intern(&result)))
             }
-            Self::List(list_lit) => {
+            ExpressionKind::List(list_lit) => {
                 let mut elements = Vec::new();
                 for elem_expr in &list_lit.elements {
                     elements.push(elem_expr.eval(env)?);
                 }
                 Ok(Val::List(elements))
             }
-            Self::Operation { lhs, rhs, op } => {
+            ExpressionKind::Tuple(tuple_lit) => {
+                let mut elements = Vec::new();
+                for elem_expr in &tuple_lit.elements {
+                    elements.push(elem_expr.eval(env)?);
+                }
+                Ok(Val::Tuple(elements))
+            }
+            ExpressionKind::Map(map_lit) => map_lit.eval(env),
+            ExpressionKind::Operation { lhs, rhs, op } => {
                 let lhs_val = lhs.eval(env)?;
                 let rhs_val = rhs.eval(env)?;
 
-                match (lhs_val, rhs_val, op) {
-                    // Arithmetic: Number operations
-                    (Val::Number(l), Val::Number(r), Op::Add) => Ok(Val::Number(l + r)),
-                    (Val::Number(l), Val::Number(r), Op::Sub) => Ok(Val::Number(l - r)),
-                    (Val::Number(l), Val::Number(r), Op::Mul) => Ok(Val::Number(l * r)),
-                    (Val::Number(l), Val::Number(r), Op::Div) => {
-                        if r == 0 {
-                            Err("Division by zero".to_string())
-                        } else {
-                            Ok(Val::Number(l / r))
-                        }
-                    }
-                    (Val::Number(l), Val::Number(r), Op::Floor) => {
-                        if r == 0 {
-                            Err("Division by zero".to_string())
-                        } else {
-                            Ok(Val::Number(l / r))
-                        }
-                    }
-
-                    // Arithmetic: Float operations
-                    (Val::Float(l), Val::Float(r), Op::Add) => Ok(Val::Float(l + r)),
-                    (Val::Float(l), Val::Float(r), Op::Sub) => Ok(Val::Float(l - r)),
-                    (Val::Float(l), Val::Float(r), Op::Mul) => Ok(Val::Float(l * r)),
-                    (Val::Float(l), Val::Float(r), Op::Div) => Ok(Val::Float(l / r)),
-
-                    // String concatenation
-                    (Val::String(l), Val::String(r), Op::Add) => {
-                        let concatenated = format!("{}{}", l, r);
-                        Ok(Val::String(env.intern(&concatenated)))
-                    }
-
-                    // Comparison: Numbers
-                    (Val::Number(l), Val::Number(r), Op::Eq) => Ok(Val::Bool(l == r)),
-                    (Val::Number(l), Val::Number(r), Op::NotEq) => Ok(Val::Bool(l != r)),
-                    (Val::Number(l), Val::Number(r), Op::Lt) => Ok(Val::Bool(l < r)),
-                    (Val::Number(l), Val::Number(r), Op::LtEq) => Ok(Val::Bool(l <= r)),
-                    (Val::Number(l), Val::Number(r), Op::Gt) => Ok(Val::Bool(l > r)),
-                    (Val::Number(l), Val::Number(r), Op::GtEq) => Ok(Val::Bool(l >= r)),
-
-                    // Comparison: Floats
-                    (Val::Float(l), Val::Float(r), Op::Eq) => Ok(Val::Bool(l == r)),
-                    (Val::Float(l), Val::Float(r), Op::NotEq) => Ok(Val::Bool(l != r)),
-                    (Val::Float(l), Val::Float(r), Op::Lt) => Ok(Val::Bool(l < r)),
-                    (Val::Float(l), Val::Float(r), Op::LtEq) => Ok(Val::Bool(l <= r)),
-                    (Val::Float(l), Val::Float(r), Op::Gt) => Ok(Val::Bool(l > r)),
-                    (Val::Float(l), Val::Float(r), Op::GtEq) => Ok(Val::Bool(l >= r)),
-
-                    // Comparison: Strings
-                    (Val::String(l), Val::String(r), Op::Eq) => Ok(Val::Bool(l == r)),
-                    (Val::String(l), Val::String(r), Op::NotEq) => Ok(Val::Bool(l != r)),
-
-                    // Comparison: Bools
-                    (Val::Bool(l), Val::Bool(r), Op::Eq) => Ok(Val::Bool(l == r)),
-                    (Val::Bool(l), Val::Bool(r), Op::NotEq) => Ok(Val::Bool(l != r)),
-
-                    // Logical operators
-                    (Val::Bool(l), Val::Bool(r), Op::And) => Ok(Val::Bool(l && r)),
-                    (Val::Bool(l), Val::Bool(r), Op::Or) => Ok(Val::Bool(l || r)),
-
-                    // Type errors
-                    (l, r, op) => Err(format!(
-                        "Type error: cannot apply operator {:?} to {:?} and {:?}",
-                        op, l, r
-                    )),
-                }
+                Ok(op.apply(lhs_val, rhs_val, env, &self.span)?)
             }
-            Self::UnaryOp { operand, op } => {
+            ExpressionKind::UnaryOp { operand, op } => {
                 let val = operand.eval(env)?;
                 match (op, val) {
                     (UnaryOp::Not, Val::Bool(b)) => Ok(Val::Bool(!b)),
                     (UnaryOp::Neg, Val::Number(n)) => Ok(Val::Number(-n)),
                     (UnaryOp::Neg, Val::Float(f)) => Ok(Val::Float(-f)),
-                    (op, val) => Err(format!(
-                        "Type error: cannot apply unary operator {:?} to {:?}",
-                        op, val
-                    )),
+                    (op, val) => Err(Error::InvalidOperand {
+                        op: op.to_string(),
+                        operand: val.type_name().to_string(),
+                        span: self.span.clone(),
+                    }
+                    .into()),
                 }
             }
-            Self::If(if_expr) => {
+            ExpressionKind::If(if_expr) => {
                 let condition_val = if_expr.condition.eval(env)?;
 
                 if condition_val.is_truthy() {
-                    if_expr.then_branch.eval(env)
+                    let val = if_expr.then_branch.eval(env)?;
+
+                    if if_expr.else_branch.is_none() {
+                        Ok(Val::Opt(Some(Box::new(val))))
+                    } else {
+                        Ok(val)
+                    }
                 } else if let Some(else_branch) = &if_expr.else_branch {
                     else_branch.eval(env)
                 } else {
-                    Ok(Val::Unit)
+                    Ok(Val::Opt(None))
                 }
             }
-            Self::While(while_loop) => {
+            ExpressionKind::While(while_loop) => {
                 let mut result = Val::Unit;
 
                 loop {
@@ -620,79 +1204,134 @@ impl Expression {
                         break;
                     }
 
-                    result = while_loop.body.eval(env)?;
+                    match loop_step(while_loop.body.eval(env))? {
+                        ControlFlow::Continue(val) => result = val,
+                        ControlFlow::Break(val) => return Ok(val),
+                    }
                 }
 
                 Ok(result)
             }
-            Self::For(for_loop) => {
+            ExpressionKind::Loop(loop_) => loop {
+                if let ControlFlow::Break(val) = loop_step(loop_.body.eval(env))? {
+                    return Ok(val);
+                }
+            },
+            ExpressionKind::For(for_loop) => {
                 let iterable_val = for_loop.iterable.eval(env)?;
                 let mut result = Val::Unit;
 
-                match iterable_val {
-                    Val::List(items) => {
-                        for item in items {
-                            let mut loop_env = env.create_child();
-                            loop_env.store_binding(for_loop.var.clone(), item);
-                            result = for_loop.body.eval(&loop_env)?;
+                let items: Box<dyn Iterator<Item = Val>> = match iterable_val {
+                    Val::List(items) => Box::new(items.into_iter()),
+                    Val::Range(range) => Box::new(range.iter().map(Val::Number)),
+                    Val::String(s) => {
+                        Box::new(s.chars().map(Val::Char).collect::<Vec<_>>().into_iter())
+                    }
+                    // each entry is a `(key, value)` tuple, in insertion order
+                    Val::Map(map) => Box::new(
+                        map.into_entries()
+                            .map(|(key, val)| Val::Tuple(vec![key, val])),
+                    ),
+                    other => {
+                        return Err(Error::TypeMismatch {
+                            expected: "list, str, range or map".to_string(),
+                            found: other.type_name().to_string(),
+                            span: for_loop.iterable.span.clone(),
                         }
-                        Ok(result)
+                        .into());
+                    }
+                };
+
+                for item in items {
+                    let mut loop_env = env.create_child();
+                    for_loop.var.bind(item, &mut loop_env, false)?;
+                    match loop_step(for_loop.body.eval(&loop_env))? {
+                        ControlFlow::Continue(val) => result = val,
+                        ControlFlow::Break(val) => return Ok(val),
                     }
-                    _ => Err("for loop requires an iterable (list)".to_string()),
                 }
+
+                Ok(result)
             }
-            Self::FunctionCall(call) => {
+            ExpressionKind::FunctionCall(call) => {
+                // built-ins only take arguments by position
+                if matches!(
+                    call.name.as_str(),
+                    "print" | "len" | "some" | "ok" | "err" | "range"
+                ) && let Some(arg) = call.named.first()
+                {
+                    return Err(Error::UnknownParam {
+                        name: call.name.clone(),
+                        param: arg.name.clone(),
+                        span: self.span.clone(),
+                    }
+                    .into());
+                }
+
                 // Check for built-in functions first
                 if call.name == "print" {
                     for arg in &call.args {
-                        let val = arg.eval(env)?;
-                        let output = match val {
-                            Val::Number(n) => n.to_string(),
-                            Val::Float(f) => f.to_string(),
-                            Val::String(s) => s.to_string(),
-                            Val::Bool(b) => b.to_string(),
-                            Val::Function(_) => String::from("<function>"),
-                            Val::List(items) => {
-                                let strs: Vec<String> = items
-                                    .iter()
-                                    .map(|v| match v {
-                                        Val::Number(n) => n.to_string(),
-                                        Val::Float(f) => f.to_string(),
-                                        Val::String(s) => s.to_string(),
-                                        Val::Bool(b) => b.to_string(),
-                                        Val::Function(_) => String::from("<function>"),
-                                        Val::List(_) => String::from("[...]"),
-                                        Val::Unit => String::from("()"),
-                                    })
-                                    .collect();
-                                format!("[{}]", strs.join(", "))
-                            }
-                            Val::Unit => String::from("()"),
-                        };
-                        println!("{}", output);
+                        println!("{}", arg.eval(env)?);
                     }
                     return Ok(Val::Unit);
                 }
 
                 if call.name == "len" {
                     if call.args.len() != 1 {
-                        return Err(format!("len() expects 1 argument, got {}", call.args.len()));
+                        return Err(Error::ArityMismatch {
+                            name: call.name.clone(),
+                            expected: 1,
+                            found: call.args.len(),
+                            span: self.span.clone(),
+                        }
+                        .into());
                     }
                     let val = call.args[0].eval(env)?;
                     let length = match val {
-                        Val::String(s) => s.len() as i64,
+                        Val::String(s) => s.chars().count() as i64,
                         Val::List(items) => items.len() as i64,
-                        _ => return Err("len() requires a string or list".to_string()),
+                        Val::Range(range) => method::range_len(&range, &call.args[0].span)?,
+                        Val::Map(map) => map.len() as i64,
+                        other => {
+                            return Err(Error::TypeMismatch {
+                                expected: "str, list, range or map".to_string(),
+                                found: other.type_name().to_string(),
+                                span: call.args[0].span.clone(),
+                            }
+                            .into());
+                        }
                     };
                     return Ok(Val::Number(length));
                 }
 
+                // `some(x)`, `ok(x)` and `err(e)` wrap their argument
+                if matches!(call.name.as_str(), "some" | "ok" | "err") {
+                    if call.args.len() != 1 {
+                        return Err(Error::ArityMismatch {
+                            name: call.name.clone(),
+                            expected: 1,
+                            found: call.args.len(),
+                            span: self.span.clone(),
+                        }
+                        .into());
+                    }
+                    let val = Box::new(call.args[0].eval(env)?);
+                    return Ok(match call.name.as_str() {
+                        "some" => Val::Opt(Some(val)),
+                        "ok" => Val::Res(Ok(val)),
+                        _ => Val::Res(Err(val)),
+                    });
+                }
+
                 if call.name == "range" {
                     if call.args.len() != 1 && call.args.len() != 2 {
-                        return Err(format!(
-                            "range() expects 1 or 2 arguments, got {}",
-                            call.args.len()
-                        ));
+                        return Err(Error::ArityMismatch {
+                            name: call.name.clone(),
+                            expected: call.args.len().clamp(1, 2),
+                            found: call.args.len(),
+                            span: self.span.clone(),
+                        }
+                        .into());
                     }
 
                     let start = if call.args.len() == 1 {
@@ -700,17 +1339,17 @@ impl Expression {
                     } else {
                         match call.args[0].eval(env)? {
                             Val::Number(n) => n,
-                            _ => return Err("range() arguments must be numbers".to_string()),
+                            other => return Err(expected_int(&other, &call.args[0]).into()),
                         }
                     };
 
-                    let end = match call.args[if call.args.len() == 1 { 0 } else { 1 }].eval(env)? {
+                    let end_arg = &call.args[call.args.len() - 1];
+                    let end = match end_arg.eval(env)? {
                         Val::Number(n) => n,
-                        _ => return Err("range() arguments must be numbers".to_string()),
+                        other => return Err(expected_int(&other, end_arg).into()),
                     };
 
-                    let items: Vec<Val> = (start..end).map(Val::Number).collect();
-                    return Ok(Val::List(items));
+                    return Ok(Val::Range(Range::new(start, end, 1, false)));
                 }
 
                 // Get the function from environment
@@ -718,42 +1357,191 @@ impl Expression {
 
                 match func_val {
                     Val::Function(func) => {
-                        // Check parameter count
-                        if func.params.len() != call.args.len() {
-                            return Err(format!(
-                                "Function '{}' expects {} arguments, got {}",
-                                call.name,
-                                func.params.len(),
-                                call.args.len()
-                            ));
-                        }
-
                         // Evaluate arguments
                         let mut arg_vals = Vec::new();
                         for arg in &call.args {
                             arg_vals.push(arg.eval(env)?);
                         }
 
-                        // Create child environment
-                        let mut func_env = env.create_child();
-
-                        // Bind parameters to argument values
-                        for (param, arg_val) in func.params.iter().zip(arg_vals.iter()) {
-                            func_env.store_binding(param.clone(), arg_val.clone());
+                        let mut named_vals = Vec::new();
+                        for arg in &call.named {
+                            named_vals.push((arg.name.clone(), arg.val.eval(env)?));
                         }
 
-                        // Evaluate function body
-                        func.body.eval(&mut func_env)
+                        func.call(&call.name, arg_vals, named_vals)
+                    }
+                    _ => Err(Error::NotCallable {
+                        name: call.name.clone(),
+                        span: self.span.clone(),
+                    }
+                    .into()),
+                }
+            }
+            ExpressionKind::Call(call) => call.eval(env, &self.span),
+            ExpressionKind::Lambda(lambda) => Ok(lambda.eval(env)),
+            ExpressionKind::BindingUsage(binding_usage) => Ok(binding_usage.eval(env)?),
+            ExpressionKind::Block(block) => block.eval(env),
+            ExpressionKind::Range(range) => range.eval(env),
+            ExpressionKind::Index(index) => index.eval(env),
+            ExpressionKind::MethodCall(call) => call.eval(env, &self.span),
+            ExpressionKind::Field(field) => field.eval(env, &self.span),
+            ExpressionKind::Group(inner) => inner.eval(env),
+            ExpressionKind::TryCatch(try_catch) => try_catch.eval(env),
+            ExpressionKind::Match(match_expr) => match_expr.eval(env, &self.span),
+            ExpressionKind::Construct(construct) => construct.eval(env, &self.span),
+            ExpressionKind::Try(inner) => match inner.eval(env)? {
+                Val::Opt(Some(val)) | Val::Res(Ok(val)) => Ok(*val),
+                early @ (Val::Opt(None) | Val::Res(Err(_))) => {
+                    Err(Unwind::Propagate(early, self.span.clone()))
+                }
+                other => Err(Error::TypeMismatch {
+                    expected: "opt or res".to_string(),
+                    found: other.type_name().to_string(),
+                    span: inner.span.clone(),
+                }
+                .into()),
+            },
+            ExpressionKind::None => Ok(Val::Opt(None)),
+            ExpressionKind::Unit => Ok(Val::Unit),
+        }
+    }
+}
+
+impl Expression {
+    // parse errors of statements nested in blocks, in source order
+    pub(crate) fn collect_errors(&self, errors: &mut Vec<Error>) {
+        match &self.kind {
+            ExpressionKind::FString(parts) => {
+                for part in parts {
+                    if let FStringPart::Interpolation(expr) = part {
+                        expr.collect_errors(errors);
                     }
-                    _ => Err(format!("'{}' is not a function", call.name)),
                 }
             }
-            Self::BindingUsage(binding_usage) => binding_usage.eval(env),
-            Self::Block(block) => block.eval(env),
+            ExpressionKind::List(list_lit) => {
+                for element in &list_lit.elements {
+                    element.collect_errors(errors);
+                }
+            }
+            ExpressionKind::Tuple(tuple_lit) => {
+                for element in &tuple_lit.elements {
+                    element.collect_errors(errors);
+                }
+            }
+            ExpressionKind::Map(map_lit) => {
+                for (key, val) in &map_lit.entries {
+                    key.collect_errors(errors);
+                    val.collect_errors(errors);
+                }
+            }
+            ExpressionKind::Operation { lhs, rhs, .. } => {
+                lhs.collect_errors(errors);
+                rhs.collect_errors(errors);
+            }
+            ExpressionKind::UnaryOp { operand, .. } => operand.collect_errors(errors),
+            ExpressionKind::If(if_expr) => {
+                if_expr.condition.collect_errors(errors);
+                if_expr.then_branch.collect_errors(errors);
+                if let Some(else_branch) = &if_expr.else_branch {
+                    else_branch.collect_errors(errors);
+                }
+            }
+            ExpressionKind::While(while_loop) => {
+                while_loop.condition.collect_errors(errors);
+                while_loop.body.collect_errors(errors);
+            }
+            ExpressionKind::Loop(loop_) => loop_.body.collect_errors(errors),
+            ExpressionKind::TryCatch(try_catch) => {
+                try_catch.body.collect_errors(errors);
+                try_catch.handler.collect_errors(errors);
+            }
+            ExpressionKind::Match(match_expr) => match_expr.collect_errors(errors),
+            ExpressionKind::Construct(construct) => {
+                for item in &construct.payload {
+                    item.collect_errors(errors);
+                }
+                for field in &construct.fields {
+                    field.val.collect_errors(errors);
+                }
+            }
+            ExpressionKind::For(for_loop) => {
+                for_loop.iterable.collect_errors(errors);
+                for_loop.body.collect_errors(errors);
+            }
+            ExpressionKind::FunctionCall(call) => {
+                for arg in &call.args {
+                    arg.collect_errors(errors);
+                }
+                for arg in &call.named {
+                    arg.val.collect_errors(errors);
+                }
+            }
+            ExpressionKind::Call(call) => {
+                call.callee.collect_errors(errors);
+                for arg in &call.args {
+                    arg.collect_errors(errors);
+                }
+                for arg in &call.named {
+                    arg.val.collect_errors(errors);
+                }
+            }
+            ExpressionKind::Lambda(lambda) => lambda.body.collect_errors(errors),
+            ExpressionKind::Block(block) => block.collect_errors(errors),
+            ExpressionKind::Range(range) => {
+                range.start.collect_errors(errors);
+                range.end.collect_errors(errors);
+                if let Some(step) = &range.step {
+                    step.collect_errors(errors);
+                }
+            }
+            ExpressionKind::Index(index) => {
+                index.target.collect_errors(errors);
+                index.index.collect_errors(errors);
+            }
+            ExpressionKind::MethodCall(call) => {
+                call.target.collect_errors(errors);
+                for arg in &call.args {
+                    arg.collect_errors(errors);
+                }
+                for arg in &call.named {
+                    arg.val.collect_errors(errors);
+                }
+            }
+            ExpressionKind::Field(field) => field.target.collect_errors(errors),
+            ExpressionKind::Group(inner) | ExpressionKind::Try(inner) => {
+                inner.collect_errors(errors)
+            }
+            ExpressionKind::Number(_)
+            | ExpressionKind::Float(_)
+            | ExpressionKind::String(_)
+            | ExpressionKind::Char(_)
+            | ExpressionKind::Bool(_)
+            | ExpressionKind::BindingUsage(_)
+            | ExpressionKind::None
+            | ExpressionKind::Unit => {}
         }
     }
 }
 
+fn expected_int(found: &Val, expr: &Expression) -> Error {
+    Error::TypeMismatch {
+        expected: "int".to_string(),
+        found: found.type_name().to_string(),
+        span: expr.span.clone(),
+    }
+}
+
+// outcome of one run of a loop body, `Break` ends the loop with its value.
+// `continue` leaves unit as the body's value
+fn loop_step(result: Result<Val, Unwind>) -> Result<ControlFlow<Val, Val>, Unwind> {
+    match result {
+        Ok(val) => Ok(ControlFlow::Continue(val)),
+        Err(Unwind::Break(val, _)) => Ok(ControlFlow::Break(val)),
+        Err(Unwind::Continue(_)) => Ok(ControlFlow::Continue(Val::Unit)),
+        Err(unwind) => Err(unwind),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -762,65 +1550,66 @@ mod tests {
 
     #[test]
     fn parse_num() {
-        assert_eq!(Number::new("321312"), Ok(("", Number(321312))))
+        assert_eq!(utils::parse_str(Number::new, "321312"), Ok(Number(321312)))
     }
 
     #[test]
     fn parse_number_as_expression() {
         assert_eq!(
-            Expression::new("475"),
-            Ok(("", Expression::Number(Number(475))))
+            utils::parse_str(Expression::new, "475"),
+            Ok(Expression::from(ExpressionKind::Number(Number(475))))
         )
     }
+
     #[test]
     fn parse_add_op() {
-        assert_eq!(Op::new("+"), Ok(("", Op::Add)));
+        assert_eq!(utils::parse_str(Op::new, "+"), Ok(Op::Add));
     }
 
     #[test]
     fn parse_sub_op() {
-        assert_eq!(Op::new("-"), Ok(("", Op::Sub)));
+        assert_eq!(utils::parse_str(Op::new, "-"), Ok(Op::Sub));
     }
 
     #[test]
     fn parse_mul_op() {
-        assert_eq!(Op::new("*"), Ok(("", Op::Mul)));
+        assert_eq!(utils::parse_str(Op::new, "*"), Ok(Op::Mul));
     }
 
     #[test]
     fn parse_div_op() {
-        assert_eq!(Op::new("/"), Ok(("", Op::Div)));
+        assert_eq!(utils::parse_str(Op::new, "/"), Ok(Op::Div));
     }
 
     #[test]
     fn parse_floor_op() {
-        assert_eq!(Op::new("//"), Ok(("", Op::Floor)));
+        assert_eq!(utils::parse_str(Op::new, "//"), Ok(Op::Floor));
     }
 
     #[test]
     fn parse_single_int() {
         assert_eq!(
-            Expression::new("50"),
-            Ok(("", Expression::Number(Number(50))))
+            utils::parse_str(Expression::new, "50"),
+            Ok(Expression::from(ExpressionKind::Number(Number(50))))
         )
     }
 
     #[test]
     fn parse_single_float() {
         assert_eq!(
-            Expression::new(" 2.5"),
-            Ok(("", Expression::Float(Float(2.5))))
+            utils::parse_str(Expression::new, " 2.5"),
+            Ok(Expression::from(ExpressionKind::Float(Float(2.5))))
         )
     }
 
     #[test]
     fn eval_add() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(20))),
-                rhs: Box::new(Expression::Number(Number(10))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(20)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
                 op: Op::Add,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Number(30))
         )
@@ -829,11 +1618,11 @@ mod tests {
     #[test]
     fn eval_sub() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(5))),
-                rhs: Box::new(Expression::Number(Number(10))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
                 op: Op::Sub,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Number(-5))
         )
@@ -842,11 +1631,11 @@ mod tests {
     #[test]
     fn eval_mul() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(20))),
-                rhs: Box::new(Expression::Number(Number(10))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(20)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
                 op: Op::Mul,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Number(200))
         )
@@ -856,11 +1645,11 @@ mod tests {
     #[test]
     fn eval_div() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(12))),
-                rhs: Box::new(Expression::Number(Number(3))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(12)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(3)))),
                 op: Op::Div,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Number(4))
         )
@@ -869,11 +1658,11 @@ mod tests {
     #[test]
     fn eval_floor() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(20))),
-                rhs: Box::new(Expression::Number(Number(3))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(20)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(3)))),
                 op: Op::Floor,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Number(6))
         )
@@ -882,39 +1671,36 @@ mod tests {
     #[test]
     fn parse_expression_with_whitespace() {
         assert_eq!(
-            Expression::new("3   //     4"),
-            Ok((
-                "",
-                Expression::Operation {
-                    lhs: Box::new(Expression::Number(Number(3))),
-                    rhs: Box::new(Expression::Number(Number(4))),
-                    op: Op::Floor,
-                }
-            ))
+            utils::parse_str(Expression::new, "3   //     4"),
+            Ok(Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(3)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(4)))),
+                op: Op::Floor,
+            }))
         )
     }
 
     #[test]
     fn parse_binding_usage() {
         assert_eq!(
-            Expression::new("bar"),
-            Ok((
-                "",
-                Expression::BindingUsage(BindingUsage {
+            utils::parse_str(Expression::new, "bar"),
+            Ok(Expression::from(ExpressionKind::BindingUsage(
+                BindingUsage {
                     name: "bar".to_string(),
-                }),
-            ))
+                }
+            )))
         )
     }
+
     #[test]
     fn eval_binding_usage() {
         let mut env = Env::default();
         env.store_binding("ten".to_string(), Val::Number(10));
 
         assert_eq!(
-            Expression::BindingUsage(BindingUsage {
+            Expression::from(ExpressionKind::BindingUsage(BindingUsage {
                 name: "ten".to_string(),
-            })
+            }))
             .eval(&env),
             Ok(Val::Number(10)),
         )
@@ -923,22 +1709,23 @@ mod tests {
     #[test]
     fn parse_block() {
         assert_eq!(
-            Expression::new("{ 300 }"),
-            Ok((
-                "",
-                Expression::Block(Block {
-                    statements: vec![Statement::Expression(Expression::Number(Number(300)))]
-                })
-            ))
+            utils::parse_str(Expression::new, "{ 300 }"),
+            Ok(Expression::from(ExpressionKind::Block(Block {
+                statements: vec![Statement::Expression(Expression::from(
+                    ExpressionKind::Number(Number(300))
+                ))]
+            })))
         )
     }
 
     #[test]
     fn eval_block() {
         assert_eq!(
-            Expression::Block(Block {
-                statements: vec![Statement::Expression(Expression::Number(Number(10)))],
-            })
+            Expression::from(ExpressionKind::Block(Block {
+                statements: vec![Statement::Expression(Expression::from(
+                    ExpressionKind::Number(Number(10))
+                ))],
+            }))
             .eval(&Env::default()),
             Ok(Val::Number(10))
         )
@@ -947,29 +1734,28 @@ mod tests {
     #[test]
     fn parse_string_literal() {
         assert_eq!(
-            Expression::new("\"hello world\""),
-            Ok((
-                "",
-                Expression::String(StringLiteral("hello world".to_string()))
-            ))
+            utils::parse_str(Expression::new, "\"hello world\""),
+            Ok(Expression::from(ExpressionKind::String(StringLiteral(
+                "hello world".to_string()
+            ))))
         )
     }
 
     #[test]
     fn parse_string_with_escapes() {
         assert_eq!(
-            Expression::new("\"hello\\nworld\""),
-            Ok((
-                "",
-                Expression::String(StringLiteral("hello\nworld".to_string()))
-            ))
+            utils::parse_str(Expression::new, "\"hello\\nworld\""),
+            Ok(Expression::from(ExpressionKind::String(StringLiteral(
+                "hello\nworld".to_string()
+            ))))
         )
     }
 
     #[test]
     fn eval_string() {
         assert_eq!(
-            Expression::String(StringLiteral("test".to_string())).eval(&Env::default()),
+            Expression::from(ExpressionKind::String(StringLiteral("test".to_string())))
+                .eval(&Env::default()),
             Ok(Val::String(Rc::from("test")))
         )
     }
@@ -977,11 +1763,15 @@ mod tests {
     #[test]
     fn eval_string_concatenation() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::String(StringLiteral("hello".to_string()))),
-                rhs: Box::new(Expression::String(StringLiteral(" world".to_string()))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::String(StringLiteral(
+                    "hello".to_string()
+                )))),
+                rhs: Box::new(Expression::from(ExpressionKind::String(StringLiteral(
+                    " world".to_string()
+                )))),
                 op: Op::Add,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::String(Rc::from("hello world")))
         )
@@ -990,60 +1780,77 @@ mod tests {
     #[test]
     fn parse_string_concatenation() {
         assert_eq!(
-            Expression::new("\"hello\" + \" world\""),
-            Ok((
-                "",
-                Expression::Operation {
-                    lhs: Box::new(Expression::String(StringLiteral("hello".to_string()))),
-                    rhs: Box::new(Expression::String(StringLiteral(" world".to_string()))),
-                    op: Op::Add,
-                }
-            ))
+            utils::parse_str(Expression::new, "\"hello\" + \" world\""),
+            Ok(Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::String(StringLiteral(
+                    "hello".to_string()
+                )))),
+                rhs: Box::new(Expression::from(ExpressionKind::String(StringLiteral(
+                    " world".to_string()
+                )))),
+                op: Op::Add,
+            }))
         )
     }
 
     #[test]
     fn type_error_number_plus_string() {
-        let result = Expression::Operation {
-            lhs: Box::new(Expression::Number(Number(10))),
-            rhs: Box::new(Expression::String(StringLiteral("test".to_string()))),
+        let result = Expression::from(ExpressionKind::Operation {
+            lhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
+            rhs: Box::new(Expression::from(ExpressionKind::String(StringLiteral(
+                "test".to_string(),
+            )))),
             op: Op::Add,
-        }
+        })
         .eval(&Env::default());
 
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Type error"));
+        assert_eq!(
+            result,
+            Err(Unwind::Error(Error::InvalidOperands {
+                op: "+".to_string(),
+                lhs: "int".to_string(),
+                rhs: "str".to_string(),
+                span: Span::default(),
+            }))
+        );
     }
 
     #[test]
     fn type_error_string_minus_string() {
-        let result = Expression::Operation {
-            lhs: Box::new(Expression::String(StringLiteral("hello".to_string()))),
-            rhs: Box::new(Expression::String(StringLiteral("world".to_string()))),
+        let result = Expression::from(ExpressionKind::Operation {
+            lhs: Box::new(Expression::from(ExpressionKind::String(StringLiteral(
+                "hello".to_string(),
+            )))),
+            rhs: Box::new(Expression::from(ExpressionKind::String(StringLiteral(
+                "world".to_string(),
+            )))),
             op: Op::Sub,
-        }
+        })
         .eval(&Env::default());
 
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Type error"));
+        assert!(matches!(
+            result,
+            Err(Unwind::Error(Error::InvalidOperands { ref op, .. })) if op == "-"
+        ));
     }
 
     #[test]
     fn parse_simple_fstring() {
         assert_eq!(
-            Expression::new("f\"hello\""),
-            Ok((
-                "",
-                Expression::FString(vec![utils::FStringPart::Text("hello".to_string())])
-            ))
+            utils::parse_str(Expression::new, "f\"hello\""),
+            Ok(Expression::from(ExpressionKind::FString(vec![
+                FStringPart::Text("hello".to_string())
+            ])))
         )
     }
 
     #[test]
     fn eval_simple_fstring() {
         assert_eq!(
-            Expression::FString(vec![utils::FStringPart::Text("hello".to_string())])
-                .eval(&Env::default()),
+            Expression::from(ExpressionKind::FString(vec![FStringPart::Text(
+                "hello".to_string()
+            )]))
+            .eval(&Env::default()),
             Ok(Val::String(Rc::from("hello")))
         )
     }
@@ -1051,15 +1858,16 @@ mod tests {
     #[test]
     fn parse_fstring_with_interpolation() {
         assert_eq!(
-            Expression::new("f\"Hello {name}!\""),
-            Ok((
-                "",
-                Expression::FString(vec![
-                    utils::FStringPart::Text("Hello ".to_string()),
-                    utils::FStringPart::Interpolation("name".to_string()),
-                    utils::FStringPart::Text("!".to_string()),
-                ])
-            ))
+            utils::parse_str(Expression::new, "f\"Hello {name}!\""),
+            Ok(Expression::from(ExpressionKind::FString(vec![
+                FStringPart::Text("Hello ".to_string()),
+                FStringPart::Interpolation(Expression::from(ExpressionKind::BindingUsage(
+                    BindingUsage {
+                        name: "name".to_string(),
+                    },
+                ))),
+                FStringPart::Text("!".to_string()),
+            ])))
         )
     }
 
@@ -1069,11 +1877,15 @@ mod tests {
         env.store_binding("name".to_string(), Val::String(Rc::from("World")));
 
         assert_eq!(
-            Expression::FString(vec![
-                utils::FStringPart::Text("Hello ".to_string()),
-                utils::FStringPart::Interpolation("name".to_string()),
-                utils::FStringPart::Text("!".to_string()),
-            ])
+            Expression::from(ExpressionKind::FString(vec![
+                FStringPart::Text("Hello ".to_string()),
+                FStringPart::Interpolation(Expression::from(ExpressionKind::BindingUsage(
+                    BindingUsage {
+                        name: "name".to_string(),
+                    },
+                ))),
+                FStringPart::Text("!".to_string()),
+            ]))
             .eval(&env),
             Ok(Val::String(Rc::from("Hello World!")))
         )
@@ -1082,10 +1894,14 @@ mod tests {
     #[test]
     fn eval_fstring_with_expression() {
         assert_eq!(
-            Expression::FString(vec![
-                utils::FStringPart::Text("Result: ".to_string()),
-                utils::FStringPart::Interpolation("10 + 20".to_string()),
-            ])
+            Expression::from(ExpressionKind::FString(vec![
+                FStringPart::Text("Result: ".to_string()),
+                FStringPart::Interpolation(Expression::from(ExpressionKind::Operation {
+                    lhs: num(10),
+                    rhs: num(20),
+                    op: Op::Add,
+                })),
+            ]))
             .eval(&Env::default()),
             Ok(Val::String(Rc::from("Result: 30")))
         )
@@ -1094,10 +1910,10 @@ mod tests {
     #[test]
     fn eval_fstring_with_number() {
         assert_eq!(
-            Expression::FString(vec![
-                utils::FStringPart::Text("Number: ".to_string()),
-                utils::FStringPart::Interpolation("42".to_string()),
-            ])
+            Expression::from(ExpressionKind::FString(vec![
+                FStringPart::Text("Number: ".to_string()),
+                FStringPart::Interpolation(*num(42)),
+            ]))
             .eval(&Env::default()),
             Ok(Val::String(Rc::from("Number: 42")))
         )
@@ -1108,23 +1924,23 @@ mod tests {
     #[test]
     fn parse_true_literal() {
         assert_eq!(
-            Expression::new("true"),
-            Ok(("", Expression::Bool(BoolLiteral(true))))
+            utils::parse_str(Expression::new, "true"),
+            Ok(Expression::from(ExpressionKind::Bool(BoolLiteral(true))))
         )
     }
 
     #[test]
     fn parse_false_literal() {
         assert_eq!(
-            Expression::new("false"),
-            Ok(("", Expression::Bool(BoolLiteral(false))))
+            utils::parse_str(Expression::new, "false"),
+            Ok(Expression::from(ExpressionKind::Bool(BoolLiteral(false))))
         )
     }
 
     #[test]
     fn eval_true() {
         assert_eq!(
-            Expression::Bool(BoolLiteral(true)).eval(&Env::default()),
+            Expression::from(ExpressionKind::Bool(BoolLiteral(true))).eval(&Env::default()),
             Ok(Val::Bool(true))
         )
     }
@@ -1132,7 +1948,7 @@ mod tests {
     #[test]
     fn eval_false() {
         assert_eq!(
-            Expression::Bool(BoolLiteral(false)).eval(&Env::default()),
+            Expression::from(ExpressionKind::Bool(BoolLiteral(false))).eval(&Env::default()),
             Ok(Val::Bool(false))
         )
     }
@@ -1166,42 +1982,42 @@ mod tests {
 
     #[test]
     fn parse_eq_operator() {
-        assert_eq!(Op::new("=="), Ok(("", Op::Eq)));
+        assert_eq!(utils::parse_str(Op::new, "=="), Ok(Op::Eq));
     }
 
     #[test]
     fn parse_neq_operator() {
-        assert_eq!(Op::new("!="), Ok(("", Op::NotEq)));
+        assert_eq!(utils::parse_str(Op::new, "!="), Ok(Op::NotEq));
     }
 
     #[test]
     fn parse_lt_operator() {
-        assert_eq!(Op::new("<"), Ok(("", Op::Lt)));
+        assert_eq!(utils::parse_str(Op::new, "<"), Ok(Op::Lt));
     }
 
     #[test]
     fn parse_gt_operator() {
-        assert_eq!(Op::new(">"), Ok(("", Op::Gt)));
+        assert_eq!(utils::parse_str(Op::new, ">"), Ok(Op::Gt));
     }
 
     #[test]
     fn parse_lte_operator() {
-        assert_eq!(Op::new("<="), Ok(("", Op::LtEq)));
+        assert_eq!(utils::parse_str(Op::new, "<="), Ok(Op::LtEq));
     }
 
     #[test]
     fn parse_gte_operator() {
-        assert_eq!(Op::new(">="), Ok(("", Op::GtEq)));
+        assert_eq!(utils::parse_str(Op::new, ">="), Ok(Op::GtEq));
     }
 
     #[test]
     fn eval_number_equality() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(5))),
-                rhs: Box::new(Expression::Number(Number(5))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
                 op: Op::Eq,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1210,11 +2026,11 @@ mod tests {
     #[test]
     fn eval_number_inequality() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(5))),
-                rhs: Box::new(Expression::Number(Number(3))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(3)))),
                 op: Op::NotEq,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1223,11 +2039,11 @@ mod tests {
     #[test]
     fn eval_number_less_than() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(3))),
-                rhs: Box::new(Expression::Number(Number(5))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(3)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
                 op: Op::Lt,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1236,11 +2052,11 @@ mod tests {
     #[test]
     fn eval_number_greater_than() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(10))),
-                rhs: Box::new(Expression::Number(Number(5))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
                 op: Op::Gt,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1249,11 +2065,11 @@ mod tests {
     #[test]
     fn eval_number_lte() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(5))),
-                rhs: Box::new(Expression::Number(Number(5))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
                 op: Op::LtEq,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1262,11 +2078,11 @@ mod tests {
     #[test]
     fn eval_number_gte() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Number(Number(10))),
-                rhs: Box::new(Expression::Number(Number(5))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
                 op: Op::GtEq,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1275,11 +2091,15 @@ mod tests {
     #[test]
     fn eval_string_equality() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::String(StringLiteral("hello".to_string()))),
-                rhs: Box::new(Expression::String(StringLiteral("hello".to_string()))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::String(StringLiteral(
+                    "hello".to_string()
+                )))),
+                rhs: Box::new(Expression::from(ExpressionKind::String(StringLiteral(
+                    "hello".to_string()
+                )))),
                 op: Op::Eq,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1288,11 +2108,11 @@ mod tests {
     #[test]
     fn eval_bool_equality() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Bool(BoolLiteral(true))),
-                rhs: Box::new(Expression::Bool(BoolLiteral(true))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
                 op: Op::Eq,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1302,22 +2122,22 @@ mod tests {
 
     #[test]
     fn parse_and_operator() {
-        assert_eq!(Op::new("&&"), Ok(("", Op::And)));
+        assert_eq!(utils::parse_str(Op::new, "&&"), Ok(Op::And));
     }
 
     #[test]
     fn parse_or_operator() {
-        assert_eq!(Op::new("||"), Ok(("", Op::Or)));
+        assert_eq!(utils::parse_str(Op::new, "||"), Ok(Op::Or));
     }
 
     #[test]
     fn eval_and_true_true() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Bool(BoolLiteral(true))),
-                rhs: Box::new(Expression::Bool(BoolLiteral(true))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
                 op: Op::And,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1326,11 +2146,11 @@ mod tests {
     #[test]
     fn eval_and_true_false() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Bool(BoolLiteral(true))),
-                rhs: Box::new(Expression::Bool(BoolLiteral(false))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(false)))),
                 op: Op::And,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(false))
         )
@@ -1339,11 +2159,11 @@ mod tests {
     #[test]
     fn eval_or_false_true() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Bool(BoolLiteral(false))),
-                rhs: Box::new(Expression::Bool(BoolLiteral(true))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(false)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
                 op: Op::Or,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1352,11 +2172,11 @@ mod tests {
     #[test]
     fn eval_or_false_false() {
         assert_eq!(
-            Expression::Operation {
-                lhs: Box::new(Expression::Bool(BoolLiteral(false))),
-                rhs: Box::new(Expression::Bool(BoolLiteral(false))),
+            Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(false)))),
+                rhs: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(false)))),
                 op: Op::Or,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(false))
         )
@@ -1367,24 +2187,21 @@ mod tests {
     #[test]
     fn parse_not_operator() {
         assert_eq!(
-            Expression::new("!true"),
-            Ok((
-                "",
-                Expression::UnaryOp {
-                    operand: Box::new(Expression::Bool(BoolLiteral(true))),
-                    op: UnaryOp::Not,
-                }
-            ))
+            utils::parse_str(Expression::new, "!true"),
+            Ok(Expression::from(ExpressionKind::UnaryOp {
+                operand: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
+                op: UnaryOp::Not,
+            }))
         )
     }
 
     #[test]
     fn eval_not_true() {
         assert_eq!(
-            Expression::UnaryOp {
-                operand: Box::new(Expression::Bool(BoolLiteral(true))),
+            Expression::from(ExpressionKind::UnaryOp {
+                operand: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
                 op: UnaryOp::Not,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(false))
         )
@@ -1393,10 +2210,10 @@ mod tests {
     #[test]
     fn eval_not_false() {
         assert_eq!(
-            Expression::UnaryOp {
-                operand: Box::new(Expression::Bool(BoolLiteral(false))),
+            Expression::from(ExpressionKind::UnaryOp {
+                operand: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(false)))),
                 op: UnaryOp::Not,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Bool(true))
         )
@@ -1405,10 +2222,10 @@ mod tests {
     #[test]
     fn eval_negate_number() {
         assert_eq!(
-            Expression::UnaryOp {
-                operand: Box::new(Expression::Number(Number(42))),
+            Expression::from(ExpressionKind::UnaryOp {
+                operand: Box::new(Expression::from(ExpressionKind::Number(Number(42)))),
                 op: UnaryOp::Neg,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Number(-42))
         )
@@ -1417,10 +2234,10 @@ mod tests {
     #[test]
     fn eval_negate_float() {
         assert_eq!(
-            Expression::UnaryOp {
-                operand: Box::new(Expression::Float(Float(2.5))),
+            Expression::from(ExpressionKind::UnaryOp {
+                operand: Box::new(Expression::from(ExpressionKind::Float(Float(2.5)))),
                 op: UnaryOp::Neg,
-            }
+            })
             .eval(&Env::default()),
             Ok(Val::Float(-2.5))
         )
@@ -1431,28 +2248,117 @@ mod tests {
     #[test]
     fn parse_if_expression() {
         assert_eq!(
-            Expression::new("if true { 42 }"),
-            Ok((
-                "",
-                Expression::If(IfExpr {
-                    condition: Box::new(Expression::Bool(BoolLiteral(true))),
-                    then_branch: Box::new(Expression::Block(Block {
-                        statements: vec![Statement::Expression(Expression::Number(Number(42)))]
-                    })),
-                    else_branch: None,
-                })
-            ))
+            utils::parse_str(Expression::new, "if true { 42 }"),
+            Ok(Expression::from(ExpressionKind::If(IfExpr {
+                condition: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
+                then_branch: Box::new(Expression::from(ExpressionKind::Block(Block {
+                    statements: vec![Statement::Expression(Expression::from(
+                        ExpressionKind::Number(Number(42))
+                    ))]
+                }))),
+                else_branch: None,
+            })))
         )
     }
 
     #[test]
     fn parse_if_else_expression() {
-        let result = Expression::new("if false { 1 } else { 2 }");
+        let result = utils::parse_str(Expression::new, "if false { 1 } else { 2 }");
         assert!(result.is_ok());
-        let (remaining, expr) = result.unwrap();
-        assert_eq!(remaining, "");
-        match expr {
-            Expression::If(if_expr) => {
+        match result.unwrap().kind {
+            ExpressionKind::If(if_expr) => {
+                assert!(if_expr.else_branch.is_some());
+            }
+            _ => panic!("Expected If expression"),
+        }
+    }
+
+    #[test]
+    fn eval_if_true() {
+        assert_eq!(
+            Expression::from(ExpressionKind::If(IfExpr {
+                condition: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
+                then_branch: Box::new(Expression::from(ExpressionKind::Block(Block {
+                    statements: vec![Statement::Expression(Expression::from(
+                        ExpressionKind::Number(Number(42))
+                    ))]
+                }))),
+                else_branch: None,
+            }))
+            .eval(&Env::default()),
+            Ok(Val::Opt(Some(Box::new(Val::Number(42)))))
+        )
+    }
+
+    #[test]
+    fn eval_if_false_no_else() {
+        assert_eq!(
+            Expression::from(ExpressionKind::If(IfExpr {
+                condition: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(false)))),
+                then_branch: Box::new(Expression::from(ExpressionKind::Block(Block {
+                    statements: vec![Statement::Expression(Expression::from(
+                        ExpressionKind::Number(Number(42))
+                    ))]
+                }))),
+                else_branch: None,
+            }))
+            .eval(&Env::default()),
+            Ok(Val::Opt(None))
+        )
+    }
+
+    #[test]
+    fn eval_if_else_true() {
+        assert_eq!(
+            Expression::from(ExpressionKind::If(IfExpr {
+                condition: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
+                then_branch: Box::new(Expression::from(ExpressionKind::Block(Block {
+                    statements: vec![Statement::Expression(Expression::from(
+                        ExpressionKind::Number(Number(1))
+                    ))]
+                }))),
+                else_branch: Some(Box::new(Expression::from(ExpressionKind::Block(Block {
+                    statements: vec![Statement::Expression(Expression::from(
+                        ExpressionKind::Number(Number(2))
+                    ))]
+                })))),
+            }))
+            .eval(&Env::default()),
+            Ok(Val::Number(1))
+        )
+    }
+
+    #[test]
+    fn eval_if_else_false() {
+        assert_eq!(
+            Expression::from(ExpressionKind::If(IfExpr {
+                condition: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(false)))),
+                then_branch: Box::new(Expression::from(ExpressionKind::Block(Block {
+                    statements: vec![Statement::Expression(Expression::from(
+                        ExpressionKind::Number(Number(1))
+                    ))]
+                }))),
+                else_branch: Some(Box::new(Expression::from(ExpressionKind::Block(Block {
+                    statements: vec![Statement::Expression(Expression::from(
+                        ExpressionKind::Number(Number(2))
+                    ))]
+                })))),
+            }))
+            .eval(&Env::default()),
+            Ok(Val::Number(2))
+        )
+    }
+
+    #[test]
+    fn parse_inline_if() {
+        let result = utils::parse_str(Expression::new, "if y > 0 ? \"hi\" : \"bye\"").unwrap();
+
+        match result.kind {
+            ExpressionKind::If(if_expr) => {
+                assert!(matches!(
+                    if_expr.condition.kind,
+                    ExpressionKind::Operation { .. }
+                ));
                 assert!(if_expr.else_branch.is_some());
             }
             _ => panic!("Expected If expression"),
@@ -1460,92 +2366,225 @@ mod tests {
     }
 
     #[test]
-    fn eval_if_true() {
-        assert_eq!(
-            Expression::If(IfExpr {
-                condition: Box::new(Expression::Bool(BoolLiteral(true))),
-                then_branch: Box::new(Expression::Block(Block {
-                    statements: vec![Statement::Expression(Expression::Number(Number(42)))]
-                })),
-                else_branch: None,
-            })
-            .eval(&Env::default()),
-            Ok(Val::Number(42))
-        )
+    fn eval_inline_if() {
+        assert_eq!(
+            eval_str("if 1 > 2 ? 10 : if 2 > 1 ? 20 : 30"),
+            Ok(Val::Number(20))
+        );
+        assert_eq!(eval_str("1 + if true ? 2 : 3"), Ok(Val::Number(3)));
+    }
+
+    #[test]
+    fn eval_if_without_else_is_optional() {
+        assert_eq!(
+            eval_str("if true ? 4 * 2"),
+            Ok(Val::Opt(Some(Box::new(Val::Number(8)))))
+        );
+        assert_eq!(eval_str("if false ? 4 * 2"), Ok(Val::Opt(None)));
+        assert_eq!(
+            eval_str("if true { 1 }"),
+            Ok(Val::Opt(Some(Box::new(Val::Number(1)))))
+        );
+        assert_eq!(eval_str("if false { 1 }"), Ok(Val::Opt(None)));
+    }
+
+    // ========== Range Tests ==========
+
+    #[test]
+    fn parse_range_binds_looser_than_arithmetic() {
+        let range = utils::parse_str(Expression::new, "0..n + 1 step 2").unwrap();
+
+        match range.kind {
+            ExpressionKind::Range(range) => {
+                assert!(matches!(
+                    range.end.kind,
+                    ExpressionKind::Operation { op: Op::Add, .. }
+                ));
+                assert!(range.step.is_some());
+                assert!(!range.inclusive);
+            }
+            _ => panic!("Expected Range expression"),
+        }
+        assert_eq!((range.span.start, range.span.end), (0, 15));
+    }
+
+    #[test]
+    fn parse_membership_test_on_range() {
+        let result = utils::parse_str(Expression::new, "x in 1..=9").unwrap();
+
+        match result.kind {
+            ExpressionKind::Operation {
+                op: Op::In, rhs, ..
+            } => {
+                assert!(matches!(rhs.kind, ExpressionKind::Range(_)));
+            }
+            _ => panic!("Expected membership test"),
+        }
+    }
+
+    #[test]
+    fn eval_range() {
+        assert_eq!(
+            eval_str("1..=10 step 3"),
+            Ok(Val::Range(Range::new(1, 10, 3, true)))
+        );
+        assert_eq!(eval_str("len(10..0 step -1)"), Ok(Val::Number(10)));
+        assert_eq!(
+            eval_str("len(range(1000000000000))"),
+            Ok(Val::Number(1000000000000))
+        );
+    }
+
+    #[test]
+    fn eval_membership() {
+        assert_eq!(eval_str("4 in 0..10 step 2"), Ok(Val::Bool(true)));
+        assert_eq!(eval_str("10 in 0..10"), Ok(Val::Bool(false)));
+        assert_eq!(eval_str("10 in 0..=10"), Ok(Val::Bool(true)));
+        assert_eq!(eval_str("2 in [1, 2, 3]"), Ok(Val::Bool(true)));
+        assert_eq!(eval_str("\"ell\" in \"hello\""), Ok(Val::Bool(true)));
+    }
+
+    #[test]
+    fn range_step_cannot_be_zero() {
+        let err = eval_str("0..5 step 1 - 1").unwrap_err();
+
+        assert_eq!(err.message(), "Range step cannot be zero");
+        assert_eq!((err.span().start, err.span().end), (10, 15));
+    }
+
+    #[test]
+    fn range_too_long_for_len() {
+        let huge = "-9223372036854775807..9223372036854775807";
+
+        let err = eval_str(&format!("len({})", huge)).unwrap_err();
+        assert_eq!(err.message(), "Range has more values than an int can count");
+        let err = eval_str(&format!("({}).len()", huge)).unwrap_err();
+        assert!(matches!(err, Error::RangeTooLong { .. }));
+    }
+
+    #[test]
+    fn range_bounds_must_be_ints() {
+        let err = eval_str("0..\"5\"").unwrap_err();
+
+        assert!(matches!(err, Error::TypeMismatch { ref found, .. } if found == "str"));
+    }
+
+    // ========== Index Tests ==========
+
+    #[test]
+    fn parse_chained_index() {
+        let result = utils::parse_str(Expression::new, "-xs[0][1..2]").unwrap();
+        let ExpressionKind::UnaryOp { operand, .. } = result.kind else {
+            panic!("Expected UnaryOp expression");
+        };
+        let ExpressionKind::Index(outer) = operand.kind else {
+            panic!("Expected Index expression");
+        };
+
+        assert!(matches!(outer.index.kind, ExpressionKind::Range(_)));
+        assert!(matches!(outer.target.kind, ExpressionKind::Index(_)));
+        assert_eq!((operand.span.start, operand.span.end), (1, 12));
+    }
+
+    #[test]
+    fn bracket_on_next_line_is_not_an_index() {
+        let tokens = crate::lexer::lex("xs\n[1]").unwrap();
+        let (rest, expr) = Expression::new(&tokens).unwrap();
+
+        assert!(matches!(expr.kind, ExpressionKind::BindingUsage(_)));
+        assert_eq!(rest[0].kind, TokenKind::LBracket);
+    }
+
+    #[test]
+    fn question_mark_propagates_only_without_operand() {
+        let expr = utils::parse_str(Expression::new, "xs.pop()?.len()").unwrap();
+        let ExpressionKind::MethodCall(len) = expr.kind else {
+            panic!("Expected MethodCall expression");
+        };
+        assert!(matches!(len.target.kind, ExpressionKind::Try(_)));
+
+        let expr = utils::parse_str(Expression::new, "if found ? x : none").unwrap();
+        assert!(matches!(expr.kind, ExpressionKind::If(IfExpr { .. })));
+    }
+
+    #[test]
+    fn try_on_top_level_value() {
+        assert_eq!(eval_str("some(3)? + 1"), Ok(Val::Number(4)));
+        assert!(matches!(
+            eval_str("none?"),
+            Err(Error::QuestionOutsideFunction { .. })
+        ));
+        assert!(matches!(
+            eval_str("5?"),
+            Err(Error::TypeMismatch { ref expected, .. }) if expected == "opt or res"
+        ));
     }
 
     #[test]
-    fn eval_if_false_no_else() {
-        assert_eq!(
-            Expression::If(IfExpr {
-                condition: Box::new(Expression::Bool(BoolLiteral(false))),
-                then_branch: Box::new(Expression::Block(Block {
-                    statements: vec![Statement::Expression(Expression::Number(Number(42)))]
-                })),
-                else_branch: None,
-            })
-            .eval(&Env::default()),
-            Ok(Val::Unit)
-        )
+    fn eval_index_and_slice() {
+        assert_eq!(eval_str("[10, 20, 30][-1]"), Ok(Val::Number(30)));
+        assert_eq!(eval_str("\"hello\"[1..=3]"), Ok(Val::String("ell".into())));
+        assert_eq!(eval_str("(0..100 step 10)[3]"), Ok(Val::Number(30)));
     }
 
     #[test]
-    fn eval_if_else_true() {
+    fn index_out_of_bounds_points_at_index() {
+        let err = eval_str("[1, 2][2]").unwrap_err();
+
         assert_eq!(
-            Expression::If(IfExpr {
-                condition: Box::new(Expression::Bool(BoolLiteral(true))),
-                then_branch: Box::new(Expression::Block(Block {
-                    statements: vec![Statement::Expression(Expression::Number(Number(1)))]
-                })),
-                else_branch: Some(Box::new(Expression::Block(Block {
-                    statements: vec![Statement::Expression(Expression::Number(Number(2)))]
-                }))),
-            })
-            .eval(&Env::default()),
-            Ok(Val::Number(1))
-        )
+            err,
+            Error::IndexOutOfBounds {
+                index: 2,
+                len: 2,
+                span: Span {
+                    start: 7,
+                    end: 8,
+                    line: 1,
+                    column: 8,
+                    ..Span::default()
+                },
+            }
+        );
     }
 
     #[test]
-    fn eval_if_else_false() {
-        assert_eq!(
-            Expression::If(IfExpr {
-                condition: Box::new(Expression::Bool(BoolLiteral(false))),
-                then_branch: Box::new(Expression::Block(Block {
-                    statements: vec![Statement::Expression(Expression::Number(Number(1)))]
-                })),
-                else_branch: Some(Box::new(Expression::Block(Block {
-                    statements: vec![Statement::Expression(Expression::Number(Number(2)))]
-                }))),
-            })
-            .eval(&Env::default()),
-            Ok(Val::Number(2))
-        )
+    fn cannot_index_number() {
+        let err = eval_str("5[0]").unwrap_err();
+
+        assert!(matches!(err, Error::TypeMismatch { ref found, .. } if found == "int"));
     }
 
     // ========== While Loop Tests ==========
 
     #[test]
     fn parse_while_loop() {
-        let result = Expression::new("while false { 1 }");
+        let result = utils::parse_str(Expression::new, "while false { 1 }");
         assert!(result.is_ok());
-        let (remaining, expr) = result.unwrap();
-        assert_eq!(remaining, "");
-        match expr {
-            Expression::While(_) => {}
+        match result.unwrap().kind {
+            ExpressionKind::While(_) => {}
             _ => panic!("Expected While expression"),
         }
     }
 
+    #[test]
+    fn parse_loop_with_block_or_parenthesized_body() {
+        for src in ["loop { 1 }", "loop(print(1))", "loop({ 1 })"] {
+            let result = utils::parse_str(Expression::new, src).unwrap();
+            assert!(matches!(result.kind, ExpressionKind::Loop(_)), "{}", src);
+        }
+    }
+
     #[test]
     fn eval_while_false() {
         assert_eq!(
-            Expression::While(WhileLoop {
-                condition: Box::new(Expression::Bool(BoolLiteral(false))),
-                body: Box::new(Expression::Block(Block {
-                    statements: vec![Statement::Expression(Expression::Number(Number(1)))]
-                })),
-            })
+            Expression::from(ExpressionKind::While(WhileLoop {
+                condition: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(false)))),
+                body: Box::new(Expression::from(ExpressionKind::Block(Block {
+                    statements: vec![Statement::Expression(Expression::from(
+                        ExpressionKind::Number(Number(1))
+                    ))]
+                }))),
+            }))
             .eval(&Env::default()),
             Ok(Val::Unit)
         )
@@ -1555,12 +2594,10 @@ mod tests {
 
     #[test]
     fn parse_for_loop() {
-        let result = Expression::new("for i in [1, 2, 3] { i }");
+        let result = utils::parse_str(Expression::new, "for i in [1, 2, 3] { i }");
         assert!(result.is_ok());
-        let (remaining, expr) = result.unwrap();
-        assert_eq!(remaining, "");
-        match expr {
-            Expression::For(_) => {}
+        match result.unwrap().kind {
+            ExpressionKind::For(_) => {}
             _ => panic!("Expected For expression"),
         }
     }
@@ -1568,13 +2605,17 @@ mod tests {
     #[test]
     fn eval_for_loop_empty_list() {
         assert_eq!(
-            Expression::For(ForLoop {
-                var: "i".to_string(),
-                iterable: Box::new(Expression::List(ListLiteral { elements: vec![] })),
-                body: Box::new(Expression::Block(Block {
-                    statements: vec![Statement::Expression(Expression::Number(Number(1)))]
-                })),
-            })
+            Expression::from(ExpressionKind::For(ForLoop {
+                var: Pattern::from("i"),
+                iterable: Box::new(Expression::from(ExpressionKind::List(ListLiteral {
+                    elements: vec![]
+                }))),
+                body: Box::new(Expression::from(ExpressionKind::Block(Block {
+                    statements: vec![Statement::Expression(Expression::from(
+                        ExpressionKind::Number(Number(1))
+                    ))]
+                }))),
+            }))
             .eval(&Env::default()),
             Ok(Val::Unit)
         )
@@ -1582,19 +2623,22 @@ mod tests {
 
     #[test]
     fn eval_for_loop_with_items() {
-        let result = Expression::For(ForLoop {
-            var: "x".to_string(),
-            iterable: Box::new(Expression::List(ListLiteral {
-                elements: vec![Expression::Number(Number(1)), Expression::Number(Number(2))],
-            })),
-            body: Box::new(Expression::Block(Block {
-                statements: vec![Statement::Expression(Expression::BindingUsage(
-                    BindingUsage {
+        let result = Expression::from(ExpressionKind::For(ForLoop {
+            var: Pattern::from("x"),
+            iterable: Box::new(Expression::from(ExpressionKind::List(ListLiteral {
+                elements: vec![
+                    Expression::from(ExpressionKind::Number(Number(1))),
+                    Expression::from(ExpressionKind::Number(Number(2))),
+                ],
+            }))),
+            body: Box::new(Expression::from(ExpressionKind::Block(Block {
+                statements: vec![Statement::Expression(Expression::from(
+                    ExpressionKind::BindingUsage(BindingUsage {
                         name: "x".to_string(),
-                    },
+                    }),
                 ))],
-            })),
-        })
+            }))),
+        }))
         .eval(&Env::default());
 
         // Last iteration returns 2
@@ -1606,49 +2650,46 @@ mod tests {
     #[test]
     fn parse_empty_list() {
         assert_eq!(
-            Expression::new("[]"),
-            Ok(("", Expression::List(ListLiteral { elements: vec![] })))
+            utils::parse_str(Expression::new, "[]"),
+            Ok(Expression::from(ExpressionKind::List(ListLiteral {
+                elements: vec![]
+            })))
         )
     }
 
     #[test]
     fn parse_list_with_numbers() {
         assert_eq!(
-            Expression::new("[1, 2, 3]"),
-            Ok((
-                "",
-                Expression::List(ListLiteral {
-                    elements: vec![
-                        Expression::Number(Number(1)),
-                        Expression::Number(Number(2)),
-                        Expression::Number(Number(3)),
-                    ]
-                })
-            ))
+            utils::parse_str(Expression::new, "[1, 2, 3]"),
+            Ok(Expression::from(ExpressionKind::List(ListLiteral {
+                elements: vec![
+                    Expression::from(ExpressionKind::Number(Number(1))),
+                    Expression::from(ExpressionKind::Number(Number(2))),
+                    Expression::from(ExpressionKind::Number(Number(3))),
+                ]
+            })))
         )
     }
 
     #[test]
     fn parse_list_with_spaces() {
         assert_eq!(
-            Expression::new("[ 1 , 2 , 3 ]"),
-            Ok((
-                "",
-                Expression::List(ListLiteral {
-                    elements: vec![
-                        Expression::Number(Number(1)),
-                        Expression::Number(Number(2)),
-                        Expression::Number(Number(3)),
-                    ]
-                })
-            ))
+            utils::parse_str(Expression::new, "[ 1 , 2 , 3 ]"),
+            Ok(Expression::from(ExpressionKind::List(ListLiteral {
+                elements: vec![
+                    Expression::from(ExpressionKind::Number(Number(1))),
+                    Expression::from(ExpressionKind::Number(Number(2))),
+                    Expression::from(ExpressionKind::Number(Number(3))),
+                ]
+            })))
         )
     }
 
     #[test]
     fn eval_empty_list() {
         assert_eq!(
-            Expression::List(ListLiteral { elements: vec![] }).eval(&Env::default()),
+            Expression::from(ExpressionKind::List(ListLiteral { elements: vec![] }))
+                .eval(&Env::default()),
             Ok(Val::List(vec![]))
         )
     }
@@ -1656,13 +2697,13 @@ mod tests {
     #[test]
     fn eval_list_with_numbers() {
         assert_eq!(
-            Expression::List(ListLiteral {
+            Expression::from(ExpressionKind::List(ListLiteral {
                 elements: vec![
-                    Expression::Number(Number(1)),
-                    Expression::Number(Number(2)),
-                    Expression::Number(Number(3)),
+                    Expression::from(ExpressionKind::Number(Number(1))),
+                    Expression::from(ExpressionKind::Number(Number(2))),
+                    Expression::from(ExpressionKind::Number(Number(3))),
                 ]
-            })
+            }))
             .eval(&Env::default()),
             Ok(Val::List(vec![
                 Val::Number(1),
@@ -1675,13 +2716,13 @@ mod tests {
     #[test]
     fn eval_list_with_mixed_types() {
         assert_eq!(
-            Expression::List(ListLiteral {
+            Expression::from(ExpressionKind::List(ListLiteral {
                 elements: vec![
-                    Expression::Number(Number(42)),
-                    Expression::String(StringLiteral("hello".to_string())),
-                    Expression::Bool(BoolLiteral(true)),
+                    Expression::from(ExpressionKind::Number(Number(42))),
+                    Expression::from(ExpressionKind::String(StringLiteral("hello".to_string()))),
+                    Expression::from(ExpressionKind::Bool(BoolLiteral(true))),
                 ]
-            })
+            }))
             .eval(&Env::default()),
             Ok(Val::List(vec![
                 Val::Number(42),
@@ -1691,16 +2732,69 @@ mod tests {
         )
     }
 
+    // ========== Map Tests ==========
+
+    #[test]
+    fn parse_map_literal() {
+        assert_eq!(
+            utils::parse_str(Expression::new, "[\"a\": 1, \"b\": 2,]"),
+            Ok(Expression::from(ExpressionKind::Map(MapLiteral {
+                entries: vec![
+                    (
+                        Expression::from(ExpressionKind::String(StringLiteral("a".to_string()))),
+                        Expression::from(ExpressionKind::Number(Number(1))),
+                    ),
+                    (
+                        Expression::from(ExpressionKind::String(StringLiteral("b".to_string()))),
+                        Expression::from(ExpressionKind::Number(Number(2))),
+                    ),
+                ]
+            })))
+        );
+        assert_eq!(
+            utils::parse_str(Expression::new, "[:]"),
+            Ok(Expression::from(ExpressionKind::Map(MapLiteral {
+                entries: vec![]
+            })))
+        );
+    }
+
+    #[test]
+    fn parse_map_missing_colon() {
+        assert!(utils::parse_str(Expression::new, "[1: 2, 3]").is_err());
+    }
+
+    #[test]
+    fn eval_map_lookup_and_membership() {
+        assert_eq!(eval_str("[\"a\": 1, \"b\": 2][\"b\"]"), Ok(Val::Number(2)));
+        assert_eq!(eval_str("3 in [3: \"x\"]"), Ok(Val::Bool(true)));
+        assert_eq!(eval_str("len([1: 1, 1: 2])"), Ok(Val::Number(1)));
+    }
+
+    #[test]
+    fn eval_map_missing_key() {
+        let err = eval_str("[\"a\": 1][\"b\"]").unwrap_err();
+
+        assert_eq!(err.message(), "Key \"b\" not found in map");
+        assert_eq!((err.span().start, err.span().end), (9, 12));
+    }
+
+    #[test]
+    fn eval_map_unhashable_key() {
+        assert!(matches!(
+            eval_str("[[1]: 2]"),
+            Err(Error::TypeMismatch { ref found, .. }) if found == "list"
+        ));
+    }
+
     // ========== Function Tests ==========
 
     #[test]
     fn parse_function_call_no_args() {
-        let result = Expression::new("foo()");
+        let result = utils::parse_str(Expression::new, "foo()");
         assert!(result.is_ok());
-        let (remaining, expr) = result.unwrap();
-        assert_eq!(remaining, "");
-        match expr {
-            Expression::FunctionCall(call) => {
+        match result.unwrap().kind {
+            ExpressionKind::FunctionCall(call) => {
                 assert_eq!(call.name, "foo");
                 assert_eq!(call.args.len(), 0);
             }
@@ -1710,12 +2804,10 @@ mod tests {
 
     #[test]
     fn parse_function_call_with_args() {
-        let result = Expression::new("add(1, 2)");
+        let result = utils::parse_str(Expression::new, "add(1, 2)");
         assert!(result.is_ok());
-        let (remaining, expr) = result.unwrap();
-        assert_eq!(remaining, "");
-        match expr {
-            Expression::FunctionCall(call) => {
+        match result.unwrap().kind {
+            ExpressionKind::FunctionCall(call) => {
                 assert_eq!(call.name, "add");
                 assert_eq!(call.args.len(), 2);
             }
@@ -1723,12 +2815,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_function_call_with_named_args() {
+        match utils::parse_str(Expression::new, "greet(\"Kiah\", end = \"?\")")
+            .unwrap()
+            .kind
+        {
+            ExpressionKind::FunctionCall(call) => {
+                assert_eq!(call.args.len(), 1);
+                assert_eq!(call.named.len(), 1);
+                assert_eq!(call.named[0].name, "end");
+            }
+            _ => panic!("Expected FunctionCall"),
+        }
+    }
+
+    #[test]
+    fn named_args_must_come_last_and_once() {
+        let err = utils::parse_str(Expression::new, "f(a = 1, 2)").unwrap_err();
+        assert_eq!(err.message(), "positional argument after named arguments");
+
+        let err = utils::parse_str(Expression::new, "f(a = 1, a = 2)").unwrap_err();
+        assert_eq!(err.message(), "argument `a` is given twice");
+    }
+
     #[test]
     fn eval_function_call_builtin_print() {
-        let result = Expression::FunctionCall(FunctionCall {
+        let result = Expression::from(ExpressionKind::FunctionCall(FunctionCall {
             name: "print".to_string(),
-            args: vec![Expression::Number(Number(42))],
-        })
+            args: vec![Expression::from(ExpressionKind::Number(Number(42)))],
+            named: Vec::new(),
+        }))
         .eval(&Env::default());
 
         assert_eq!(result, Ok(Val::Unit));
@@ -1737,10 +2854,13 @@ mod tests {
     #[test]
     fn eval_function_call_builtin_len_string() {
         assert_eq!(
-            Expression::FunctionCall(FunctionCall {
+            Expression::from(ExpressionKind::FunctionCall(FunctionCall {
                 name: "len".to_string(),
-                args: vec![Expression::String(StringLiteral("hello".to_string()))],
-            })
+                args: vec![Expression::from(ExpressionKind::String(StringLiteral(
+                    "hello".to_string()
+                )))],
+                named: Vec::new(),
+            }))
             .eval(&Env::default()),
             Ok(Val::Number(5))
         )
@@ -1749,16 +2869,17 @@ mod tests {
     #[test]
     fn eval_function_call_builtin_len_list() {
         assert_eq!(
-            Expression::FunctionCall(FunctionCall {
+            Expression::from(ExpressionKind::FunctionCall(FunctionCall {
                 name: "len".to_string(),
-                args: vec![Expression::List(ListLiteral {
+                args: vec![Expression::from(ExpressionKind::List(ListLiteral {
                     elements: vec![
-                        Expression::Number(Number(1)),
-                        Expression::Number(Number(2)),
-                        Expression::Number(Number(3)),
+                        Expression::from(ExpressionKind::Number(Number(1))),
+                        Expression::from(ExpressionKind::Number(Number(2))),
+                        Expression::from(ExpressionKind::Number(Number(3))),
                     ]
-                })],
-            })
+                }))],
+                named: Vec::new(),
+            }))
             .eval(&Env::default()),
             Ok(Val::Number(3))
         )
@@ -1767,70 +2888,75 @@ mod tests {
     #[test]
     fn eval_function_call_builtin_range_one_arg() {
         assert_eq!(
-            Expression::FunctionCall(FunctionCall {
+            Expression::from(ExpressionKind::FunctionCall(FunctionCall {
                 name: "range".to_string(),
-                args: vec![Expression::Number(Number(5))],
-            })
+                args: vec![Expression::from(ExpressionKind::Number(Number(5)))],
+                named: Vec::new(),
+            }))
             .eval(&Env::default()),
-            Ok(Val::List(vec![
-                Val::Number(0),
-                Val::Number(1),
-                Val::Number(2),
-                Val::Number(3),
-                Val::Number(4),
-            ]))
+            Ok(Val::Range(Range::new(0, 5, 1, false)))
         )
     }
 
     #[test]
     fn eval_function_call_builtin_range_two_args() {
         assert_eq!(
-            Expression::FunctionCall(FunctionCall {
+            Expression::from(ExpressionKind::FunctionCall(FunctionCall {
                 name: "range".to_string(),
-                args: vec![Expression::Number(Number(2)), Expression::Number(Number(5))],
-            })
+                args: vec![
+                    Expression::from(ExpressionKind::Number(Number(2))),
+                    Expression::from(ExpressionKind::Number(Number(5)))
+                ],
+                named: Vec::new(),
+            }))
             .eval(&Env::default()),
-            Ok(Val::List(vec![
-                Val::Number(2),
-                Val::Number(3),
-                Val::Number(4),
-            ]))
+            Ok(Val::Range(Range::new(2, 5, 1, false)))
         )
     }
 
     #[test]
     fn eval_user_defined_function() {
-        use crate::function_def::FunctionDef;
+        use crate::function_def::{FunctionDef, Param};
 
         let mut env = Env::default();
 
         // Define: fn double(x) { x + x }
         let func_def = FunctionDef {
             name: "double".to_string(),
-            params: vec!["x".to_string()],
-            body: Box::new(Statement::Expression(Expression::Operation {
-                lhs: Box::new(Expression::BindingUsage(BindingUsage {
-                    name: "x".to_string(),
-                })),
-                rhs: Box::new(Expression::BindingUsage(BindingUsage {
-                    name: "x".to_string(),
-                })),
-                op: Op::Add,
-            })),
+            params: vec![Param::from("x")],
+            ret: None,
+            body: Box::new(Statement::Expression(Expression::from(
+                ExpressionKind::Operation {
+                    lhs: Box::new(Expression::from(ExpressionKind::BindingUsage(
+                        BindingUsage {
+                            name: "x".to_string(),
+                        },
+                    ))),
+                    rhs: Box::new(Expression::from(ExpressionKind::BindingUsage(
+                        BindingUsage {
+                            name: "x".to_string(),
+                        },
+                    ))),
+                    op: Op::Add,
+                },
+            ))),
+            span: Span::default(),
         };
 
         // Store function
         let func_val = Val::Function(crate::val::Function {
             params: func_def.params.clone(),
             body: Rc::new(*func_def.body.clone()),
+            env: env.clone(),
         });
         env.store_binding("double".to_string(), func_val);
 
         // Call: double(21)
-        let result = Expression::FunctionCall(FunctionCall {
+        let result = Expression::from(ExpressionKind::FunctionCall(FunctionCall {
             name: "double".to_string(),
-            args: vec![Expression::Number(Number(21))],
-        })
+            args: vec![Expression::from(ExpressionKind::Number(Number(21)))],
+            named: Vec::new(),
+        }))
         .eval(&env);
 
         assert_eq!(result, Ok(Val::Number(42)));
@@ -1838,39 +2964,49 @@ mod tests {
 
     #[test]
     fn test_function_parameter_binding() {
-        use crate::function_def::FunctionDef;
+        use crate::function_def::{FunctionDef, Param};
 
         let mut env = Env::default();
 
         // Define: fn add(a, b) { a + b }
         let func_def = FunctionDef {
             name: "add".to_string(),
-            params: vec!["a".to_string(), "b".to_string()],
-            body: Box::new(Statement::Expression(Expression::Operation {
-                lhs: Box::new(Expression::BindingUsage(BindingUsage {
-                    name: "a".to_string(),
-                })),
-                rhs: Box::new(Expression::BindingUsage(BindingUsage {
-                    name: "b".to_string(),
-                })),
-                op: Op::Add,
-            })),
+            params: vec![Param::from("a"), Param::from("b")],
+            ret: None,
+            body: Box::new(Statement::Expression(Expression::from(
+                ExpressionKind::Operation {
+                    lhs: Box::new(Expression::from(ExpressionKind::BindingUsage(
+                        BindingUsage {
+                            name: "a".to_string(),
+                        },
+                    ))),
+                    rhs: Box::new(Expression::from(ExpressionKind::BindingUsage(
+                        BindingUsage {
+                            name: "b".to_string(),
+                        },
+                    ))),
+                    op: Op::Add,
+                },
+            ))),
+            span: Span::default(),
         };
 
         let func_val = Val::Function(crate::val::Function {
             params: func_def.params.clone(),
             body: Rc::new(*func_def.body.clone()),
+            env: env.clone(),
         });
         env.store_binding("add".to_string(), func_val);
 
         // Call: add(10, 32)
-        let result = Expression::FunctionCall(FunctionCall {
+        let result = Expression::from(ExpressionKind::FunctionCall(FunctionCall {
             name: "add".to_string(),
             args: vec![
-                Expression::Number(Number(10)),
-                Expression::Number(Number(32)),
+                Expression::from(ExpressionKind::Number(Number(10))),
+                Expression::from(ExpressionKind::Number(Number(32))),
             ],
-        })
+            named: Vec::new(),
+        }))
         .eval(&env);
 
         assert_eq!(result, Ok(Val::Number(42)));
@@ -1904,4 +3040,333 @@ mod tests {
         // Child shadows parent's binding
         assert_eq!(child.get_binding_value_restrict("x"), Ok(Val::Number(20)));
     }
+
+    // ========== Precedence Tests ==========
+
+    fn num(n: i64) -> Box<Expression> {
+        Box::new(Expression::from(ExpressionKind::Number(Number(n))))
+    }
+
+    fn binding(name: &str) -> Box<Expression> {
+        Box::new(Expression::from(ExpressionKind::BindingUsage(
+            BindingUsage {
+                name: name.to_string(),
+            },
+        )))
+    }
+
+    #[test]
+    fn parse_mul_binds_tighter_than_add() {
+        assert_eq!(
+            utils::parse_str(Expression::new, "1 + 2 * 3"),
+            Ok(Expression::from(ExpressionKind::Operation {
+                lhs: num(1),
+                rhs: Box::new(Expression::from(ExpressionKind::Operation {
+                    lhs: num(2),
+                    rhs: num(3),
+                    op: Op::Mul,
+                })),
+                op: Op::Add,
+            }))
+        )
+    }
+
+    #[test]
+    fn parse_same_precedence_is_left_associative() {
+        assert_eq!(
+            utils::parse_str(Expression::new, "a - b - c"),
+            Ok(Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Operation {
+                    lhs: binding("a"),
+                    rhs: binding("b"),
+                    op: Op::Sub,
+                })),
+                rhs: binding("c"),
+                op: Op::Sub,
+            }))
+        )
+    }
+
+    #[test]
+    fn parse_comparisons_bind_tighter_than_logical_operators() {
+        assert_eq!(
+            utils::parse_str(Expression::new, "x > 0 && y < 5 || z"),
+            Ok(Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Operation {
+                    lhs: Box::new(Expression::from(ExpressionKind::Operation {
+                        lhs: binding("x"),
+                        rhs: num(0),
+                        op: Op::Gt,
+                    })),
+                    rhs: Box::new(Expression::from(ExpressionKind::Operation {
+                        lhs: binding("y"),
+                        rhs: num(5),
+                        op: Op::Lt,
+                    })),
+                    op: Op::And,
+                })),
+                rhs: binding("z"),
+                op: Op::Or,
+            }))
+        )
+    }
+
+    #[test]
+    fn parse_unary_binds_tighter_than_binary() {
+        assert_eq!(
+            utils::parse_str(Expression::new, "-a * b"),
+            Ok(Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::UnaryOp {
+                    operand: binding("a"),
+                    op: UnaryOp::Neg,
+                })),
+                rhs: binding("b"),
+                op: Op::Mul,
+            }))
+        )
+    }
+
+    #[test]
+    fn parse_nested_unary_operators() {
+        assert_eq!(
+            utils::parse_str(Expression::new, "!!true"),
+            Ok(Expression::from(ExpressionKind::UnaryOp {
+                operand: Box::new(Expression::from(ExpressionKind::UnaryOp {
+                    operand: Box::new(Expression::from(ExpressionKind::Bool(BoolLiteral(true)))),
+                    op: UnaryOp::Not,
+                })),
+                op: UnaryOp::Not,
+            }))
+        )
+    }
+
+    #[test]
+    fn cannot_parse_operation_with_missing_rhs() {
+        assert!(utils::parse_str(Expression::new, "1 + 2 *").is_err());
+    }
+
+    #[test]
+    fn eval_chained_arithmetic() {
+        let expr = utils::parse_str(Expression::new, "2 + 3 * 4 - 10 / 2").unwrap();
+        assert_eq!(expr.eval(&Env::default()), Ok(Val::Number(9)));
+    }
+
+    #[test]
+    fn eval_if_with_compound_condition() {
+        let expr =
+            utils::parse_str(Expression::new, "if 1 < 2 && 3 >= 3 { 1 } else { 2 }").unwrap();
+        assert_eq!(expr.eval(&Env::default()), Ok(Val::Number(1)));
+    }
+
+    // ========== Grouping Tests ==========
+
+    #[test]
+    fn parse_group_overrides_precedence() {
+        assert_eq!(
+            utils::parse_str(Expression::new, "(a + b) * c"),
+            Ok(Expression::from(ExpressionKind::Operation {
+                lhs: Box::new(Expression::from(ExpressionKind::Group(Box::new(
+                    Expression::from(ExpressionKind::Operation {
+                        lhs: binding("a"),
+                        rhs: binding("b"),
+                        op: Op::Add,
+                    })
+                )))),
+                rhs: binding("c"),
+                op: Op::Mul,
+            }))
+        )
+    }
+
+    #[test]
+    fn parse_nested_groups() {
+        assert_eq!(
+            utils::parse_str(Expression::new, "(( 1 ))"),
+            Ok(Expression::from(ExpressionKind::Group(Box::new(
+                Expression::from(ExpressionKind::Group(num(1)))
+            ))))
+        )
+    }
+
+    #[test]
+    fn parse_unit_literal() {
+        assert_eq!(
+            utils::parse_str(Expression::new, "( )"),
+            Ok(Expression::from(ExpressionKind::Unit))
+        )
+    }
+
+    #[test]
+    fn cannot_parse_unclosed_group() {
+        assert!(utils::parse_str(Expression::new, "(1 + 2").is_err());
+    }
+
+    #[test]
+    fn parse_function_call_with_grouped_args() {
+        match utils::parse_str(Expression::new, "add((1 + 2) * 3, (4))")
+            .unwrap()
+            .kind
+        {
+            ExpressionKind::FunctionCall(call) => assert_eq!(call.args.len(), 2),
+            _ => panic!("Expected FunctionCall"),
+        }
+    }
+
+    #[test]
+    fn eval_group() {
+        let expr = utils::parse_str(Expression::new, "(2 + 3) * -(1 - 5)").unwrap();
+        assert_eq!(expr.eval(&Env::default()), Ok(Val::Number(20)));
+    }
+
+    #[test]
+    fn eval_unit_literal() {
+        assert_eq!(
+            Expression::from(ExpressionKind::Unit).eval(&Env::default()),
+            Ok(Val::Unit)
+        );
+    }
+
+    // ========== Span Tests ==========
+
+    #[test]
+    fn operation_span_covers_both_operands() {
+        let expr = utils::parse_str(Expression::new, "1 +\n  foo * 3").unwrap();
+
+        assert_eq!(
+            expr.span,
+            Span {
+                start: 0,
+                end: 13,
+                line: 1,
+                column: 1,
+                ..Span::default()
+            }
+        );
+
+        let ExpressionKind::Operation { rhs, .. } = &expr.kind else {
+            panic!("Expected Operation");
+        };
+        assert_eq!(
+            rhs.span,
+            Span {
+                start: 6,
+                end: 13,
+                line: 2,
+                column: 3,
+                ..Span::default()
+            }
+        );
+    }
+
+    #[test]
+    fn unary_span_starts_at_operator() {
+        let expr = utils::parse_str(Expression::new, "  -(x)").unwrap();
+
+        assert_eq!(expr.span.start, 2);
+        assert_eq!(expr.span.end, 6);
+    }
+
+    #[test]
+    fn if_span_covers_else_branch() {
+        let expr = utils::parse_str(Expression::new, "if a { 1 } else if b { 2 }").unwrap();
+        assert_eq!(expr.span.end, 26);
+
+        let ExpressionKind::If(if_expr) = &expr.kind else {
+            panic!("Expected If expression");
+        };
+        assert_eq!(if_expr.then_branch.span.start, 5);
+        assert_eq!(if_expr.else_branch.as_ref().unwrap().span.start, 16);
+    }
+
+    #[test]
+    fn fstring_interpolation_spans_point_into_source() {
+        let expr = utils::parse_str(Expression::new, "f\"a {x + 1} b\"").unwrap();
+
+        let ExpressionKind::FString(parts) = &expr.kind else {
+            panic!("Expected f-string");
+        };
+        let FStringPart::Interpolation(inner) = &parts[1] else {
+            panic!("Expected interpolation");
+        };
+        assert_eq!(inner.span.start, 5);
+        assert_eq!(inner.span.end, 10);
+    }
+
+    #[test]
+    fn cannot_parse_empty_fstring_interpolation() {
+        assert!(utils::parse_str(Expression::new, "f\"{}\"").is_err());
+    }
+
+    #[test]
+    fn parse_error_points_at_token() {
+        let err = utils::parse_str(Expression::new, "[1,\n 2 3]").unwrap_err();
+
+        assert_eq!(
+            err.message(),
+            "expected ',' or ']' in list literal, found number `3`"
+        );
+        assert_eq!((err.span().line, err.span().column), (2, 4));
+    }
+
+    // Error Tests
+
+    fn eval_str(s: &str) -> Result<Val, Error> {
+        utils::parse_str(Expression::new, s)?
+            .eval(&Env::default())
+            .map_err(Unwind::into_error)
+    }
+
+    #[test]
+    fn division_by_zero_points_at_operation() {
+        let err = eval_str("1 + 4 / 0").unwrap_err();
+
+        assert!(matches!(err, Error::DivisionByZero { .. }));
+        assert_eq!((err.span().start, err.span().end), (4, 9));
+    }
+
+    #[test]
+    fn unbound_name_gets_span_of_usage() {
+        let err = eval_str("1 + nope").unwrap_err();
+
+        assert_eq!(
+            err,
+            Error::UnboundName {
+                name: "nope".to_string(),
+                span: Span {
+                    start: 4,
+                    end: 8,
+                    line: 1,
+                    column: 5,
+                    ..Span::default()
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn unary_type_error_names_operand_type() {
+        assert!(matches!(
+            eval_str("-true"),
+            Err(Error::InvalidOperand { ref op, ref operand, .. }) if op == "-" && operand == "bool"
+        ));
+    }
+
+    #[test]
+    fn builtin_arity_mismatch() {
+        assert!(matches!(
+            eval_str("len(1, 2)"),
+            Err(Error::ArityMismatch { ref name, expected: 1, found: 2, .. }) if name == "len"
+        ));
+    }
+
+    #[test]
+    fn for_over_non_list_is_type_mismatch() {
+        let err = eval_str("for x in 5 { x }").unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::TypeMismatch { ref expected, ref found, .. } if expected == "list, str, range or map" && found == "int"
+        ));
+        assert_eq!(err.span().start, 9);
+    }
 }
@@ -1,4 +1,6 @@
 use crate::env::Env;
+use crate::error::Error;
+use crate::lexer::Token;
 use crate::utils;
 use crate::val::Val;
 
@@ -8,18 +10,13 @@ pub(crate) struct BindingUsage {
 }
 
 impl BindingUsage {
-    pub(super) fn new(s: &str) -> Result<(&str, Self), String> {
-        let (s, name) = utils::extract_ident(s)?;
+    pub(super) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (tokens, name) = utils::extract_ident(tokens)?;
 
-        Ok((
-            s,
-            Self {
-                name: name.to_string(),
-            },
-        ))
+        Ok((tokens, Self { name }))
     }
 
-    pub(super) fn eval(&self, env: &Env) -> Result<Val, String> {
+    pub(super) fn eval(&self, env: &Env) -> Result<Val, Error> {
         env.get_binding_value_restrict(&self.name)
     }
 }
@@ -27,17 +24,15 @@ impl BindingUsage {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lexer::Span;
 
     #[test]
     fn parse_binding_usage() {
         assert_eq!(
-            BindingUsage::new("abc"),
-            Ok((
-                "",
-                BindingUsage {
-                    name: "abc".to_string(),
-                }
-            ))
+            utils::parse_str(BindingUsage::new, "abc"),
+            Ok(BindingUsage {
+                name: "abc".to_string(),
+            })
         )
     }
 
@@ -65,7 +60,10 @@ mod tests {
                 name: "this_dont_exists".to_string()
             }
             .eval(&empty_env),
-            Err("binding with name 'this_dont_exists' does not exist".to_string())
+            Err(Error::UnboundName {
+                name: "this_dont_exists".to_string(),
+                span: Span::default(),
+            })
         )
     }
 }
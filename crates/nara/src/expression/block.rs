@@ -1,4 +1,11 @@
-use crate::{env::Env, statement::Statement, utils, val::Val};
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    lexer::{Token, TokenKind},
+    statement::Statement,
+    utils,
+    val::Val,
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) struct Block {
@@ -6,27 +13,25 @@ pub(crate) struct Block {
 }
 
 impl Block {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        let s = utils::tag("{", s)?;
-        let (s, _) = utils::extract_whitespace(s);
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::LBrace, tokens)?;
 
-        let (s, statements) = utils::sequence(Statement::new, s)?;
+        let (tokens, statements) = utils::sequence(Statement::new, Statement::Error, tokens);
 
-        let (s, _) = utils::extract_whitespace(s);
-        let s = utils::tag("}", s)?;
+        let tokens = utils::tag(&TokenKind::RBrace, tokens)?;
 
         // optional extract semicolon
-        let (s, _) = utils::extract_semicolon(s);
+        let tokens = utils::extract_semicolon(tokens);
 
-        Ok((s, Block { statements }))
+        Ok((tokens, Block { statements }))
     }
 
-    pub(super) fn eval(&self, env: &Env) -> Result<Val, String> {
+    pub(super) fn eval(&self, env: &Env) -> Result<Val, Unwind> {
         if self.statements.is_empty() {
             return Ok(Val::Unit);
         }
 
-        // Doesn’t compile because Env doesn’t implement Clone.
+        // bindings made inside the block live in a frame of their own
         let mut child_env = env.create_child();
 
         let stmts_except_last = &self.statements[..self.statements.len() - 1];
@@ -37,25 +42,30 @@ impl Block {
         // can unwrap safely because I checked that the block is not empty.
         self.statements.last().unwrap().eval(&mut child_env)
     }
+
+    pub(super) fn collect_errors(&self, errors: &mut Vec<Error>) {
+        for stmt in &self.statements {
+            stmt.collect_errors(errors);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::{Expression, Number, Op};
+    use super::super::{Expression, ExpressionKind, Number, Op};
     use super::*;
     use crate::binding_def::BindingDef;
     use crate::expression::binding_usage::BindingUsage;
+    use crate::lexer::Span;
+    use crate::pattern::Pattern;
 
     #[test]
     fn parse_empty_block() {
         assert_eq!(
-            Block::new("{}"),
-            Ok((
-                "",
-                Block {
-                    statements: Vec::new()
-                }
-            ))
+            utils::parse_str(Block::new, "{}"),
+            Ok(Block {
+                statements: Vec::new()
+            })
         )
     }
 
@@ -73,51 +83,49 @@ mod tests {
     #[test]
     fn parse_empty_block_with_whitespace_adn_semicolon() {
         assert_eq!(
-            Block::new("{    };"),
-            Ok((
-                "",
-                Block {
-                    statements: Vec::new()
-                }
-            ))
+            utils::parse_str(Block::new, "{    };"),
+            Ok(Block {
+                statements: Vec::new()
+            })
         )
     }
 
     #[test]
     fn parse_block_in_one_line() {
         assert_eq!(
-            Block::new("{val one=1;one}"),
-            Ok((
-                "",
-                Block {
-                    statements: vec![
-                        Statement::BindingDef(BindingDef {
-                            name: "one".to_string(),
-                            val: Expression::Number(Number(1)),
-                        }),
-                        Statement::Expression(Expression::BindingUsage(BindingUsage {
+            utils::parse_str(Block::new, "{val one=1;one}"),
+            Ok(Block {
+                statements: vec![
+                    Statement::BindingDef(BindingDef {
+                        pattern: Pattern::from("one"),
+                        mutable: false,
+                        ty: None,
+                        val: Some(Expression::from(ExpressionKind::Number(Number(1)))),
+                        span: Span::default(),
+                    }),
+                    Statement::Expression(Expression::from(ExpressionKind::BindingUsage(
+                        BindingUsage {
                             name: "one".to_string(),
-                        })),
-                    ]
-                }
-            ))
+                        }
+                    ))),
+                ]
+            })
         )
     }
 
     #[test]
     fn parse_block_with_single_statement() {
         assert_eq!(
-            Block::new("{3+5}"),
-            Ok((
-                "",
-                Block {
-                    statements: vec![Statement::Expression(Expression::Operation {
-                        lhs: Box::new(Expression::Number(Number(3))),
-                        rhs: Box::new(Expression::Number(Number(5))),
+            utils::parse_str(Block::new, "{3+5}"),
+            Ok(Block {
+                statements: vec![Statement::Expression(Expression::from(
+                    ExpressionKind::Operation {
+                        lhs: Box::new(Expression::from(ExpressionKind::Number(Number(3)))),
+                        rhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
                         op: Op::Add,
-                    })]
-                }
-            ))
+                    }
+                ))]
+            })
         )
     }
 
@@ -125,11 +133,13 @@ mod tests {
     fn eval_block_with_one_expression() {
         assert_eq!(
             Block {
-                statements: vec![Statement::Expression(Expression::Operation {
-                    lhs: Box::new(Expression::Number(Number(3))),
-                    rhs: Box::new(Expression::Number(Number(5))),
-                    op: Op::Mul,
-                })]
+                statements: vec![Statement::Expression(Expression::from(
+                    ExpressionKind::Operation {
+                        lhs: Box::new(Expression::from(ExpressionKind::Number(Number(3)))),
+                        rhs: Box::new(Expression::from(ExpressionKind::Number(Number(5)))),
+                        op: Op::Mul,
+                    }
+                ))]
             }
             .eval(&Env::default()),
             Ok(Val::Number(15))
@@ -141,12 +151,17 @@ mod tests {
             Block {
                 statements: vec![
                     Statement::BindingDef(BindingDef {
-                        name: "one".to_string(),
-                        val: Expression::Number(Number(1)),
+                        pattern: Pattern::from("one"),
+                        mutable: false,
+                        ty: None,
+                        val: Some(Expression::from(ExpressionKind::Number(Number(1)))),
+                        span: Span::default(),
                     }),
-                    Statement::Expression(Expression::BindingUsage(BindingUsage {
-                        name: "one".to_string(),
-                    })),
+                    Statement::Expression(Expression::from(ExpressionKind::BindingUsage(
+                        BindingUsage {
+                            name: "one".to_string(),
+                        }
+                    ))),
                 ],
             }
             .eval(&Env::default()),
@@ -160,16 +175,25 @@ mod tests {
             Block {
                 statements: vec![
                     Statement::BindingDef(BindingDef {
-                        name: "foo".to_string(),
-                        val: Expression::Number(Number(5)),
+                        pattern: Pattern::from("foo"),
+                        mutable: false,
+                        ty: None,
+                        val: Some(Expression::from(ExpressionKind::Number(Number(5)))),
+                        span: Span::default(),
                     }),
                     Statement::BindingDef(BindingDef {
-                        name: "bar".to_string(),
-                        val: Expression::Number(Number(4)),
+                        pattern: Pattern::from("bar"),
+                        mutable: false,
+                        ty: None,
+                        val: Some(Expression::from(ExpressionKind::Number(Number(4)))),
+                        span: Span::default(),
                     }),
                     Statement::BindingDef(BindingDef {
-                        name: "baz".to_string(),
-                        val: Expression::Number(Number(3)),
+                        pattern: Pattern::from("baz"),
+                        mutable: false,
+                        ty: None,
+                        val: Some(Expression::from(ExpressionKind::Number(Number(3)))),
+                        span: Span::default(),
                     }),
                 ],
             }
@@ -183,13 +207,13 @@ mod tests {
         assert_eq!(
             Block {
                 statements: vec![
-                    Statement::Expression(Expression::Number(Number(100))),
-                    Statement::Expression(Expression::Number(Number(30))),
-                    Statement::Expression(Expression::Operation {
-                        lhs: Box::new(Expression::Number(Number(10))),
-                        rhs: Box::new(Expression::Number(Number(7))),
+                    Statement::Expression(Expression::from(ExpressionKind::Number(Number(100)))),
+                    Statement::Expression(Expression::from(ExpressionKind::Number(Number(30)))),
+                    Statement::Expression(Expression::from(ExpressionKind::Operation {
+                        lhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
+                        rhs: Box::new(Expression::from(ExpressionKind::Number(Number(7)))),
                         op: Op::Sub,
-                    }),
+                    })),
                 ],
             }
             .eval(&Env::default()),
@@ -200,41 +224,49 @@ mod tests {
     #[test]
     fn parse_block_with_multi_statements() {
         assert_eq!(
-            Block::new(
+            utils::parse_str(
+                Block::new,
                 "{
                     val x = 10;
                     val y = x;
                     x
                 }"
             ),
-            Ok((
-                "",
-                Block {
-                    statements: vec![
-                        Statement::BindingDef(BindingDef {
-                            name: "x".to_string(),
-                            val: Expression::Number(Number(10)),
-                        }),
-                        Statement::BindingDef(BindingDef {
-                            name: "y".to_string(),
-                            val: Expression::BindingUsage(BindingUsage {
+            Ok(Block {
+                statements: vec![
+                    Statement::BindingDef(BindingDef {
+                        pattern: Pattern::from("x"),
+                        mutable: false,
+                        ty: None,
+                        val: Some(Expression::from(ExpressionKind::Number(Number(10)))),
+                        span: Span::default(),
+                    }),
+                    Statement::BindingDef(BindingDef {
+                        pattern: Pattern::from("y"),
+                        mutable: false,
+                        ty: None,
+                        val: Some(Expression::from(ExpressionKind::BindingUsage(
+                            BindingUsage {
                                 name: "x".to_string(),
-                            }),
-                        }),
-                        Statement::Expression(Expression::BindingUsage(BindingUsage {
+                            }
+                        ))),
+                        span: Span::default(),
+                    }),
+                    Statement::Expression(Expression::from(ExpressionKind::BindingUsage(
+                        BindingUsage {
                             name: "x".to_string(),
-                        })) // Statement::Expression(Expression::Operation {
-                            //     lhs: Expression::BindingUsage(BindingUsage {
-                            //         name: "x".to_string(),
-                            //     }),
-                            //     rhs: Expression::BindingUsage(BindingUsage {
-                            //         name: "y".to_string(),
-                            //     }),
-                            //     op: Op::Add,
-                            // }),
-                    ],
-                },
-            )),
+                        }
+                    ))) // Statement::Expression(Expression::from(ExpressionKind::Operation {
+                        //     lhs: Expression::from(ExpressionKind::BindingUsage(BindingUsage {
+                        //         name: "x".to_string(),
+                        //     })),
+                        //     rhs: Expression::from(ExpressionKind::BindingUsage(BindingUsage {
+                        //         name: "y".to_string(),
+                        //     })),
+                        //     op: Op::Add,
+                        // })),
+                ],
+            }),
         );
     }
 
@@ -247,14 +279,21 @@ mod tests {
             Block {
                 statements: vec![
                     Statement::BindingDef(BindingDef {
-                        name: "baz".to_string(),
-                        val: Expression::BindingUsage(BindingUsage {
-                            name: "foo".to_string(),
-                        }),
+                        pattern: Pattern::from("baz"),
+                        mutable: false,
+                        ty: None,
+                        val: Some(Expression::from(ExpressionKind::BindingUsage(
+                            BindingUsage {
+                                name: "foo".to_string(),
+                            }
+                        ))),
+                        span: Span::default(),
                     }),
-                    Statement::Expression(Expression::BindingUsage(BindingUsage {
-                        name: "baz".to_string(),
-                    })),
+                    Statement::Expression(Expression::from(ExpressionKind::BindingUsage(
+                        BindingUsage {
+                            name: "baz".to_string(),
+                        }
+                    ))),
                 ],
             }
             .eval(&env),
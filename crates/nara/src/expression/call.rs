@@ -0,0 +1,136 @@
+use super::{Expression, ExpressionKind, FunctionCall, NamedArg};
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    lexer::{Span, Token, TokenKind},
+    utils,
+    val::Val,
+};
+
+// `callee(args)` where the function is the result of another call or an
+// index, `adder(1)(2)` or `handlers[0](event)`
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Call {
+    pub(crate) callee: Box<Expression>,
+    pub(crate) args: Vec<Expression>,
+    pub(crate) named: Vec<NamedArg>,
+}
+
+impl Call {
+    // `(` on the line of a call or index, on a new line it starts a group
+    pub(super) fn follows(tokens: &[Token], callee: &Expression) -> bool {
+        *utils::peek(tokens) == TokenKind::LParen
+            && tokens[0].span.line == callee.span.line
+            && matches!(
+                callee.kind,
+                ExpressionKind::FunctionCall(_)
+                    | ExpressionKind::Call(_)
+                    | ExpressionKind::MethodCall(_)
+                    | ExpressionKind::Index(_)
+            )
+    }
+
+    // `tokens` start at the `(`
+    pub(super) fn new(callee: Expression, tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (tokens, (args, named)) = FunctionCall::new_call_args(tokens)?;
+
+        Ok((
+            tokens,
+            Self {
+                callee: Box::new(callee),
+                args,
+                named,
+            },
+        ))
+    }
+
+    pub(super) fn eval(&self, env: &Env, span: &Span) -> Result<Val, Unwind> {
+        let Val::Function(func) = self.callee.eval(env)? else {
+            return Err(Error::NotCallable {
+                name: self.name(),
+                span: span.clone(),
+            }
+            .into());
+        };
+
+        let mut args = Vec::new();
+        for arg in &self.args {
+            args.push(arg.eval(env)?);
+        }
+
+        let mut named = Vec::new();
+        for arg in &self.named {
+            named.push((arg.name.clone(), arg.val.eval(env)?));
+        }
+
+        func.call(&self.name(), args, named)
+    }
+
+    // how errors name the function, `adder(..)` for `adder(1)(2)`
+    pub(crate) fn name(&self) -> String {
+        describe(&self.callee)
+    }
+}
+
+fn describe(expr: &Expression) -> String {
+    match &expr.kind {
+        ExpressionKind::BindingUsage(usage) => usage.name.clone(),
+        ExpressionKind::FunctionCall(call) => format!("{}(..)", call.name),
+        ExpressionKind::Call(call) => format!("{}(..)", call.name()),
+        ExpressionKind::Index(index) => format!("{}[..]", describe(&index.target)),
+        ExpressionKind::MethodCall(call) => {
+            format!("{}.{}(..)", describe(&call.target), call.name)
+        }
+        ExpressionKind::Field(field) => format!("{}.{}", describe(&field.target), field.name),
+        _ => "..".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str) -> Result<Val, Error> {
+        crate::parse(s).unwrap().eval(&mut Env::default())
+    }
+
+    #[test]
+    fn parse_call_of_call() {
+        let expr = utils::parse_str(Expression::new, "f(1)(2)(x = 3)").unwrap();
+
+        let ExpressionKind::Call(outer) = expr.kind else {
+            panic!("Expected Call expression");
+        };
+        assert_eq!(outer.named[0].name, "x");
+        assert!(matches!(outer.callee.kind, ExpressionKind::Call(_)));
+    }
+
+    #[test]
+    fn paren_on_next_line_is_not_a_call() {
+        let tokens = crate::lexer::lex("f(1)\n(2)").unwrap();
+        let (rest, expr) = Expression::new(&tokens).unwrap();
+
+        assert!(matches!(expr.kind, ExpressionKind::FunctionCall(_)));
+        assert_eq!(rest[0].kind, TokenKind::LParen);
+    }
+
+    #[test]
+    fn call_results_and_items() {
+        assert_eq!(
+            eval("fn adder(a) b -> a + b\nadder(1)(2)"),
+            Ok(Val::Number(3))
+        );
+        assert_eq!(
+            eval("val fs = [x -> x * 2, x -> x + 1]\nfs[0](5) + fs[1](5)"),
+            Ok(Val::Number(16))
+        );
+    }
+
+    #[test]
+    fn call_of_non_function() {
+        let err = eval("val xs = [1]\nxs[0](5)").unwrap_err();
+
+        assert_eq!(err.message(), "'xs[..]' is not a function");
+        assert_eq!(err.span().line, 2);
+    }
+}
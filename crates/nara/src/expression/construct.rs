@@ -0,0 +1,325 @@
+use super::{Expression, FunctionCall};
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    lexer::{Span, Token, TokenKind},
+    utils,
+    val::{Instance, Type, Val},
+};
+
+// `Worker@Other("cook") { name = "Ezra", age = 23 }`. `Worker$ { ... }` and
+// `Worker { ... }` construct the default variant, the field list can be left
+// out when every field has a default value. the payload and the field list
+// have to start on the line of the type name
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Construct {
+    pub(crate) ty: String,
+    pub(crate) variant: Option<String>,
+    pub(crate) payload: Vec<Expression>,
+    pub(crate) fields: Vec<FieldInit>,
+}
+
+// `name = value` in the field list
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct FieldInit {
+    pub(crate) name: String,
+    pub(crate) val: Expression,
+}
+
+impl Construct {
+    // `field_lists` is off in `if` and `while` conditions, `for` iterables and
+    // `match` subjects, where the brace opens the body instead
+    pub(super) fn starts_at(tokens: &[Token], field_lists: bool) -> bool {
+        if !matches!(utils::peek(tokens), TokenKind::Ident(_))
+            || tokens[1].span.line != tokens[0].span.line
+        {
+            return false;
+        }
+
+        match tokens[1].kind {
+            TokenKind::At | TokenKind::Dollar => true,
+            TokenKind::LBrace => field_lists,
+            _ => false,
+        }
+    }
+
+    pub(super) fn new(tokens: &[Token], field_lists: bool) -> Result<(&[Token], Self), Error> {
+        let line = tokens[0].span.line;
+        let (tokens, ty) = utils::extract_ident(tokens)?;
+
+        let (tokens, variant) = match utils::peek(tokens) {
+            TokenKind::At => {
+                let (tokens, name) = utils::extract_ident(&tokens[1..])?;
+                (tokens, Some(name))
+            }
+            TokenKind::Dollar => (&tokens[1..], None),
+            _ => (tokens, None),
+        };
+
+        let on_line =
+            |tokens: &[Token], kind| *utils::peek(tokens) == kind && tokens[0].span.line == line;
+
+        let (tokens, payload) = if variant.is_some() && on_line(tokens, TokenKind::LParen) {
+            FunctionCall::new_args(tokens)?
+        } else {
+            (tokens, Vec::new())
+        };
+
+        let (tokens, fields) = if field_lists && on_line(tokens, TokenKind::LBrace) {
+            Self::new_fields(tokens)?
+        } else {
+            (tokens, Vec::new())
+        };
+
+        Ok((
+            tokens,
+            Self {
+                ty,
+                variant,
+                payload,
+                fields,
+            },
+        ))
+    }
+
+    // `{ name = value, ... }`
+    fn new_fields(tokens: &[Token]) -> Result<(&[Token], Vec<FieldInit>), Error> {
+        let mut tokens = utils::tag(&TokenKind::LBrace, tokens)?;
+        let mut fields: Vec<FieldInit> = Vec::new();
+
+        loop {
+            if let Ok(rest) = utils::tag(&TokenKind::RBrace, tokens) {
+                return Ok((rest, fields));
+            }
+
+            let (rest, name) = utils::extract_ident(tokens)?;
+            if fields.iter().any(|field| field.name == name) {
+                return Err(Error::Syntax {
+                    message: format!("field `{}` is given twice", name),
+                    span: tokens[0].span.clone(),
+                });
+            }
+
+            let rest = utils::tag(&TokenKind::Assign, rest)?;
+            let (rest, val) = Expression::new(rest)?;
+            fields.push(FieldInit { name, val });
+
+            match utils::peek(rest) {
+                TokenKind::Comma => tokens = &rest[1..],
+                TokenKind::RBrace => return Ok((&rest[1..], fields)),
+                _ => return Err(utils::expected("',' or '}' in fields", rest)),
+            }
+        }
+    }
+
+    pub(super) fn eval(&self, env: &Env, span: &Span) -> Result<Val, Unwind> {
+        let ty = match env.get_binding_value_restrict(&self.ty)? {
+            Val::Type(ty) => ty,
+            other => {
+                return Err(Error::TypeMismatch {
+                    expected: "type".to_string(),
+                    found: other.type_name().to_string(),
+                    span: span.clone(),
+                }
+                .into());
+            }
+        };
+
+        let variant = self.variant(&ty, span)?;
+        let expected = variant.map_or(0, |(_, len)| len);
+        if self.payload.len() != expected {
+            return Err(Error::PayloadMismatch {
+                variant: variant.map_or_else(String::new, |(name, _)| name.to_string()),
+                ty: ty.name().to_string(),
+                expected,
+                found: self.payload.len(),
+                span: span.clone(),
+            }
+            .into());
+        }
+
+        let payload = self
+            .payload
+            .iter()
+            .map(|item| item.eval(env))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut given = Vec::new();
+        for FieldInit { name, val } in &self.fields {
+            if ty.def.field(name).is_none() {
+                return Err(Error::NoField {
+                    name: name.clone(),
+                    ty: ty.name().to_string(),
+                    span: val.span.clone(),
+                }
+                .into());
+            }
+            given.push((name, val.eval(env)?));
+        }
+
+        // fields keep the order of the declaration, whatever the order they
+        // were given in
+        let mut fields = Vec::new();
+        for field in &ty.def.fields {
+            let val = match given.iter().position(|(name, _)| **name == field.name) {
+                Some(i) => given.swap_remove(i).1,
+                None => match &field.default {
+                    Some(default) => default.eval(&ty.env.create_child())?,
+                    None => {
+                        return Err(Error::MissingField {
+                            name: field.name.clone(),
+                            ty: ty.name().to_string(),
+                            span: span.clone(),
+                        }
+                        .into());
+                    }
+                },
+            };
+            fields.push((env.intern(&field.name), val));
+        }
+
+        Ok(Val::Instance(Box::new(Instance {
+            ty: env.intern(ty.name()),
+            variant: variant.map(|(name, _)| env.intern(name)),
+            payload,
+            fields,
+        })))
+    }
+
+    // name and payload length of the constructed variant, none for a type
+    // without variants
+    fn variant<'a>(&self, ty: &'a Type, span: &Span) -> Result<Option<(&'a str, usize)>, Error> {
+        let def = &ty.def;
+
+        let variant = match &self.variant {
+            Some(name) => def.variant(name).ok_or_else(|| Error::NoVariant {
+                name: name.clone(),
+                ty: ty.name().to_string(),
+                span: span.clone(),
+            })?,
+            None if def.variants.is_empty() => return Ok(None),
+            None => match def.default_variant {
+                Some(i) => &def.variants[i],
+                None => {
+                    return Err(Error::NoDefaultVariant {
+                        ty: ty.name().to_string(),
+                        span: span.clone(),
+                    });
+                }
+            },
+        };
+
+        Ok(Some((&variant.name, variant.payload.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{ExpressionKind, StringLiteral};
+    use super::*;
+    use crate::statement::Statement;
+
+    const WORKER: &str = "type Worker {
+        @Developer
+        $NoJob
+        @Other(str)
+
+        name: str
+        eyes: int = 2
+    }";
+
+    fn eval(s: &str) -> Result<Val, Error> {
+        let mut env = Env::default();
+        utils::parse_str(Statement::new, WORKER)?
+            .eval(&mut env)
+            .map_err(Unwind::into_error)?;
+
+        utils::parse_str(Expression::new, s)?
+            .eval(&env)
+            .map_err(Unwind::into_error)
+    }
+
+    #[test]
+    fn parse_construct() {
+        assert_eq!(
+            utils::parse_str(
+                Expression::new,
+                "Worker@Other(\"cook\") { name = \"Ezra\" }"
+            )
+            .map(|expr| expr.kind),
+            Ok(ExpressionKind::Construct(Construct {
+                ty: "Worker".to_string(),
+                variant: Some("Other".to_string()),
+                payload: vec![Expression::from(ExpressionKind::String(StringLiteral(
+                    "cook".to_string()
+                )))],
+                fields: vec![FieldInit {
+                    name: "name".to_string(),
+                    val: Expression::from(ExpressionKind::String(StringLiteral(
+                        "Ezra".to_string()
+                    ))),
+                }],
+            }))
+        );
+    }
+
+    #[test]
+    fn eval_construct_with_defaults() {
+        assert_eq!(
+            eval("Worker@Other(\"cook\") { name = \"Ezra\" }").map(|val| val.to_string()),
+            Ok(r#"Worker@Other("cook") { name = "Ezra", eyes = 2 }"#.to_string())
+        );
+        assert_eq!(
+            eval("Worker$ { eyes = 1, name = \"Kiah\" }").map(|val| val.to_string()),
+            Ok(r#"Worker@NoJob { name = "Kiah", eyes = 1 }"#.to_string())
+        );
+    }
+
+    #[test]
+    fn missing_and_unknown_fields() {
+        assert_eq!(
+            eval("Worker@Developer { eyes = 2 }").map_err(|err| err.message()),
+            Err("Missing field 'name' of type Worker".to_string())
+        );
+        assert_eq!(
+            eval("Worker@Developer { name = \"Kiah\", age = 20 }").map_err(|err| err.message()),
+            Err("No field 'age' on type Worker".to_string())
+        );
+    }
+
+    #[test]
+    fn wrong_variant_or_payload() {
+        assert_eq!(
+            eval("Worker@Chef { name = \"Kiah\" }").map_err(|err| err.message()),
+            Err("No variant '@Chef' on type Worker".to_string())
+        );
+        assert_eq!(
+            eval("Worker@Other { name = \"Kiah\" }").map_err(|err| err.message()),
+            Err("Variant '@Other' of type Worker holds 1 value, got 0".to_string())
+        );
+    }
+
+    #[test]
+    fn brace_after_condition_opens_body() {
+        let expr = utils::parse_str(Expression::new, "if worker { 1 }").unwrap();
+        let ExpressionKind::If(if_expr) = expr.kind else {
+            panic!("expected an if expression");
+        };
+
+        assert!(matches!(
+            if_expr.condition.kind,
+            ExpressionKind::BindingUsage(_)
+        ));
+        assert_eq!(
+            eval("if Worker$ { 1 }").map_err(|err| err.message()),
+            Err("Missing field 'name' of type Worker".to_string())
+        );
+    }
+
+    #[test]
+    fn cannot_give_field_twice() {
+        let err = utils::parse_str(Expression::new, "Point { x = 1, x = 2 }").unwrap_err();
+
+        assert_eq!(err.message(), "field `x` is given twice");
+    }
+}
@@ -0,0 +1,360 @@
+use super::Expression;
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    lexer::{Span, Token, TokenKind},
+    utils,
+    val::{Map, Range, Val},
+};
+
+// `target[index]`, or a slice `target[start..end]` when the index is a range.
+// negative indices count from the end
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Index {
+    pub(crate) target: Box<Expression>,
+    pub(crate) index: Box<Expression>,
+}
+
+impl Index {
+    // `[` on the line of the indexed expression, on a new line it starts a
+    // list literal
+    pub(super) fn follows(tokens: &[Token], target: &Expression) -> bool {
+        *utils::peek(tokens) == TokenKind::LBracket && tokens[0].span.line == target.span.line
+    }
+
+    // `tokens` start at the `[`
+    pub(super) fn new(target: Expression, tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::LBracket, tokens)?;
+        let (tokens, index) = Expression::new(tokens)?;
+        let tokens = utils::tag(&TokenKind::RBracket, tokens)?;
+
+        Ok((
+            tokens,
+            Self {
+                target: Box::new(target),
+                index: Box::new(index),
+            },
+        ))
+    }
+
+    pub(super) fn eval(&self, env: &Env) -> Result<Val, Unwind> {
+        let target = self.target.eval(env)?;
+        let index = self.index.eval(env)?;
+
+        Ok(get(&target, &index, &self.index.span)?)
+    }
+}
+
+// element or slice of a list, string or range, or the value of a map's key
+pub(crate) fn get(target: &Val, index: &Val, span: &Span) -> Result<Val, Error> {
+    match (target, index) {
+        (Val::Map(map), key) => {
+            check_key(key, span)?;
+            map.get(key).cloned().ok_or_else(|| missing_key(key, span))
+        }
+        (Val::List(items), Val::Number(i)) => Ok(items[resolve(*i, items.len(), span)?].clone()),
+        (Val::List(items), Val::Range(range)) => Ok(Val::List(
+            slice(range, items.len(), span)?
+                .iter()
+                .map(|i| items[i as usize].clone())
+                .collect(),
+        )),
+        // strings are indexed by character, not by byte
+        (Val::String(s), Val::Number(i)) => {
+            let chars: Vec<char> = s.chars().collect();
+            Ok(Val::Char(chars[resolve(*i, chars.len(), span)?]))
+        }
+        (Val::String(s), Val::Range(range)) => {
+            let chars: Vec<char> = s.chars().collect();
+            let sliced: String = slice(range, chars.len(), span)?
+                .iter()
+                .map(|i| chars[i as usize])
+                .collect();
+            Ok(Val::String(sliced.into()))
+        }
+        (Val::Range(outer), Val::Number(i)) => {
+            let i = resolve(*i, outer.len(), span)?;
+            Ok(Val::Number(outer.get(i).unwrap()))
+        }
+        (Val::Range(outer), Val::Range(range)) => {
+            Ok(slice_range(outer, &slice(range, outer.len(), span)?))
+        }
+        (Val::List(_) | Val::String(_) | Val::Range(_), other) => Err(Error::TypeMismatch {
+            expected: "int or range".to_string(),
+            found: other.type_name().to_string(),
+            span: span.clone(),
+        }),
+        (other, _) => Err(Error::TypeMismatch {
+            expected: "list, str, range or map".to_string(),
+            found: other.type_name().to_string(),
+            span: span.clone(),
+        }),
+    }
+}
+
+// replace the element at the end of `path` inside `target`, where each step
+// of the path is an index into the list or a key of the map found by the
+// previous one. the last key of a path may be new to its map
+pub(crate) fn set(target: &mut Val, path: &[(Val, Span)], val: Val) -> Result<(), Error> {
+    let Some(((index, span), rest)) = path.split_first() else {
+        *target = val;
+        return Ok(());
+    };
+
+    match (target, index) {
+        (Val::Map(map), key) if rest.is_empty() => match map.insert(key.clone(), val) {
+            Ok(_) => Ok(()),
+            Err(key) => Err(key_mismatch(&key, span)),
+        },
+        (Val::Map(map), key) => {
+            check_key(key, span)?;
+            match map.get_mut(key) {
+                Some(inner) => set(inner, rest, val),
+                None => Err(missing_key(key, span)),
+            }
+        }
+        (Val::List(items), Val::Number(i)) => {
+            let i = resolve(*i, items.len(), span)?;
+            set(&mut items[i], rest, val)
+        }
+        (Val::List(_), other) => Err(Error::TypeMismatch {
+            expected: "int".to_string(),
+            found: other.type_name().to_string(),
+            span: span.clone(),
+        }),
+        (other, _) => Err(Error::TypeMismatch {
+            expected: "list or map".to_string(),
+            found: other.type_name().to_string(),
+            span: span.clone(),
+        }),
+    }
+}
+
+pub(super) fn check_key(key: &Val, span: &Span) -> Result<(), Error> {
+    if Map::is_key(key) {
+        Ok(())
+    } else {
+        Err(key_mismatch(key, span))
+    }
+}
+
+pub(super) fn key_mismatch(key: &Val, span: &Span) -> Error {
+    Error::TypeMismatch {
+        expected: Map::KEY_TYPES.to_string(),
+        found: key.type_name().to_string(),
+        span: span.clone(),
+    }
+}
+
+pub(super) fn missing_key(key: &Val, span: &Span) -> Error {
+    Error::MissingKey {
+        key: key.repr(),
+        span: span.clone(),
+    }
+}
+
+// position of `index` in a sequence of `len` items
+pub(super) fn resolve(index: i64, len: usize, span: &Span) -> Result<usize, Error> {
+    match usize::try_from(from_end(index, len)) {
+        Ok(resolved) if resolved < len => Ok(resolved),
+        _ => Err(Error::IndexOutOfBounds {
+            index,
+            len,
+            span: span.clone(),
+        }),
+    }
+}
+
+// a negative index counts from the end. a range can be longer than an int
+// can count, its positions past `i64::MAX` are out of reach
+fn from_end(index: i64, len: usize) -> i64 {
+    if index < 0 {
+        i64::try_from(index as i128 + len as i128).unwrap_or(i64::MAX)
+    } else {
+        index
+    }
+}
+
+// positions picked by a slice range. negative bounds count from the end, the
+// resolved end may be one past the last item. errors name the bound as it
+// was written, the start for a first position outside the sequence and the
+// end for a last one
+fn slice(range: &Range, len: usize, span: &Span) -> Result<Range, Error> {
+    let resolved = Range::new(
+        from_end(range.start, len),
+        from_end(range.end, len),
+        range.step,
+        range.inclusive,
+    );
+
+    let last = resolved.len().checked_sub(1).and_then(|i| resolved.get(i));
+    for (i, written) in [(resolved.get(0), range.start), (last, range.end)] {
+        if let Some(i) = i
+            && !usize::try_from(i).is_ok_and(|i| i < len)
+        {
+            return Err(Error::IndexOutOfBounds {
+                index: written,
+                len,
+                span: span.clone(),
+            });
+        }
+    }
+
+    Ok(resolved)
+}
+
+// the values of `outer` at `positions`, which are in bounds, as a range
+fn slice_range(outer: &Range, positions: &Range) -> Val {
+    let at = |index: usize| outer.get(positions.get(index).unwrap() as usize).unwrap();
+    let len = positions.len();
+    let step = if len > 1 {
+        positions.step.checked_mul(outer.step)
+    } else {
+        Some(1)
+    };
+
+    match (len, step) {
+        (0, _) => Val::Range(Range::new(outer.start, outer.start, 1, false)),
+        // inclusive when the value after the last one is not an int
+        (_, Some(step)) => {
+            let (first, last) = (at(0), at(len - 1));
+            Val::Range(match last.checked_add(step) {
+                Some(end) => Range::new(first, end, step, false),
+                None => Range::new(first, last, step, true),
+            })
+        }
+        // two values further apart than an int can count
+        (_, None) => Val::List((0..len).map(|index| Val::Number(at(index))).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(items: &[i64]) -> Val {
+        Val::List(items.iter().copied().map(Val::Number).collect())
+    }
+
+    fn range(start: i64, end: i64) -> Val {
+        Val::Range(Range::new(start, end, 1, false))
+    }
+
+    #[test]
+    fn get_with_negative_index() {
+        let span = Span::default();
+
+        assert_eq!(
+            get(&list(&[1, 2, 3]), &Val::Number(-1), &span),
+            Ok(Val::Number(3))
+        );
+        assert_eq!(
+            get(&list(&[1, 2, 3]), &Val::Number(-3), &span),
+            Ok(Val::Number(1))
+        );
+    }
+
+    #[test]
+    fn get_out_of_bounds() {
+        let err = get(&list(&[1, 2, 3]), &Val::Number(-4), &Span::default()).unwrap_err();
+
+        assert_eq!(err.message(), "Index -4 is out of bounds for length 3");
+    }
+
+    #[test]
+    fn slice_list_and_string() {
+        let span = Span::default();
+
+        assert_eq!(
+            get(&list(&[1, 2, 3, 4]), &range(1, 3), &span),
+            Ok(list(&[2, 3]))
+        );
+        assert_eq!(
+            get(&list(&[1, 2, 3, 4]), &range(-2, 4), &span),
+            Ok(list(&[3, 4]))
+        );
+        assert_eq!(get(&list(&[1, 2]), &range(1, 1), &span), Ok(list(&[])));
+        assert_eq!(
+            get(&Val::String("héllo".into()), &range(1, 4), &span),
+            Ok(Val::String("éll".into()))
+        );
+        assert!(get(&list(&[1, 2]), &range(0, 3), &span).is_err());
+    }
+
+    #[test]
+    fn slice_out_of_bounds_names_the_written_bound() {
+        let span = Span::default();
+        let err = get(&list(&[1, 2, 3]), &range(0, 10), &span).unwrap_err();
+        assert_eq!(err.message(), "Index 10 is out of bounds for length 3");
+
+        let err = get(&list(&[1, 2, 3]), &range(-5, 2), &span).unwrap_err();
+        assert_eq!(err.message(), "Index -5 is out of bounds for length 3");
+    }
+
+    #[test]
+    fn slice_of_range_is_range() {
+        let span = Span::default();
+        let stepped = Val::Range(Range::new(0, 100, 10, false));
+
+        assert_eq!(
+            get(&range(0, 10), &range(2, 5), &span),
+            Ok(Val::Range(Range::new(2, 5, 1, false)))
+        );
+        assert_eq!(
+            get(&stepped, &Val::Range(Range::new(-1, 0, -3, true)), &span),
+            Ok(Val::Range(Range::new(90, -30, -30, false)))
+        );
+        assert_eq!(
+            get(&range(0, 10), &range(3, 3), &span),
+            Ok(Val::Range(Range::new(0, 0, 1, false)))
+        );
+        assert_eq!(
+            get(
+                &Val::Range(Range::new(0, i64::MAX, 1, true)),
+                &Val::Range(Range::new(-2, -1, 1, true)),
+                &span
+            ),
+            Ok(Val::Range(Range::new(i64::MAX - 1, i64::MAX, 1, true)))
+        );
+    }
+
+    #[test]
+    fn strings_are_indexed_by_character() {
+        assert_eq!(
+            get(
+                &Val::String("añb".into()),
+                &Val::Number(2),
+                &Span::default()
+            ),
+            Ok(Val::Char('b'))
+        );
+    }
+
+    #[test]
+    fn set_adds_missing_key_only_at_the_end() {
+        let span = Span::default();
+        let mut val = Val::Map(Map::default());
+
+        assert_eq!(
+            set(&mut val, &[(Val::Number(1), span.clone())], list(&[])),
+            Ok(())
+        );
+        assert_eq!(get(&val, &Val::Number(1), &span), Ok(list(&[])));
+
+        let path = [(Val::Number(2), span.clone()), (Val::Number(0), span)];
+        let err = set(&mut val, &path, Val::Unit).unwrap_err();
+        assert_eq!(err.message(), "Key 2 not found in map");
+    }
+
+    #[test]
+    fn set_nested_element() {
+        let mut val = Val::List(vec![list(&[1, 2]), list(&[3, 4])]);
+        let path = [
+            (Val::Number(1), Span::default()),
+            (Val::Number(-1), Span::default()),
+        ];
+
+        assert_eq!(set(&mut val, &path, Val::Number(40)), Ok(()));
+        assert_eq!(val, Val::List(vec![list(&[1, 2]), list(&[3, 40])]));
+    }
+}
@@ -0,0 +1,194 @@
+use super::Expression;
+use crate::{
+    env::Env,
+    error::Error,
+    function_def::Param,
+    lexer::{Token, TokenKind},
+    statement::Statement,
+    type_expr::TypeExpr,
+    utils,
+    val::{Function, Val},
+};
+use std::rc::Rc;
+
+// anonymous function, `(a, b: int) -> int: a + b`. the parameters need
+// parentheses when there are none, and when there are several and the lambda
+// is an item of a comma separated list, like a call argument
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Lambda {
+    pub(crate) params: Vec<Param>,
+    pub(crate) ret: Option<TypeExpr>,
+    // kept as a statement so that calling a lambda works like calling a `fn`
+    pub(crate) body: Rc<Statement>,
+}
+
+impl Lambda {
+    // whether the tokens start a lambda, `bare_list` allows several
+    // parameters without parentheses
+    pub(super) fn starts_at(tokens: &[Token], bare_list: bool) -> bool {
+        let params = match utils::peek(tokens) {
+            TokenKind::LParen => utils::extract_params(tokens),
+            TokenKind::Ident(_) => Self::bare_params(tokens, bare_list),
+            _ => return false,
+        };
+
+        matches!(params, Ok((rest, _)) if *utils::peek(rest) == TokenKind::Arrow)
+    }
+
+    pub(super) fn new(tokens: &[Token], bare_list: bool) -> Result<(&[Token], Self), Error> {
+        let (tokens, params) = match utils::peek(tokens) {
+            TokenKind::LParen => utils::extract_params(tokens)?,
+            _ => Self::bare_params(tokens, bare_list)?,
+        };
+
+        let tokens = utils::tag(&TokenKind::Arrow, tokens)?;
+
+        // `-> int: body` declares the return type, `-> body` leaves it out
+        let (tokens, ret) = match TypeExpr::new(tokens) {
+            Ok((rest, ret)) if *utils::peek(rest) == TokenKind::Colon => (&rest[1..], Some(ret)),
+            _ => (tokens, None),
+        };
+
+        let (tokens, body) = Expression::new(tokens)?;
+
+        Ok((
+            tokens,
+            Self {
+                params,
+                ret,
+                body: Rc::new(Statement::Expression(body)),
+            },
+        ))
+    }
+
+    fn bare_params(mut tokens: &[Token], list: bool) -> Result<(&[Token], Vec<Param>), Error> {
+        let mut params = Vec::new();
+
+        loop {
+            let (rest, param) = Param::new(tokens)?;
+            params.push(param);
+
+            match utils::peek(rest) {
+                TokenKind::Comma if list => tokens = &rest[1..],
+                _ => return Ok((rest, params)),
+            }
+        }
+    }
+
+    // a lambda closes over the scope it is evaluated in
+    pub(super) fn eval(&self, env: &Env) -> Val {
+        Val::Function(Function {
+            params: self.params.clone(),
+            body: self.body.clone(),
+            env: env.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::{BindingUsage, ExpressionKind, Number, Op};
+    use crate::lexer::Span;
+    use crate::pattern::Pattern;
+
+    fn binding(name: &str) -> Box<Expression> {
+        Box::new(Expression::from(ExpressionKind::BindingUsage(
+            BindingUsage {
+                name: name.to_string(),
+            },
+        )))
+    }
+
+    fn lambda(s: &str) -> Lambda {
+        utils::parse_str(|tokens| Lambda::new(tokens, true), s).unwrap()
+    }
+
+    #[test]
+    fn parse_lambda_without_params() {
+        assert_eq!(
+            lambda("() -> 1 + 3"),
+            Lambda {
+                params: Vec::new(),
+                ret: None,
+                body: Rc::new(Statement::Expression(Expression::from(
+                    ExpressionKind::Operation {
+                        lhs: Box::new(Expression::from(ExpressionKind::Number(Number(1)))),
+                        rhs: Box::new(Expression::from(ExpressionKind::Number(Number(3)))),
+                        op: Op::Add,
+                    }
+                ))),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_lambda_with_bare_params() {
+        assert_eq!(
+            lambda("a, b -> a * b"),
+            Lambda {
+                params: vec![Param::from("a"), Param::from("b")],
+                ret: None,
+                body: Rc::new(Statement::Expression(Expression::from(
+                    ExpressionKind::Operation {
+                        lhs: binding("a"),
+                        rhs: binding("b"),
+                        op: Op::Mul,
+                    }
+                ))),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_lambda_with_types() {
+        let lambda = lambda("(first: int, second: int) -> int: { first + second }");
+
+        assert_eq!(lambda.params.len(), 2);
+        assert_eq!(lambda.params[1].pattern, Pattern::from("second"));
+        assert!(lambda.params[1].ty.is_some());
+        assert_eq!(
+            lambda.ret,
+            Some(TypeExpr {
+                name: "int".to_string(),
+                args: Vec::new(),
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_lambda_with_typed_bare_params() {
+        let lambda = lambda("param1: int, param2: int -> int: param1 + param2");
+
+        assert_eq!(lambda.params.len(), 2);
+        assert!(lambda.ret.is_some());
+    }
+
+    #[test]
+    fn lambda_body_that_looks_like_a_type_is_not_a_return_type() {
+        let lambda = lambda("x -> f(x)");
+
+        assert_eq!(lambda.ret, None);
+        assert!(matches!(
+            &*lambda.body,
+            Statement::Expression(Expression {
+                kind: ExpressionKind::FunctionCall(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn detect_lambda_start() {
+        let starts =
+            |s: &str, bare_list| Lambda::starts_at(&crate::lexer::lex(s).unwrap(), bare_list);
+
+        assert!(starts("x -> x", false));
+        assert!(starts("(a, b) -> a", false));
+        assert!(starts("a, b -> a", true));
+        assert!(!starts("a, b -> a", false));
+        assert!(!starts("(a + b)", false));
+        assert!(!starts("x", true));
+    }
+}
@@ -0,0 +1,177 @@
+use super::Expression;
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    lexer::{Span, Token, TokenKind},
+    pattern::Pattern,
+    utils,
+    val::Val,
+};
+
+// `match subject { pattern: body ... }`, evaluates the body of the first arm
+// whose pattern fits the subject and whose `if` guard, if any, holds. arms
+// may be separated by commas, which is needed when the next pattern would
+// otherwise continue the body, like a negative number
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Match {
+    pub(crate) subject: Box<Expression>,
+    pub(crate) arms: Vec<MatchArm>,
+}
+
+// `pattern if guard: body`
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct MatchArm {
+    pub(crate) pattern: Pattern,
+    pub(crate) guard: Option<Expression>,
+    pub(crate) body: Expression,
+}
+
+impl Match {
+    pub(super) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::Match, tokens)?;
+        let (tokens, subject) = Expression::new_condition(tokens)?;
+        let mut tokens = utils::tag(&TokenKind::LBrace, tokens)?;
+
+        let mut arms = Vec::new();
+        while *utils::peek(tokens) != TokenKind::RBrace {
+            let (rest, arm) = MatchArm::new(tokens)?;
+            arms.push(arm);
+
+            tokens = utils::tag(&TokenKind::Comma, rest).unwrap_or(rest);
+        }
+        let tokens = utils::tag(&TokenKind::RBrace, tokens)?;
+
+        Ok((
+            tokens,
+            Self {
+                subject: Box::new(subject),
+                arms,
+            },
+        ))
+    }
+
+    pub(super) fn eval(&self, env: &Env, span: &Span) -> Result<Val, Unwind> {
+        let subject = self.subject.eval(env)?;
+
+        for arm in &self.arms {
+            let mut bindings = Vec::new();
+            if !arm.pattern.matches(&subject, &mut bindings) {
+                continue;
+            }
+
+            // the guard already sees the bindings of its pattern
+            let mut arm_env = env.create_child();
+            for (name, val) in bindings {
+                arm_env.store_binding(name, val);
+            }
+
+            if let Some(guard) = &arm.guard
+                && !guard.eval(&arm_env)?.is_truthy()
+            {
+                continue;
+            }
+
+            return arm.body.eval(&arm_env);
+        }
+
+        Err(Error::NoMatch {
+            val: subject.repr(),
+            span: span.clone(),
+        }
+        .into())
+    }
+
+    pub(super) fn collect_errors(&self, errors: &mut Vec<Error>) {
+        self.subject.collect_errors(errors);
+        for arm in &self.arms {
+            if let Some(guard) = &arm.guard {
+                guard.collect_errors(errors);
+            }
+            arm.body.collect_errors(errors);
+        }
+    }
+}
+
+impl MatchArm {
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (tokens, pattern) = Pattern::new(tokens)?;
+
+        let (tokens, guard) = match utils::tag(&TokenKind::If, tokens) {
+            Ok(tokens) => {
+                let (tokens, guard) = Expression::new(tokens)?;
+                (tokens, Some(guard))
+            }
+            Err(_) => (tokens, None),
+        };
+
+        let tokens = utils::tag(&TokenKind::Colon, tokens)?;
+        let (tokens, body) = Expression::new(tokens)?;
+
+        Ok((
+            tokens,
+            Self {
+                pattern,
+                guard,
+                body,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str) -> Result<Val, Error> {
+        utils::parse_str(Expression::new, s)?
+            .eval(&Env::default())
+            .map_err(Unwind::into_error)
+    }
+
+    #[test]
+    fn parse_match_with_guard() {
+        let expr = utils::parse_str(Expression::new, "match x { n if n > 0: n, _: 0 }").unwrap();
+
+        let crate::expression::ExpressionKind::Match(match_expr) = expr.kind else {
+            panic!("expected a match expression");
+        };
+        assert_eq!(match_expr.arms.len(), 2);
+        assert_eq!(match_expr.arms[0].pattern, Pattern::from("n"));
+        assert!(match_expr.arms[0].guard.is_some());
+        assert_eq!(match_expr.arms[1].pattern, Pattern::Wildcard);
+    }
+
+    #[test]
+    fn eval_first_matching_arm() {
+        assert_eq!(
+            eval(
+                "match [1, 2, 3] {
+                    []: 0
+                    [x]: x
+                    [first, ..rest] if first > 5: first
+                    [_, ..rest]: len(rest)
+                }"
+            ),
+            Ok(Val::Number(2))
+        );
+        assert_eq!(
+            eval("match 'b' { 'a': 1, 'b': 2, _: 3 }"),
+            Ok(Val::Number(2))
+        );
+    }
+
+    #[test]
+    fn bindings_stay_in_the_arm() {
+        let err = eval("{ match 1 { x: x }; x }").unwrap_err();
+
+        assert_eq!(err.message(), "binding with name 'x' does not exist");
+    }
+
+    #[test]
+    fn no_arm_matches() {
+        let err = eval("match \"c\" { \"a\": 1, \"b\": 2 }").unwrap_err();
+
+        assert_eq!(err.message(), "No pattern matches \"c\"");
+        assert_eq!(err.kind(), "no_match");
+    }
+}
@@ -0,0 +1,789 @@
+use super::{Expression, ExpressionKind, FunctionCall, NamedArg, index};
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    lexer::{Span, Token, TokenKind},
+    utils,
+    val::{Map, Range, Val},
+};
+
+// `target.name(args)`, calls a method built into the target's type or one
+// attached to it by an `impl`
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct MethodCall {
+    pub(crate) target: Box<Expression>,
+    pub(crate) name: String,
+    pub(crate) args: Vec<Expression>,
+    pub(crate) named: Vec<NamedArg>,
+}
+
+// `target.name`, reads a field of the target. `pair.0` reads the first item
+// of a tuple, `result.ok` and `result.err` the sides of a res as opts
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Field {
+    pub(crate) target: Box<Expression>,
+    pub(crate) name: String,
+}
+
+// `.name` or `.name(args)` after `target`, `tokens` start at the `.`
+pub(super) fn new(
+    target: Expression,
+    tokens: &[Token],
+) -> Result<(&[Token], ExpressionKind), Error> {
+    let tokens = utils::tag(&TokenKind::Dot, tokens)?;
+    let target = Box::new(target);
+
+    if let TokenKind::Number(position) = utils::peek(tokens) {
+        let name = position.to_string();
+        return Ok((&tokens[1..], ExpressionKind::Field(Field { target, name })));
+    }
+
+    let (tokens, name) = utils::extract_ident(tokens)?;
+
+    if *utils::peek(tokens) != TokenKind::LParen {
+        return Ok((tokens, ExpressionKind::Field(Field { target, name })));
+    }
+
+    let (tokens, (args, named)) = FunctionCall::new_call_args(tokens)?;
+
+    Ok((
+        tokens,
+        ExpressionKind::MethodCall(MethodCall {
+            target,
+            name,
+            args,
+            named,
+        }),
+    ))
+}
+
+impl MethodCall {
+    pub(super) fn eval(&self, env: &Env, span: &Span) -> Result<Val, Unwind> {
+        let Some((name, steps)) = place(&self.target) else {
+            let mut target = self.target.eval(env)?;
+            let args = self.eval_args(env)?;
+            let named = self.eval_named(env)?;
+            return call(&mut target, &self.name, args, named, env, span);
+        };
+
+        let mut path = Vec::new();
+        for step in steps {
+            path.push(match step {
+                Step::Index(index) => Part::Index(index.eval(env)?, index.span.clone()),
+                Step::Field(name, span) => Part::Field(name.to_string(), span.clone()),
+            });
+        }
+        let args = self.eval_args(env)?;
+        let named = self.eval_named(env)?;
+
+        // read after the arguments, which may have changed the binding
+        let mut root = env
+            .get_binding_value_restrict(name)
+            .map_err(|err| err.or_span(&self.target.span))?;
+        let mut target = root.clone();
+        for part in &path {
+            target = part.get(&target)?;
+        }
+
+        let writes_back = mutates(&target, &self.name);
+        let result = call(&mut target, &self.name, args, named, env, span)?;
+
+        // lists and maps are values, a built-in method changing one stores it
+        // back into the binding it was read from
+        if writes_back {
+            Part::set(&mut root, &path, target)?;
+            env.assign(name, root).map_err(|err| match err {
+                Error::AssignToImmutable { name, .. } => Error::MutateImmutable {
+                    name,
+                    span: span.clone(),
+                },
+                other => other.or_span(span),
+            })?;
+        }
+        Ok(result)
+    }
+
+    fn eval_args(&self, env: &Env) -> Result<Vec<Val>, Unwind> {
+        self.args.iter().map(|arg| arg.eval(env)).collect()
+    }
+
+    fn eval_named(&self, env: &Env) -> Result<Vec<(String, Val)>, Unwind> {
+        let mut named = Vec::new();
+        for arg in &self.named {
+            named.push((arg.name.clone(), arg.val.eval(env)?));
+        }
+
+        Ok(named)
+    }
+}
+
+impl Field {
+    pub(super) fn eval(&self, env: &Env, span: &Span) -> Result<Val, Unwind> {
+        let target = self.target.eval(env)?;
+        Ok(field(&target, &self.name, span)?)
+    }
+}
+
+fn field(target: &Val, name: &str, span: &Span) -> Result<Val, Error> {
+    if let Val::Tuple(items) = target
+        && let Some(item) = name.parse().ok().and_then(|i: usize| items.get(i))
+    {
+        return Ok(item.clone());
+    }
+    if let Val::Error(caught) = target {
+        match name {
+            "kind" => return Ok(Val::String(caught.kind.clone())),
+            "message" => return Ok(Val::String(caught.message.clone())),
+            _ => {}
+        }
+    }
+    if let Val::Res(res) = target {
+        match (name, res) {
+            ("ok", Ok(val)) | ("err", Err(val)) => return Ok(Val::Opt(Some(val.clone()))),
+            ("ok" | "err", _) => return Ok(Val::Opt(None)),
+            _ => {}
+        }
+    }
+    if let Val::Instance(instance) = target
+        && let Some(val) = instance.field(name)
+    {
+        return Ok(val.clone());
+    }
+
+    Err(Error::NoField {
+        name: name.to_string(),
+        ty: target.type_name().to_string(),
+        span: span.clone(),
+    })
+}
+
+// index or field leading from a binding to the target of a method,
+// `grid[i].cells` takes an index and then a field
+enum Step<'a> {
+    Index(&'a Expression),
+    Field(&'a str, &'a Span),
+}
+
+// step with its index evaluated
+enum Part {
+    Index(Val, Span),
+    Field(String, Span),
+}
+
+impl Part {
+    fn get(&self, target: &Val) -> Result<Val, Error> {
+        match self {
+            Self::Index(index, span) => index::get(target, index, span),
+            Self::Field(name, span) => field(target, name, span),
+        }
+    }
+
+    // replace the value at the end of `path` inside `target`. only tuple
+    // items and instance fields hold a value a method can change, the other
+    // fields are read from a copy
+    fn set(target: &mut Val, path: &[Self], val: Val) -> Result<(), Error> {
+        let Some((part, rest)) = path.split_first() else {
+            *target = val;
+            return Ok(());
+        };
+
+        let mut inner = part.get(target)?;
+        Self::set(&mut inner, rest, val)?;
+
+        match part {
+            Self::Index(index, span) => index::set(target, &[(index.clone(), span.clone())], inner),
+            Self::Field(name, span) => {
+                let slot = match target {
+                    Val::Tuple(items) => name.parse().ok().and_then(|i: usize| items.get_mut(i)),
+                    Val::Instance(instance) => instance.field_mut(name),
+                    _ => None,
+                };
+                let Some(slot) = slot else {
+                    return Err(Error::NoField {
+                        name: name.clone(),
+                        ty: target.type_name().to_string(),
+                        span: span.clone(),
+                    });
+                };
+
+                *slot = inner;
+                Ok(())
+            }
+        }
+    }
+}
+
+// binding, indexes and fields a target like `grid[i].cells` was read from
+fn place(expr: &Expression) -> Option<(&str, Vec<Step<'_>>)> {
+    match &expr.kind {
+        ExpressionKind::BindingUsage(binding_usage) => Some((&binding_usage.name, Vec::new())),
+        ExpressionKind::Index(index) => {
+            let (name, mut steps) = place(&index.target)?;
+            steps.push(Step::Index(&index.index));
+            Some((name, steps))
+        }
+        ExpressionKind::Field(field) => {
+            let (name, mut steps) = place(&field.target)?;
+            steps.push(Step::Field(&field.name, &expr.span));
+            Some((name, steps))
+        }
+        ExpressionKind::Group(inner) => place(inner),
+        _ => None,
+    }
+}
+
+// methods `call` knows for each kind of value, an `impl` can't add a method
+// of the same name
+const BUILT_IN: &[(&str, &[&str])] = &[
+    (
+        "str",
+        &[
+            "len",
+            "upper",
+            "lower",
+            "trim",
+            "contains",
+            "starts_with",
+            "ends_with",
+            "split",
+            "chars",
+            "replace",
+        ],
+    ),
+    ("char", &["code", "to_str"]),
+    ("int", &["to_char"]),
+    (
+        "list",
+        &[
+            "len",
+            "push",
+            "pop",
+            "insert",
+            "remove",
+            "clear",
+            "reverse",
+            "enumerate",
+            "contains",
+            "join",
+            "map",
+            "filter",
+        ],
+    ),
+    (
+        "map",
+        &[
+            "len", "insert", "remove", "get", "contains", "keys", "values", "clear",
+        ],
+    ),
+    ("opt", &["is_some", "is_none", "unwrap", "unwrap_or", "map"]),
+    ("res", &["is_ok", "is_err", "unwrap", "unwrap_or", "map"]),
+    ("range", &["len", "contains"]),
+];
+
+pub(crate) fn is_built_in(kind: &str, name: &str) -> bool {
+    BUILT_IN
+        .iter()
+        .any(|(of, names)| *of == kind && names.contains(&name))
+}
+
+// built-in methods that change the value they are called on. methods from an
+// `impl` get a copy of the value and never change it
+fn mutates(target: &Val, name: &str) -> bool {
+    match target {
+        Val::List(_) => matches!(
+            name,
+            "push" | "pop" | "insert" | "remove" | "clear" | "reverse"
+        ),
+        Val::Map(_) => matches!(name, "insert" | "remove" | "clear"),
+        _ => false,
+    }
+}
+
+// number of values of a range as an int, `i64::MIN..i64::MAX` has too many
+pub(super) fn range_len(range: &Range, span: &Span) -> Result<i64, Error> {
+    i64::try_from(range.len()).map_err(|_| Error::RangeTooLong { span: span.clone() })
+}
+
+// run a built-in method of `target`, or one from an `impl`
+fn call(
+    target: &mut Val,
+    name: &str,
+    args: Vec<Val>,
+    named: Vec<(String, Val)>,
+    env: &Env,
+    span: &Span,
+) -> Result<Val, Unwind> {
+    // built-in methods take their arguments by position only
+    if let Some((param, _)) = named.first()
+        && is_built_in(target.type_name(), name)
+    {
+        return Err(Error::UnknownParam {
+            name: name.to_string(),
+            param: param.clone(),
+            span: span.clone(),
+        }
+        .into());
+    }
+
+    let arity = |expected: usize| {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(Error::ArityMismatch {
+                name: name.to_string(),
+                expected,
+                found: args.len(),
+                span: span.clone(),
+            })
+        }
+    };
+    let mismatch = |expected: &str, found: &Val| Error::TypeMismatch {
+        expected: expected.to_string(),
+        found: found.type_name().to_string(),
+        span: span.clone(),
+    };
+
+    match (target, name) {
+        (Val::String(s), "len") => {
+            arity(0)?;
+            Ok(Val::Number(s.chars().count() as i64))
+        }
+        (Val::String(s), "upper") => {
+            arity(0)?;
+            Ok(Val::String(env.intern(&s.to_uppercase())))
+        }
+        (Val::String(s), "lower") => {
+            arity(0)?;
+            Ok(Val::String(env.intern(&s.to_lowercase())))
+        }
+        (Val::String(s), "trim") => {
+            arity(0)?;
+            Ok(Val::String(env.intern(s.trim())))
+        }
+        (Val::String(s), "contains" | "starts_with" | "ends_with") => {
+            arity(1)?;
+            let Val::String(pattern) = &args[0] else {
+                return Err(mismatch("str", &args[0]).into());
+            };
+
+            Ok(Val::Bool(match name {
+                "contains" => s.contains(&**pattern),
+                "starts_with" => s.starts_with(&**pattern),
+                _ => s.ends_with(&**pattern),
+            }))
+        }
+        (Val::String(s), "split") => {
+            arity(1)?;
+            let Val::String(separator) = &args[0] else {
+                return Err(mismatch("str", &args[0]).into());
+            };
+
+            Ok(Val::List(
+                s.split(&**separator)
+                    .map(|part| Val::String(env.intern(part)))
+                    .collect(),
+            ))
+        }
+        (Val::String(s), "chars") => {
+            arity(0)?;
+            Ok(Val::List(s.chars().map(Val::Char).collect()))
+        }
+        (Val::String(s), "replace") => {
+            arity(2)?;
+            match (&args[0], &args[1]) {
+                (Val::String(from), Val::String(to)) => {
+                    Ok(Val::String(env.intern(&s.replace(&**from, to))))
+                }
+                (Val::String(_), other) | (other, _) => Err(mismatch("str", other).into()),
+            }
+        }
+
+        (Val::Char(c), "code") => {
+            arity(0)?;
+            Ok(Val::Number(*c as i64))
+        }
+        (Val::Char(c), "to_str") => {
+            arity(0)?;
+            Ok(Val::String(env.intern(&c.to_string())))
+        }
+        // not every number is a code point, like a surrogate or a negative one
+        (Val::Number(n), "to_char") => {
+            arity(0)?;
+            let c = u32::try_from(*n).ok().and_then(char::from_u32);
+            Ok(Val::Opt(c.map(|c| Box::new(Val::Char(c)))))
+        }
+
+        (Val::List(items), "len") => {
+            arity(0)?;
+            Ok(Val::Number(items.len() as i64))
+        }
+        (Val::List(items), "push") => {
+            arity(1)?;
+            items.extend(args);
+            Ok(Val::Unit)
+        }
+        (Val::List(items), "pop") => {
+            arity(0)?;
+            Ok(Val::Opt(items.pop().map(Box::new)))
+        }
+        (Val::List(items), "insert") => {
+            arity(2)?;
+            let mut args = args.into_iter();
+            let (index, val) = (args.next().unwrap(), args.next().unwrap());
+            let Val::Number(i) = index else {
+                return Err(mismatch("int", &index).into());
+            };
+
+            // inserting right after the last item appends
+            let i = if i == items.len() as i64 {
+                items.len()
+            } else {
+                index::resolve(i, items.len(), span)?
+            };
+            items.insert(i, val);
+            Ok(Val::Unit)
+        }
+        (Val::List(items), "remove") => {
+            arity(1)?;
+            let Val::Number(i) = args[0] else {
+                return Err(mismatch("int", &args[0]).into());
+            };
+
+            Ok(items.remove(index::resolve(i, items.len(), span)?))
+        }
+        (Val::List(items), "clear") => {
+            arity(0)?;
+            items.clear();
+            Ok(Val::Unit)
+        }
+        (Val::List(items), "reverse") => {
+            arity(0)?;
+            items.reverse();
+            Ok(Val::Unit)
+        }
+        (Val::List(items), "enumerate") => {
+            arity(0)?;
+            Ok(Val::List(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| Val::Tuple(vec![Val::Number(i as i64), item.clone()]))
+                    .collect(),
+            ))
+        }
+        (Val::List(items), "contains") => {
+            arity(1)?;
+            Ok(Val::Bool(items.contains(&args[0])))
+        }
+        (Val::List(items), "join") => {
+            arity(1)?;
+            let Val::String(separator) = &args[0] else {
+                return Err(mismatch("str", &args[0]).into());
+            };
+
+            let parts: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+            Ok(Val::String(env.intern(&parts.join(separator))))
+        }
+        (Val::List(items), "map" | "filter") => {
+            arity(1)?;
+            let Val::Function(func) = &args[0] else {
+                return Err(mismatch("fn", &args[0]).into());
+            };
+            func.check_arity(name, 1, span)?;
+
+            let mut result = Vec::new();
+            for item in items.iter() {
+                let val = func.call(name, vec![item.clone()], Vec::new())?;
+
+                if name == "map" {
+                    result.push(val);
+                } else if val.is_truthy() {
+                    result.push(item.clone());
+                }
+            }
+            Ok(Val::List(result))
+        }
+
+        (Val::Map(map), "len") => {
+            arity(0)?;
+            Ok(Val::Number(map.len() as i64))
+        }
+        (Val::Map(map), "insert") => {
+            arity(2)?;
+            let mut args = args.into_iter();
+            let (key, val) = (args.next().unwrap(), args.next().unwrap());
+
+            match map.insert(key, val) {
+                Ok(previous) => Ok(Val::Opt(previous.map(Box::new))),
+                Err(key) => Err(index::key_mismatch(&key, span).into()),
+            }
+        }
+        (Val::Map(map), "remove" | "get") => {
+            arity(1)?;
+            index::check_key(&args[0], span)?;
+
+            let val = if name == "remove" {
+                map.remove(&args[0])
+            } else {
+                map.get(&args[0]).cloned()
+            };
+            Ok(Val::Opt(val.map(Box::new)))
+        }
+        (Val::Map(map), "contains") => {
+            arity(1)?;
+            Ok(Val::Bool(map.contains_key(&args[0])))
+        }
+        (Val::Map(map), "keys") => {
+            arity(0)?;
+            Ok(Val::List(map.keys().cloned().collect()))
+        }
+        (Val::Map(map), "values") => {
+            arity(0)?;
+            Ok(Val::List(map.values().cloned().collect()))
+        }
+        (Val::Map(map), "clear") => {
+            arity(0)?;
+            *map = Map::default();
+            Ok(Val::Unit)
+        }
+
+        (Val::Opt(opt), "is_some" | "is_none") => {
+            arity(0)?;
+            Ok(Val::Bool(opt.is_some() == (name == "is_some")))
+        }
+        (Val::Res(res), "is_ok" | "is_err") => {
+            arity(0)?;
+            Ok(Val::Bool(res.is_ok() == (name == "is_ok")))
+        }
+        (Val::Opt(Some(val)) | Val::Res(Ok(val)), "unwrap") => {
+            arity(0)?;
+            Ok((**val).clone())
+        }
+        (target @ (Val::Opt(None) | Val::Res(Err(_))), "unwrap") => {
+            arity(0)?;
+            Err(Error::UnwrapFailed {
+                val: target.repr(),
+                span: span.clone(),
+            }
+            .into())
+        }
+        (Val::Opt(Some(val)) | Val::Res(Ok(val)), "unwrap_or") => {
+            arity(1)?;
+            Ok((**val).clone())
+        }
+        (Val::Opt(None) | Val::Res(Err(_)), "unwrap_or") => {
+            arity(1)?;
+            Ok(args.into_iter().next().unwrap())
+        }
+        // `none` and `err` are passed through unchanged
+        (target @ (Val::Opt(_) | Val::Res(_)), "map") => {
+            arity(1)?;
+            let Val::Function(func) = &args[0] else {
+                return Err(mismatch("fn", &args[0]).into());
+            };
+            func.check_arity(name, 1, span)?;
+
+            Ok(match target {
+                Val::Opt(Some(val)) => Val::Opt(Some(Box::new(func.call(
+                    name,
+                    vec![(**val).clone()],
+                    Vec::new(),
+                )?))),
+                Val::Res(Ok(val)) => Val::Res(Ok(Box::new(func.call(
+                    name,
+                    vec![(**val).clone()],
+                    Vec::new(),
+                )?))),
+                other => other.clone(),
+            })
+        }
+
+        (Val::Range(range), "len") => {
+            arity(0)?;
+            Ok(Val::Number(range_len(range, span)?))
+        }
+        (Val::Range(range), "contains") => {
+            arity(1)?;
+            let Val::Number(n) = args[0] else {
+                return Err(mismatch("int", &args[0]).into());
+            };
+
+            Ok(Val::Bool(range.contains(n)))
+        }
+
+        // a method from an `impl` gets the value as its first argument
+        (target, _) => match env.get_method(target.type_name(), name) {
+            Some(method) => {
+                let mut args = args;
+                args.insert(0, target.clone());
+                method.call(name, args, named)
+            }
+            None => Err(Error::NoMethod {
+                name: name.to_string(),
+                ty: target.type_name().to_string(),
+                span: span.clone(),
+            }
+            .into()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str) -> Result<Val, Error> {
+        utils::parse_str(Expression::new, s)?
+            .eval(&Env::default())
+            .map_err(Unwind::into_error)
+    }
+
+    #[test]
+    fn parse_method_call_chain() {
+        let expr = utils::parse_str(Expression::new, "xs.map(x -> x * 2).len()").unwrap();
+
+        let ExpressionKind::MethodCall(len) = expr.kind else {
+            panic!("Expected MethodCall expression");
+        };
+        assert_eq!(len.name, "len");
+        assert!(len.args.is_empty());
+
+        let ExpressionKind::MethodCall(map) = len.target.kind else {
+            panic!("Expected MethodCall expression");
+        };
+        assert_eq!(map.name, "map");
+        assert_eq!(map.args.len(), 1);
+    }
+
+    #[test]
+    fn parse_method_call_with_named_args() {
+        let expr = utils::parse_str(Expression::new, "1.a(2, x = 5)").unwrap();
+
+        let ExpressionKind::MethodCall(call) = expr.kind else {
+            panic!("Expected MethodCall expression");
+        };
+        assert_eq!(call.args.len(), 1);
+        assert_eq!(call.named[0].name, "x");
+    }
+
+    #[test]
+    fn parse_field_access() {
+        let expr = utils::parse_str(Expression::new, "worker.name").unwrap();
+
+        assert!(matches!(
+            expr.kind,
+            ExpressionKind::Field(Field { ref name, .. }) if name == "name"
+        ));
+    }
+
+    #[test]
+    fn tuple_positions() {
+        assert_eq!(eval("$(1, $(2, 3)).1.0"), Ok(Val::Number(2)));
+        assert_eq!(
+            eval("$(1, 2).2").unwrap_err().message(),
+            "No field '2' on type tuple"
+        );
+    }
+
+    #[test]
+    fn opt_and_res_methods() {
+        assert_eq!(
+            eval("some(2).map(x -> x * 10).unwrap()"),
+            Ok(Val::Number(20))
+        );
+        assert_eq!(eval("none.map(x -> x * 10)"), Ok(Val::Opt(None)));
+        assert_eq!(eval("err(1).unwrap_or(5)"), Ok(Val::Number(5)));
+        assert_eq!(eval("ok(1).is_err()"), Ok(Val::Bool(false)));
+        assert_eq!(
+            eval("err(\"bad\").err"),
+            Ok(Val::Opt(Some(Box::new(Val::String("bad".into())))))
+        );
+        assert_eq!(eval("err(\"bad\").ok"), Ok(Val::Opt(None)));
+    }
+
+    #[test]
+    fn unwrap_failure() {
+        let err = eval("err(\"bad\").unwrap()").unwrap_err();
+
+        assert_eq!(err.message(), "Called `unwrap` on err(\"bad\")");
+        assert_eq!((err.span().start, err.span().end), (0, 19));
+    }
+
+    #[test]
+    fn string_methods() {
+        assert_eq!(eval("\"añb\".len()"), Ok(Val::Number(3)));
+        assert_eq!(
+            eval("\" Hi \".trim().upper()"),
+            Ok(Val::String("HI".into()))
+        );
+        assert_eq!(
+            eval("\"a,b\".split(\",\")"),
+            Ok(Val::List(vec![
+                Val::String("a".into()),
+                Val::String("b".into())
+            ]))
+        );
+        assert_eq!(eval("\"hello\".starts_with(\"he\")"), Ok(Val::Bool(true)));
+    }
+
+    #[test]
+    fn char_conversions() {
+        assert_eq!(eval("'a'.code()"), Ok(Val::Number(97)));
+        assert_eq!(
+            eval("97.to_char()"),
+            Ok(Val::Opt(Some(Box::new(Val::Char('a')))))
+        );
+        assert_eq!(eval("(-1).to_char()"), Ok(Val::Opt(None)));
+        assert_eq!(eval("'é'.to_str() + \"!\""), Ok(Val::String("é!".into())));
+        assert_eq!(
+            eval("\"hé\".chars()"),
+            Ok(Val::List(vec![Val::Char('h'), Val::Char('é')]))
+        );
+    }
+
+    #[test]
+    fn list_methods_on_temporaries() {
+        assert_eq!(
+            eval("[1, 2, 3].filter(x -> x > 1).map(x -> x * 10)"),
+            Ok(Val::List(vec![Val::Number(20), Val::Number(30)]))
+        );
+        assert_eq!(eval("[1, 2].join(\"-\")"), Ok(Val::String("1-2".into())));
+        assert_eq!(eval("[].pop()"), Ok(Val::Opt(None)));
+    }
+
+    #[test]
+    fn map_methods_on_temporaries() {
+        assert_eq!(
+            eval("[\"a\": 1].get(\"a\")"),
+            Ok(Val::Opt(Some(Box::new(Val::Number(1)))))
+        );
+        assert_eq!(eval("[\"a\": 1].get(\"b\")"), Ok(Val::Opt(None)));
+        assert_eq!(
+            eval("[2: \"x\", 1: \"y\"].keys()"),
+            Ok(Val::List(vec![Val::Number(2), Val::Number(1)]))
+        );
+        assert_eq!(eval("[true: 0].contains(false)"), Ok(Val::Bool(false)));
+    }
+
+    #[test]
+    fn unknown_method() {
+        let err = eval("5.len()").unwrap_err();
+
+        assert_eq!(err.message(), "No method 'len' on type int");
+        assert_eq!((err.span().start, err.span().end), (0, 7));
+    }
+
+    #[test]
+    fn built_in_methods_take_no_named_args() {
+        let err = eval("[1].push(item = 2)").unwrap_err();
+
+        assert_eq!(err.message(), "Function 'push' has no parameter 'item'");
+    }
+
+    #[test]
+    fn method_arity_mismatch() {
+        let err = eval("[1].push()").unwrap_err();
+
+        assert_eq!(err.message(), "Function 'push' expects 1 argument, got 0");
+    }
+}
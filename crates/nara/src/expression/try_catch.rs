@@ -0,0 +1,126 @@
+use super::Expression;
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    lexer::{Token, TokenKind},
+    utils,
+    val::{Caught, Val},
+};
+
+// `try { ... } catch name { ... }`. a runtime error raised in the first
+// block, an `err` it evaluates to or one left by `?` inside it, runs the
+// second block with the error bound to `name`. an `ok` it evaluates to is
+// unwrapped
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct TryCatch {
+    pub(crate) body: Box<Expression>,
+    pub(crate) name: String,
+    pub(crate) handler: Box<Expression>,
+}
+
+impl TryCatch {
+    pub(super) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::Try, tokens)?;
+        let (tokens, body) = Expression::new_block(tokens)?;
+
+        let tokens = utils::tag(&TokenKind::Catch, tokens)?;
+        let (tokens, name) = utils::extract_ident(tokens)?;
+        let (tokens, handler) = Expression::new_block(tokens)?;
+
+        Ok((
+            tokens,
+            Self {
+                body: Box::new(body),
+                name,
+                handler: Box::new(handler),
+            },
+        ))
+    }
+
+    pub(super) fn eval(&self, env: &Env) -> Result<Val, Unwind> {
+        let caught = match self.body.eval(env) {
+            Ok(Val::Res(Ok(val))) => return Ok(*val),
+            Ok(Val::Res(Err(val))) | Err(Unwind::Propagate(Val::Res(Err(val)), _)) => Caught {
+                kind: env.intern("err"),
+                message: env.intern(&val.to_string()),
+            },
+            Err(Unwind::Error(err)) => Caught {
+                kind: env.intern(err.kind()),
+                message: env.intern(&err.message()),
+            },
+            // `return`, `break`, `continue` and a `none` left by `?` pass
+            // through to their targets
+            other => return other,
+        };
+
+        let mut handler_env = env.create_child();
+        handler_env.store_binding(self.name.clone(), Val::Error(caught));
+        self.handler.eval(&handler_env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str) -> Result<Val, Error> {
+        utils::parse_str(Expression::new, s)?
+            .eval(&Env::default())
+            .map_err(Unwind::into_error)
+    }
+
+    #[test]
+    fn parse_try_catch() {
+        let expr = utils::parse_str(Expression::new, "try { 1 / 0 } catch e { e.kind }").unwrap();
+
+        assert!(matches!(
+            expr.kind,
+            crate::expression::ExpressionKind::TryCatch(TryCatch { ref name, .. }) if name == "e"
+        ));
+    }
+
+    #[test]
+    fn catch_runtime_error() {
+        assert_eq!(
+            eval("try { 1 / 0 } catch e { f\"{e.kind}: {e.message}\" }"),
+            Ok(Val::String("division_by_zero: Division by zero".into()))
+        );
+    }
+
+    #[test]
+    fn catch_err_result() {
+        assert_eq!(
+            eval("try { err(\"too small\") } catch e { e.message }"),
+            Ok(Val::String("too small".into()))
+        );
+        assert_eq!(eval("try { ok(3) } catch e { 0 }"), Ok(Val::Number(3)));
+        assert_eq!(eval("try { 4 } catch e { 0 }"), Ok(Val::Number(4)));
+    }
+
+    #[test]
+    fn catch_err_left_by_question_mark() {
+        assert_eq!(
+            eval("try { err(\"x\")? } catch e { 0 }"),
+            Ok(Val::Number(0))
+        );
+        assert_eq!(
+            crate::parse("fn f() { try { err(\"x\")? } catch e { \"caught\" } }\nf()")
+                .unwrap()
+                .eval(&mut Env::default()),
+            Ok(Val::String("caught".into()))
+        );
+        assert_eq!(
+            crate::parse("fn f() { try { return err(\"x\") } catch e { 0 } }\nf()")
+                .unwrap()
+                .eval(&mut Env::default()),
+            Ok(Val::Res(Err(Box::new(Val::String("x".into())))))
+        );
+    }
+
+    #[test]
+    fn error_in_handler_is_not_caught() {
+        let err = eval("try { missing } catch e { e.nope }").unwrap_err();
+
+        assert_eq!(err.message(), "No field 'nope' on type error");
+    }
+}
@@ -1,29 +1,110 @@
-use crate::{statement::Statement, utils};
+use crate::{
+    error::Error,
+    expression::Expression,
+    lexer::{Span, Token, TokenKind},
+    pattern::Pattern,
+    statement::Statement,
+    type_expr::TypeExpr,
+    utils,
+};
 
-#[derive(Debug, PartialEq, Clone)]
+// parameter of a function or lambda, with an optional type annotation. the
+// argument may be taken apart, like `(a, b)` in `fn add((a, b))`. a default
+// value, `(sep = ", ")`, is evaluated at each call leaving the argument out,
+// after the parameters before it are bound
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Param {
+    pub(crate) pattern: Pattern,
+    pub(crate) ty: Option<TypeExpr>,
+    pub(crate) default: Option<Expression>,
+}
+
+impl Param {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (tokens, pattern) = Pattern::new(tokens)?;
+
+        let (tokens, ty) = match utils::tag(&TokenKind::Colon, tokens) {
+            Ok(tokens) => TypeExpr::new(tokens).map(|(tokens, ty)| (tokens, Some(ty)))?,
+            Err(_) => (tokens, None),
+        };
+
+        Ok((
+            tokens,
+            Self {
+                pattern,
+                ty,
+                default: None,
+            },
+        ))
+    }
+
+    // name an argument can be passed by, parameters taking it apart have none
+    pub(crate) fn name(&self) -> Option<&str> {
+        match &self.pattern {
+            Pattern::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+// untyped parameter, for trees built in tests
+#[cfg(test)]
+impl From<&str> for Param {
+    fn from(name: &str) -> Self {
+        Self {
+            pattern: Pattern::from(name),
+            ty: None,
+            default: None,
+        }
+    }
+}
+
+// `fn name(params): ret body`
+#[derive(Debug, Clone)]
 pub(crate) struct FunctionDef {
     pub(crate) name: String,
-    pub(crate) params: Vec<String>,
+    pub(crate) params: Vec<Param>,
+    pub(crate) ret: Option<TypeExpr>,
     pub(crate) body: Box<Statement>,
+    // not read by the interpreter yet, kept for diagnostics and tooling
+    #[allow(dead_code)]
+    pub(crate) span: Span,
+}
+
+// spans are not part of a function's identity, see `Expression`
+impl PartialEq for FunctionDef {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.params == other.params
+            && self.ret == other.ret
+            && self.body == other.body
+    }
 }
 
 impl FunctionDef {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        let s = utils::tag("fn", s)?;
-        let (s, _) = utils::extract_whitespace_restrict(s)?;
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let tokens = utils::tag(&TokenKind::Fn, tokens)?;
+
+        let (tokens, name) = utils::extract_ident(tokens)?;
 
-        let (s, name) = utils::extract_ident(s)?;
+        let (tokens, params) = utils::extract_params(tokens)?;
 
-        let (s, params) = utils::extract_params(s)?;
+        let (tokens, ret) = match utils::tag(&TokenKind::Colon, tokens) {
+            Ok(tokens) => TypeExpr::new(tokens).map(|(tokens, ret)| (tokens, Some(ret)))?,
+            Err(_) => (tokens, None),
+        };
 
-        let (s, body) = Statement::new(s)?;
+        let (tokens, body) = Statement::new(tokens)?;
 
         Ok((
-            s,
+            tokens,
             Self {
-                name: name.to_string(),
+                name,
                 params,
+                ret,
                 body: Box::new(body),
+                span: utils::consumed_span(start, tokens),
             },
         ))
     }
@@ -32,22 +113,23 @@ impl FunctionDef {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::expression::{Block, Expression};
+    use crate::expression::{Block, Expression, ExpressionKind};
 
     #[test]
     fn parse_function_def_with_no_params_and_empty_body() {
         assert_eq!(
-            FunctionDef::new("fn nothing() {}"),
-            Ok((
-                "",
-                FunctionDef {
-                    name: "nothing".to_string(),
-                    params: Vec::new(),
-                    body: Box::new(Statement::Expression(Expression::Block(Block {
+            utils::parse_str(FunctionDef::new, "fn nothing() {}"),
+            Ok(FunctionDef {
+                name: "nothing".to_string(),
+                params: Vec::new(),
+                ret: None,
+                body: Box::new(Statement::Expression(Expression::from(
+                    ExpressionKind::Block(Block {
                         statements: Vec::new()
-                    }))),
-                }
-            ))
+                    })
+                ))),
+                span: Span::default(),
+            })
         )
     }
 }
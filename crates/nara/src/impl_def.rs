@@ -0,0 +1,316 @@
+use crate::{
+    env::Env,
+    error::Error,
+    expression,
+    function_def::FunctionDef,
+    lexer::{Span, Token, TokenKind},
+    type_expr::TypeExpr,
+    utils,
+    val::{Function, Val},
+};
+use std::rc::Rc;
+
+// kinds of built-in values, as named by `Val::type_name`
+const BUILT_IN_KINDS: &[&str] = &[
+    "int", "float", "str", "char", "bool", "fn", "list", "tuple", "opt", "res", "range", "map",
+    "error", "type", "proto", "nil",
+];
+
+// `impl Proto(kind) { type Item = int fn next(self) ... }`, gives every value
+// of a kind the methods of a protocol. the kind is a built-in one like `list`
+// or a type declared with `type`, arguments like in `list(int)` are not told
+// apart. the methods are found by `value.next()` in the scope of the `impl`
+// and below. a method can't share its name with a built-in method of the kind
+#[derive(Debug, Clone)]
+pub(crate) struct ImplDef {
+    pub(crate) proto: String,
+    pub(crate) target: TypeExpr,
+    pub(crate) types: Vec<(String, TypeExpr)>,
+    pub(crate) methods: Vec<FunctionDef>,
+    pub(crate) span: Span,
+}
+
+// spans are not part of an impl's identity, see `Expression`
+impl PartialEq for ImplDef {
+    fn eq(&self, other: &Self) -> bool {
+        self.proto == other.proto
+            && self.target == other.target
+            && self.types == other.types
+            && self.methods == other.methods
+    }
+}
+
+impl ImplDef {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let tokens = utils::tag(&TokenKind::Impl, tokens)?;
+        let (tokens, proto) = utils::extract_ident(tokens)?;
+        let tokens = utils::tag(&TokenKind::LParen, tokens)?;
+        let (tokens, target) = TypeExpr::new(tokens)?;
+        let tokens = utils::tag(&TokenKind::RParen, tokens)?;
+        let mut tokens = utils::tag(&TokenKind::LBrace, tokens)?;
+
+        let mut types: Vec<(String, TypeExpr)> = Vec::new();
+        let mut methods: Vec<FunctionDef> = Vec::new();
+
+        // one member per line, or separated by commas
+        while *utils::peek(tokens) != TokenKind::RBrace {
+            let member = tokens;
+
+            tokens = match utils::peek(tokens) {
+                TokenKind::Type => {
+                    let (rest, name) = utils::extract_ident(&tokens[1..])?;
+                    let rest = utils::tag(&TokenKind::Assign, rest)?;
+                    let (rest, ty) = TypeExpr::new(rest)?;
+                    if types.iter().any(|(other, _)| *other == name) {
+                        return Err(utils::declared_twice("type", &name, member));
+                    }
+
+                    types.push((name, ty));
+                    rest
+                }
+                TokenKind::Fn => {
+                    let (rest, method) = FunctionDef::new(tokens)?;
+                    if methods.iter().any(|m| m.name == method.name) {
+                        return Err(utils::declared_twice("method", &method.name, member));
+                    }
+
+                    methods.push(method);
+                    rest
+                }
+                _ => return Err(utils::expected("`type` or `fn`", tokens)),
+            };
+
+            tokens = utils::tag(&TokenKind::Comma, tokens).unwrap_or(tokens);
+        }
+        let tokens = utils::tag(&TokenKind::RBrace, tokens)?;
+        let tokens = utils::extract_semicolon(tokens);
+
+        Ok((
+            tokens,
+            Self {
+                proto,
+                target,
+                types,
+                methods,
+                span: utils::consumed_span(start, tokens),
+            },
+        ))
+    }
+
+    // check the impl against its protocol and attach the methods to the kind
+    pub(crate) fn eval(&self, env: &mut Env) -> Result<(), Error> {
+        let proto = match env.get_binding_value_restrict(&self.proto) {
+            Ok(Val::Proto(proto)) => proto,
+            Ok(other) => return Err(self.mismatch("proto", &other)),
+            Err(err) => return Err(err.or_span(&self.span)),
+        };
+
+        let kind = &self.target.name;
+        if !BUILT_IN_KINDS.contains(&kind.as_str()) {
+            match env.get_binding_value_restrict(kind) {
+                Ok(Val::Type(_)) => {}
+                Ok(other) => return Err(self.mismatch("type", &other)),
+                Err(err) => return Err(err.or_span(&self.span)),
+            }
+        }
+
+        let def = &proto.def;
+        let of = format!("{}({})", def.name, kind);
+        for name in &def.types {
+            if !self.types.iter().any(|(given, _)| given == name) {
+                return Err(Error::MissingAssocType {
+                    name: name.clone(),
+                    of: of.clone(),
+                    span: self.span.clone(),
+                });
+            }
+        }
+
+        for sig in &def.methods {
+            let Some(method) = self.methods.iter().find(|m| m.name == sig.name) else {
+                return Err(Error::MissingMethod {
+                    name: sig.name.clone(),
+                    of: of.clone(),
+                    span: self.span.clone(),
+                });
+            };
+
+            if expression::is_built_in(kind, &sig.name) {
+                return Err(Error::BuiltInMethod {
+                    name: sig.name.clone(),
+                    of: of.clone(),
+                    span: self.span.clone(),
+                });
+            }
+
+            if method.params.len() != sig.params.len() {
+                return Err(Error::ArityMismatch {
+                    name: sig.name.clone(),
+                    expected: sig.params.len(),
+                    found: method.params.len(),
+                    span: self.span.clone(),
+                });
+            }
+        }
+
+        let extra = self
+            .types
+            .iter()
+            .map(|(name, _)| name)
+            .find(|name| !def.types.contains(name))
+            .or_else(|| {
+                self.methods
+                    .iter()
+                    .map(|method| &method.name)
+                    .find(|name| !def.methods.iter().any(|sig| sig.name == **name))
+            });
+        if let Some(name) = extra {
+            return Err(Error::NotInProto {
+                name: name.clone(),
+                proto: def.name.clone(),
+                span: self.span.clone(),
+            });
+        }
+
+        for method in &self.methods {
+            let func = Function {
+                params: method.params.clone(),
+                body: Rc::new(*method.body.clone()),
+                env: env.clone(),
+            };
+            env.define_method(kind, method.name.clone(), func);
+        }
+
+        Ok(())
+    }
+
+    fn mismatch(&self, expected: &str, found: &Val) -> Error {
+        Error::TypeMismatch {
+            expected: expected.to_string(),
+            found: found.type_name().to_string(),
+            span: self.span.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str) -> Result<Val, Error> {
+        crate::parse(s).unwrap().eval(&mut Env::default())
+    }
+
+    #[test]
+    fn parse_impl_def() {
+        let impl_def = utils::parse_str(
+            ImplDef::new,
+            "impl Iterator(list(int)) {
+                type Item = int
+                fn next(self) self[0]
+            }",
+        )
+        .unwrap();
+
+        assert_eq!(impl_def.proto, "Iterator");
+        assert_eq!(impl_def.target.name, "list");
+        assert_eq!(impl_def.types[0].0, "Item");
+        assert_eq!(impl_def.methods[0].name, "next");
+    }
+
+    #[test]
+    fn dispatch_on_kind() {
+        assert_eq!(
+            eval(
+                "proto Size { fn size(self) }
+                impl Size(list) { fn size(self) self.len() }
+                impl Size(int) { fn size(self) self }
+                [1, 2, 3].size() + 4.size()"
+            ),
+            Ok(Val::Number(7))
+        );
+    }
+
+    #[test]
+    fn missing_members() {
+        assert_eq!(
+            eval("proto Size { fn size(self) }\nimpl Size(str) {}").map_err(|err| err.message()),
+            Err("Impl Size(str) is missing method 'size'".to_string())
+        );
+        assert_eq!(
+            eval("proto Seq { type Item }\nimpl Seq(list) {}").map_err(|err| err.message()),
+            Err("Impl Seq(list) is missing type 'Item'".to_string())
+        );
+    }
+
+    #[test]
+    fn member_not_in_proto() {
+        let err = eval(
+            "proto Size { fn size(self) }\nimpl Size(str) { fn size(self) 1, fn half(self) 0 }",
+        )
+        .unwrap_err();
+
+        assert_eq!(err.message(), "Protocol Size has no member 'half'");
+        assert_eq!(err.span().line, 2);
+    }
+
+    #[test]
+    fn impl_for_unknown_kind() {
+        let err =
+            eval("proto Size { fn size(self) }\nimpl Size(Point) { fn size(self) 0 }").unwrap_err();
+
+        assert_eq!(err.message(), "binding with name 'Point' does not exist");
+    }
+
+    #[test]
+    fn methods_are_scoped() {
+        let err = eval(
+            "proto Size { fn size(self) }
+            { impl Size(int) { fn size(self) 1 }; 2.size() }
+            3.size()",
+        )
+        .unwrap_err();
+
+        assert_eq!(err.message(), "No method 'size' on type int");
+    }
+
+    #[test]
+    fn cannot_replace_built_in_method() {
+        let err = eval("proto P { fn len(self) }\nimpl P(list) { fn len(self) 99 }").unwrap_err();
+
+        assert_eq!(
+            err.message(),
+            "Impl P(list) cannot replace built-in method 'len'"
+        );
+        assert_eq!(
+            eval("proto P { fn len(self) }\nimpl P(int) { fn len(self) 99 }\n1.len()"),
+            Ok(Val::Number(99))
+        );
+    }
+
+    #[test]
+    fn named_args_and_defaults() {
+        assert_eq!(
+            eval(
+                "proto Scale { fn scale(self, by, plus) }
+                impl Scale(int) { fn scale(self, by = 2, plus = 0) self * by + plus }
+                3.scale(plus = 1) + 3.scale(10, plus = 1)"
+            ),
+            Ok(Val::Number(38))
+        );
+    }
+
+    #[test]
+    fn methods_do_not_assign() {
+        assert_eq!(
+            eval(
+                "proto Stack { fn push(self, x) }
+                impl Stack(int) { fn push(self, x) self + x }
+                val n = 1
+                n.push(2) + n"
+            ),
+            Ok(Val::Number(4))
+        );
+    }
+}
@@ -0,0 +1,1097 @@
+use crate::error::Error;
+use std::{fmt, sync::Arc};
+
+// location of a piece of source code
+// `start` and `end` are byte offsets, `line` and `column` point at `start`
+// and both count from 1. the default span (line 0) points nowhere
+#[derive(Clone, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+    // the whole source the span points into, shared by every token of one
+    // input so errors can quote it even after the input is gone
+    pub(crate) source: Option<Arc<str>>,
+}
+
+impl Span {
+    // span starting where `self` starts and ending where `other` ends
+    pub(crate) fn to(&self, other: &Span) -> Span {
+        Span {
+            end: self.end.max(other.end),
+            ..self.clone()
+        }
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        self.line == 0
+    }
+
+    // the source code the span points into, if it is known
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+}
+
+// two spans are equal when they cover the same code, wherever it came from
+impl PartialEq for Span {
+    fn eq(&self, other: &Self) -> bool {
+        (self.start, self.end, self.line, self.column)
+            == (other.start, other.end, other.line, other.column)
+    }
+}
+
+// the source is left out, it would repeat the whole input for every node
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Span")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("line", &self.line)
+            .field("column", &self.column)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TokenKind {
+    // Literals and names
+    Ident(String),
+    Number(i64),
+    Float(f64),
+    String(String),
+    Char(char),
+    FString(Vec<FStringSegment>),
+    // source the lexer could not read, like an unexpected character or an
+    // unclosed string. parsers fail at it and report the error it holds
+    Error(Box<Error>),
+    // Keywords
+    Val,
+    Mut,
+    Fn,
+    If,
+    Else,
+    While,
+    Loop,
+    For,
+    Return,
+    Break,
+    Continue,
+    Try,
+    Catch,
+    Match,
+    Type,
+    Proto,
+    Impl,
+    In,
+    True,
+    False,
+    None,
+    // Delimiters
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Comma,
+    Colon,
+    Question,
+    Semicolon,
+    // Operators
+    Assign,
+    PlusAssign,
+    MinusAssign,
+    StarAssign,
+    SlashAssign,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    DoubleSlash,
+    EqEq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    AndAnd,
+    OrOr,
+    Bang,
+    Arrow,
+    Dot,
+    DotDot,
+    DotDotEq,
+    Dollar,
+    At,
+    Eof,
+}
+
+impl TokenKind {
+    fn keyword(ident: &str) -> Option<Self> {
+        match ident {
+            "val" => Some(Self::Val),
+            "mut" => Some(Self::Mut),
+            "fn" => Some(Self::Fn),
+            "if" => Some(Self::If),
+            "else" => Some(Self::Else),
+            "while" => Some(Self::While),
+            "loop" => Some(Self::Loop),
+            "for" => Some(Self::For),
+            "return" => Some(Self::Return),
+            "break" => Some(Self::Break),
+            "continue" => Some(Self::Continue),
+            "try" => Some(Self::Try),
+            "catch" => Some(Self::Catch),
+            "match" => Some(Self::Match),
+            "type" => Some(Self::Type),
+            "proto" => Some(Self::Proto),
+            "impl" => Some(Self::Impl),
+            "in" => Some(Self::In),
+            "true" => Some(Self::True),
+            "false" => Some(Self::False),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Self::Ident(name) => return write!(f, "identifier `{}`", name),
+            Self::Number(n) => return write!(f, "number `{}`", n),
+            Self::Float(n) => return write!(f, "float `{}`", n),
+            Self::String(_) => return write!(f, "string literal"),
+            Self::Char(_) => return write!(f, "character literal"),
+            Self::FString(_) => return write!(f, "f-string literal"),
+            Self::Error(_) => return write!(f, "invalid token"),
+            Self::Eof => return write!(f, "end of input"),
+            Self::Val => "val",
+            Self::Mut => "mut",
+            Self::Fn => "fn",
+            Self::If => "if",
+            Self::Else => "else",
+            Self::While => "while",
+            Self::Loop => "loop",
+            Self::For => "for",
+            Self::Return => "return",
+            Self::Break => "break",
+            Self::Continue => "continue",
+            Self::Try => "try",
+            Self::Catch => "catch",
+            Self::Match => "match",
+            Self::Type => "type",
+            Self::Proto => "proto",
+            Self::Impl => "impl",
+            Self::In => "in",
+            Self::True => "true",
+            Self::False => "false",
+            Self::None => "none",
+            Self::LParen => "(",
+            Self::RParen => ")",
+            Self::LBrace => "{",
+            Self::RBrace => "}",
+            Self::LBracket => "[",
+            Self::RBracket => "]",
+            Self::Comma => ",",
+            Self::Colon => ":",
+            Self::Question => "?",
+            Self::Semicolon => ";",
+            Self::Assign => "=",
+            Self::PlusAssign => "+=",
+            Self::MinusAssign => "-=",
+            Self::StarAssign => "*=",
+            Self::SlashAssign => "/=",
+            Self::Plus => "+",
+            Self::Minus => "-",
+            Self::Star => "*",
+            Self::Slash => "/",
+            Self::DoubleSlash => "//",
+            Self::EqEq => "==",
+            Self::NotEq => "!=",
+            Self::Lt => "<",
+            Self::LtEq => "<=",
+            Self::Gt => ">",
+            Self::GtEq => ">=",
+            Self::AndAnd => "&&",
+            Self::OrOr => "||",
+            Self::Bang => "!",
+            Self::Arrow => "->",
+            Self::Dot => ".",
+            Self::DotDot => "..",
+            Self::DotDotEq => "..=",
+            Self::Dollar => "$",
+            Self::At => "@",
+        };
+
+        write!(f, "`{}`", symbol)
+    }
+}
+
+// f-strings are split into text and the tokens of each `{...}` interpolation,
+// so interpolated expressions keep their real position in the source
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FStringSegment {
+    Text(String),
+    Interpolation(Vec<Token>),
+}
+
+// `# ...` line comment, `text` is everything after the `#` up to the end of
+// the line
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Comment {
+    pub(crate) text: String,
+    pub(crate) span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Token {
+    pub(crate) kind: TokenKind,
+    pub(crate) span: Span,
+    // comments between the previous token and this one. the parser never looks
+    // at them, they are kept for tools like a formatter. comments at the end
+    // of the input belong to `Eof`
+    pub(crate) comments: Vec<Comment>,
+}
+
+// turn source code into tokens, failing at the first part that can't be
+// read, for tests that start from source code
+#[cfg(test)]
+pub(crate) fn lex(s: &str) -> Result<Vec<Token>, Error> {
+    let tokens = lex_partial(s);
+
+    match first_error(&tokens) {
+        Some(err) => Err(err.clone()),
+        None => Ok(tokens),
+    }
+}
+
+// tokens of the whole source, always ending with an `Eof` token. every part
+// that could not be read becomes an `Error` token and lexing picks up again
+// right after it
+pub(crate) fn lex_partial(s: &str) -> Vec<Token> {
+    Lexer {
+        src: s,
+        source: Arc::from(s),
+        pos: 0,
+        end: s.len(),
+        line: 1,
+        column: 1,
+    }
+    .tokenize()
+}
+
+pub(crate) fn first_error(tokens: &[Token]) -> Option<&Error> {
+    tokens.iter().find_map(|token| match &token.kind {
+        TokenKind::Error(err) => Some(&**err),
+        _ => None,
+    })
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    source: Arc<str>,
+    pos: usize,
+    end: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lexer<'_> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..self.end].chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.src[self.pos..self.end].chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> &str {
+        let start = self.pos;
+        while self.peek().is_some_and(&accept) {
+            self.bump();
+        }
+        &self.src[start..self.pos]
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        Error::Syntax {
+            message: msg.to_string(),
+            span: Span {
+                start: self.pos,
+                end: self.pos,
+                line: self.line,
+                column: self.column,
+                source: Some(self.source.clone()),
+            },
+        }
+    }
+
+    fn tokenize(mut self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+
+        loop {
+            let comments = self.trivia();
+
+            let (start, line, column) = (self.pos, self.line, self.column);
+            let kind = match self.token(&tokens) {
+                Ok(kind) => kind,
+                Err(err) => {
+                    // an unexpected character is skipped on its own
+                    if self.pos == start {
+                        self.bump();
+                    }
+                    TokenKind::Error(Box::new(err))
+                }
+            };
+
+            let is_eof = kind == TokenKind::Eof;
+            tokens.push(Token {
+                kind,
+                span: Span {
+                    start,
+                    end: self.pos,
+                    line,
+                    column,
+                    source: Some(self.source.clone()),
+                },
+                comments,
+            });
+
+            if is_eof {
+                return tokens;
+            }
+        }
+    }
+
+    fn token(&mut self, tokens: &[Token]) -> Result<TokenKind, Error> {
+        Ok(match self.peek() {
+            None => TokenKind::Eof,
+            // `pair.0.1` reads two positions, not the float `0.1`
+            Some(c) if c.is_ascii_digit() => {
+                let after_dot = tokens.last().is_some_and(|t| t.kind == TokenKind::Dot);
+                self.number(!after_dot)?
+            }
+            Some('f') if self.peek_second() == Some('"') => {
+                self.bump();
+                self.fstring()?
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let ident = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+                TokenKind::keyword(ident).unwrap_or_else(|| TokenKind::Ident(ident.to_string()))
+            }
+            Some('"') => TokenKind::String(self.string()?),
+            Some('\'') => TokenKind::Char(self.char()?),
+            Some(_) => self.symbol()?,
+        })
+    }
+
+    // skip whitespace and comments, collecting the comments
+    fn trivia(&mut self) -> Vec<Comment> {
+        let mut comments = Vec::new();
+
+        loop {
+            self.take_while(char::is_whitespace);
+
+            if self.peek() != Some('#') {
+                return comments;
+            }
+
+            let (start, line, column) = (self.pos, self.line, self.column);
+            self.bump();
+            let text = self.take_while(|c| c != '\n').to_string();
+
+            comments.push(Comment {
+                text,
+                span: Span {
+                    start,
+                    end: self.pos,
+                    line,
+                    column,
+                    source: Some(self.source.clone()),
+                },
+            });
+        }
+    }
+
+    fn number(&mut self, allow_float: bool) -> Result<TokenKind, Error> {
+        let start = self.pos;
+        self.take_while(|c| c.is_ascii_digit());
+
+        // a dot only makes a float when digits follow it
+        if allow_float
+            && self.peek() == Some('.')
+            && self.peek_second().is_some_and(|c| c.is_ascii_digit())
+        {
+            self.bump();
+            self.take_while(|c| c.is_ascii_digit());
+
+            let float = &self.src[start..self.pos];
+            return Ok(TokenKind::Float(float.parse().unwrap()));
+        }
+
+        self.src[start..self.pos]
+            .parse()
+            .map(TokenKind::Number)
+            .map_err(|_| self.error("number literal is too large"))
+    }
+
+    fn symbol(&mut self) -> Result<TokenKind, Error> {
+        // Try multi-character operators first
+        if self.src[self.pos..self.end].starts_with("..=") {
+            self.bump();
+            self.bump();
+            self.bump();
+            return Ok(TokenKind::DotDotEq);
+        }
+
+        let two_chars = match (self.peek(), self.peek_second()) {
+            (Some('='), Some('=')) => Some(TokenKind::EqEq),
+            (Some('!'), Some('=')) => Some(TokenKind::NotEq),
+            (Some('<'), Some('=')) => Some(TokenKind::LtEq),
+            (Some('>'), Some('=')) => Some(TokenKind::GtEq),
+            (Some('&'), Some('&')) => Some(TokenKind::AndAnd),
+            (Some('|'), Some('|')) => Some(TokenKind::OrOr),
+            (Some('/'), Some('/')) => Some(TokenKind::DoubleSlash),
+            (Some('+'), Some('=')) => Some(TokenKind::PlusAssign),
+            (Some('-'), Some('=')) => Some(TokenKind::MinusAssign),
+            (Some('-'), Some('>')) => Some(TokenKind::Arrow),
+            (Some('.'), Some('.')) => Some(TokenKind::DotDot),
+            (Some('*'), Some('=')) => Some(TokenKind::StarAssign),
+            (Some('/'), Some('=')) => Some(TokenKind::SlashAssign),
+            _ => None,
+        };
+
+        if let Some(kind) = two_chars {
+            self.bump();
+            self.bump();
+            return Ok(kind);
+        }
+
+        let kind = match self.peek() {
+            Some('(') => TokenKind::LParen,
+            Some(')') => TokenKind::RParen,
+            Some('{') => TokenKind::LBrace,
+            Some('}') => TokenKind::RBrace,
+            Some('[') => TokenKind::LBracket,
+            Some(']') => TokenKind::RBracket,
+            Some(',') => TokenKind::Comma,
+            Some('.') => TokenKind::Dot,
+            Some(':') => TokenKind::Colon,
+            Some('?') => TokenKind::Question,
+            Some(';') => TokenKind::Semicolon,
+            Some('=') => TokenKind::Assign,
+            Some('+') => TokenKind::Plus,
+            Some('-') => TokenKind::Minus,
+            Some('*') => TokenKind::Star,
+            Some('/') => TokenKind::Slash,
+            Some('<') => TokenKind::Lt,
+            Some('>') => TokenKind::Gt,
+            Some('!') => TokenKind::Bang,
+            Some('$') => TokenKind::Dollar,
+            Some('@') => TokenKind::At,
+            Some(c) => return Err(self.error(&format!("unexpected character '{}'", c))),
+            None => return Err(self.error("unexpected end of input")),
+        };
+
+        self.bump();
+        Ok(kind)
+    }
+
+    // We need while let here because we call bump() again for escape sequences
+    fn string(&mut self) -> Result<String, Error> {
+        // skip the opening quote
+        self.bump();
+        let mut result = String::new();
+
+        while let Some(ch) = self.bump() {
+            match ch {
+                '"' => return Ok(result),
+                '\\' => match self.bump() {
+                    Some('n') => result.push('\n'),
+                    Some('t') => result.push('\t'),
+                    Some('r') => result.push('\r'),
+                    Some('\\') => result.push('\\'),
+                    Some('"') => result.push('"'),
+                    Some(escaped) => {
+                        result.push('\\');
+                        result.push(escaped);
+                    }
+                    None => return Err(self.error("unexpected end of string after backslash")),
+                },
+                _ => result.push(ch),
+            }
+        }
+
+        Err(self.error("unclosed string literal"))
+    }
+
+    fn char(&mut self) -> Result<char, Error> {
+        let result = self.char_literal();
+
+        // a broken literal like `'ab'` is one token, lexing goes on after its
+        // closing quote
+        if result.is_err() && !matches!(self.src[..self.pos].chars().next_back(), Some('\'' | '\n'))
+        {
+            self.take_while(|c| c != '\'' && c != '\n');
+            if self.peek() == Some('\'') {
+                self.bump();
+            }
+        }
+
+        result
+    }
+
+    fn char_literal(&mut self) -> Result<char, Error> {
+        // skip the opening quote
+        self.bump();
+
+        let c = match self.bump() {
+            Some('\\') => self.char_escape()?,
+            Some('\'') => return Err(self.error("empty character literal")),
+            Some('\n') | None => return Err(self.error("unclosed character literal")),
+            Some(c) => c,
+        };
+
+        match self.bump() {
+            Some('\'') => Ok(c),
+            _ => Err(self.error("character literal must hold exactly one character")),
+        }
+    }
+
+    // escape after a backslash in a character literal, including `\u{1F600}`
+    fn char_escape(&mut self) -> Result<char, Error> {
+        match self.bump() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some('0') => Ok('\0'),
+            Some('\\') => Ok('\\'),
+            Some('\'') => Ok('\''),
+            Some('"') => Ok('"'),
+            Some('u') => {
+                if self.bump() != Some('{') {
+                    return Err(self.error("expected `{` after `\\u`"));
+                }
+                let digits = self.take_while(|c| c.is_ascii_hexdigit()).to_string();
+                if self.bump() != Some('}') {
+                    return Err(self.error("expected `}` closing the unicode escape"));
+                }
+
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("invalid unicode escape"))
+            }
+            Some(c) => Err(self.error(&format!("unknown character escape '\\{}'", c))),
+            None => Err(self.error("unexpected end of character literal after backslash")),
+        }
+    }
+
+    fn fstring(&mut self) -> Result<TokenKind, Error> {
+        // skip the opening quote, the `f` is already consumed
+        self.bump();
+        let mut segments = Vec::new();
+        let mut current_text = String::new();
+
+        while let Some(ch) = self.bump() {
+            match ch {
+                '"' => {
+                    // End of f-string
+                    if !current_text.is_empty() {
+                        segments.push(FStringSegment::Text(current_text));
+                    }
+                    return Ok(TokenKind::FString(segments));
+                }
+                '{' => {
+                    // Start of interpolation
+                    if !current_text.is_empty() {
+                        segments.push(FStringSegment::Text(std::mem::take(&mut current_text)));
+                    }
+
+                    // Find matching }, the inner lexer starts where the
+                    // interpolation starts so its spans point into the source
+                    let inner = Lexer {
+                        src: self.src,
+                        source: self.source.clone(),
+                        pos: self.pos,
+                        end: self.end,
+                        line: self.line,
+                        column: self.column,
+                    };
+
+                    let mut depth = 1;
+                    while let Some(ch) = self.peek() {
+                        if ch == '{' {
+                            depth += 1;
+                        } else if ch == '}' {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        self.bump();
+                    }
+
+                    if depth != 0 {
+                        return Err(self.error("unclosed interpolation in f-string"));
+                    }
+
+                    let tokens = Lexer {
+                        end: self.pos,
+                        ..inner
+                    }
+                    .tokenize();
+                    if let Some(err) = first_error(&tokens) {
+                        return Err(err.clone());
+                    }
+                    segments.push(FStringSegment::Interpolation(tokens));
+
+                    // skip the closing brace
+                    self.bump();
+                }
+                '\\' => match self.bump() {
+                    Some('n') => current_text.push('\n'),
+                    Some('t') => current_text.push('\t'),
+                    Some('r') => current_text.push('\r'),
+                    Some('\\') => current_text.push('\\'),
+                    Some('"') => current_text.push('"'),
+                    Some('{') => current_text.push('{'),
+                    Some('}') => current_text.push('}'),
+                    Some(escaped) => {
+                        current_text.push('\\');
+                        current_text.push(escaped);
+                    }
+                    None => return Err(self.error("unexpected end of f-string after backslash")),
+                },
+                _ => current_text.push(ch),
+            }
+        }
+
+        Err(self.error("unclosed f-string literal"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(s: &str) -> Vec<TokenKind> {
+        lex(s)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    #[test]
+    fn lex_empty_input() {
+        assert_eq!(kinds("  \n  "), vec![TokenKind::Eof]);
+    }
+
+    #[test]
+    fn lex_alphanumeric_ident() {
+        assert_eq!(
+            kinds("foobar()"),
+            vec![
+                TokenKind::Ident("foobar".to_string()),
+                TokenKind::LParen,
+                TokenKind::RParen,
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_ident_with_underscores() {
+        assert_eq!(
+            kinds("_my_var2"),
+            vec![TokenKind::Ident("_my_var2".to_string()), TokenKind::Eof]
+        );
+    }
+
+    #[test]
+    fn lex_keywords() {
+        assert_eq!(
+            kinds("val mut fn if else while loop for in return break continue true false"),
+            vec![
+                TokenKind::Val,
+                TokenKind::Mut,
+                TokenKind::Fn,
+                TokenKind::If,
+                TokenKind::Else,
+                TokenKind::While,
+                TokenKind::Loop,
+                TokenKind::For,
+                TokenKind::In,
+                TokenKind::Return,
+                TokenKind::Break,
+                TokenKind::Continue,
+                TokenKind::True,
+                TokenKind::False,
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_declaration_keywords() {
+        assert_eq!(
+            kinds("type proto impl"),
+            vec![
+                TokenKind::Type,
+                TokenKind::Proto,
+                TokenKind::Impl,
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_keyword_prefix_as_ident() {
+        assert_eq!(
+            kinds("valaaa"),
+            vec![TokenKind::Ident("valaaa".to_string()), TokenKind::Eof]
+        );
+    }
+
+    #[test]
+    fn lex_number_followed_by_ident() {
+        assert_eq!(
+            kinds("123abc"),
+            vec![
+                TokenKind::Number(123),
+                TokenKind::Ident("abc".to_string()),
+                TokenKind::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_float() {
+        assert_eq!(kinds("2.5"), vec![TokenKind::Float(2.5), TokenKind::Eof]);
+    }
+
+    #[test]
+    fn lex_range_is_not_float() {
+        assert_eq!(
+            kinds("0..3 1..=n xs.len"),
+            vec![
+                TokenKind::Number(0),
+                TokenKind::DotDot,
+                TokenKind::Number(3),
+                TokenKind::Number(1),
+                TokenKind::DotDotEq,
+                TokenKind::Ident("n".to_string()),
+                TokenKind::Ident("xs".to_string()),
+                TokenKind::Dot,
+                TokenKind::Ident("len".to_string()),
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_too_large_number() {
+        assert!(lex("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn lex_operators() {
+        assert_eq!(
+            kinds("== != <= >= && || // + - * / < > ! ="),
+            vec![
+                TokenKind::EqEq,
+                TokenKind::NotEq,
+                TokenKind::LtEq,
+                TokenKind::GtEq,
+                TokenKind::AndAnd,
+                TokenKind::OrOr,
+                TokenKind::DoubleSlash,
+                TokenKind::Plus,
+                TokenKind::Minus,
+                TokenKind::Star,
+                TokenKind::Slash,
+                TokenKind::Lt,
+                TokenKind::Gt,
+                TokenKind::Bang,
+                TokenKind::Assign,
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_assignment_operators() {
+        assert_eq!(
+            kinds("x += 1 -= *= /= //= : ? ->"),
+            vec![
+                TokenKind::Ident("x".to_string()),
+                TokenKind::PlusAssign,
+                TokenKind::Number(1),
+                TokenKind::MinusAssign,
+                TokenKind::StarAssign,
+                TokenKind::SlashAssign,
+                TokenKind::DoubleSlash,
+                TokenKind::Assign,
+                TokenKind::Colon,
+                TokenKind::Question,
+                TokenKind::Arrow,
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_positions_after_dot_as_integers() {
+        assert_eq!(
+            kinds("$(1, 2.5).0.1"),
+            vec![
+                TokenKind::Dollar,
+                TokenKind::LParen,
+                TokenKind::Number(1),
+                TokenKind::Comma,
+                TokenKind::Float(2.5),
+                TokenKind::RParen,
+                TokenKind::Dot,
+                TokenKind::Number(0),
+                TokenKind::Dot,
+                TokenKind::Number(1),
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_char_literals() {
+        assert_eq!(
+            kinds(r"'a' '\n' '\'' '\u{1F600}' 'é'"),
+            vec![
+                TokenKind::Char('a'),
+                TokenKind::Char('\n'),
+                TokenKind::Char('\''),
+                TokenKind::Char('😀'),
+                TokenKind::Char('é'),
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_invalid_char_literals() {
+        let message = |s| lex(s).unwrap_err().message();
+
+        assert_eq!(message("''"), "empty character literal");
+        assert_eq!(
+            message("'ab'"),
+            "character literal must hold exactly one character"
+        );
+        assert_eq!(message(r"'\u{110000}'"), "invalid unicode escape");
+        assert_eq!(message(r"'\q'"), "unknown character escape '\\q'");
+    }
+
+    #[test]
+    fn lex_unexpected_character() {
+        assert_eq!(
+            lex("1 ^"),
+            Err(Error::Syntax {
+                message: "unexpected character '^'".to_string(),
+                span: Span {
+                    start: 2,
+                    end: 2,
+                    line: 1,
+                    column: 3,
+                    ..Span::default()
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn lex_partial_goes_on_after_errors() {
+        let kinds: Vec<String> = lex_partial("1 ^ 'ab' 2")
+            .into_iter()
+            .map(|token| token.kind.to_string())
+            .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                "number `1`",
+                "invalid token",
+                "invalid token",
+                "number `2`",
+                "end of input"
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_string_with_escapes() {
+        assert_eq!(
+            kinds("\"hello\\n\\\"world\\\"\""),
+            vec![
+                TokenKind::String("hello\n\"world\"".to_string()),
+                TokenKind::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_unclosed_string() {
+        assert!(lex("\"hello").is_err());
+    }
+
+    #[test]
+    fn token_spans_have_offsets_lines_and_columns() {
+        let tokens = lex("val x = 1;\n  x").unwrap();
+
+        assert_eq!(
+            tokens[1].span,
+            Span {
+                start: 4,
+                end: 5,
+                line: 1,
+                column: 5,
+                ..Span::default()
+            }
+        );
+        assert_eq!(
+            tokens[5].span,
+            Span {
+                start: 13,
+                end: 14,
+                line: 2,
+                column: 3,
+                ..Span::default()
+            }
+        );
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        let tokens = lex("\"é\" x").unwrap();
+
+        assert_eq!(tokens[1].span.start, 5);
+        assert_eq!(tokens[1].span.column, 5);
+    }
+
+    #[test]
+    fn lex_skips_comments() {
+        assert_eq!(
+            kinds("# leading\nx # after x\n# own line\n+ 1 #"),
+            vec![
+                TokenKind::Ident("x".to_string()),
+                TokenKind::Plus,
+                TokenKind::Number(1),
+                TokenKind::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_are_kept_on_following_token() {
+        let tokens = lex("val x = 1; # one\n#two\nx # trailing").unwrap();
+
+        let texts = |token: &Token| -> Vec<String> {
+            token.comments.iter().map(|c| c.text.clone()).collect()
+        };
+
+        assert_eq!(tokens[5].kind, TokenKind::Ident("x".to_string()));
+        assert_eq!(texts(&tokens[5]), vec![" one", "two"]);
+        assert_eq!(texts(&tokens[6]), vec![" trailing"]);
+        assert_eq!(
+            tokens[5].comments[0].span,
+            Span {
+                start: 11,
+                end: 16,
+                line: 1,
+                column: 12,
+                ..Span::default()
+            }
+        );
+        assert!(tokens[0].comments.is_empty());
+    }
+
+    #[test]
+    fn hash_inside_string_is_not_a_comment() {
+        assert_eq!(
+            kinds("\"# not a comment\""),
+            vec![
+                TokenKind::String("# not a comment".to_string()),
+                TokenKind::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_simple_fstring() {
+        assert_eq!(
+            kinds("f\"hello\""),
+            vec![
+                TokenKind::FString(vec![FStringSegment::Text("hello".to_string())]),
+                TokenKind::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_fstring_with_interpolation() {
+        let tokens = lex("f\"Hello {name}!\"").unwrap();
+
+        let TokenKind::FString(segments) = &tokens[0].kind else {
+            panic!("Expected f-string token");
+        };
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0], FStringSegment::Text("Hello ".to_string()));
+        assert_eq!(segments[2], FStringSegment::Text("!".to_string()));
+
+        let FStringSegment::Interpolation(inner) = &segments[1] else {
+            panic!("Expected interpolation");
+        };
+
+        assert_eq!(inner[0].kind, TokenKind::Ident("name".to_string()));
+        assert_eq!(inner[0].span.start, 9);
+        assert_eq!(inner[1].kind, TokenKind::Eof);
+    }
+
+    #[test]
+    fn lex_fstring_with_escaped_braces() {
+        assert_eq!(
+            kinds("f\"test \\{not_interpolated\\}\""),
+            vec![
+                TokenKind::FString(vec![FStringSegment::Text(
+                    "test {not_interpolated}".to_string()
+                )]),
+                TokenKind::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_fstring_unclosed() {
+        assert!(lex("f\"hello").is_err());
+    }
+
+    #[test]
+    fn lex_fstring_unclosed_interpolation() {
+        assert!(lex("f\"hello {name\"").is_err());
+    }
+}
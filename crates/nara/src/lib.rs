@@ -1,49 +1,131 @@
+mod assignment;
 mod binding_def;
+mod checker;
 mod env;
+mod error;
 mod expression;
 mod function_def;
+mod impl_def;
 mod interner;
+mod lexer;
+mod loop_control;
+mod pattern;
+mod proto_def;
+mod return_statement;
 mod statement;
+mod type_def;
+mod type_expr;
 mod utils;
 mod val;
 
+use error::Unwind;
+
 pub use env::Env;
+pub use error::Error;
 pub use interner::StringInterner;
-pub use val::Val;
+pub use lexer::Span;
+pub use val::{Caught, Instance, Map, Proto, Range, Type, Val};
 
 #[derive(Debug)]
 pub struct Parse(Vec<statement::Statement>);
 
 impl Parse {
-    pub fn eval(&self, env: &mut env::Env) -> Result<Val, String> {
+    pub fn eval(&self, env: &mut env::Env) -> Result<Val, Error> {
         if self.0.is_empty() {
             return Ok(Val::Unit);
         }
 
         // Evaluate all statements except the last
         for stmt in &self.0[..self.0.len() - 1] {
-            stmt.eval(env)?;
+            stmt.eval(env).map_err(Unwind::into_error)?;
         }
 
         // Return the result of the last statement
-        self.0.last().unwrap().eval(env)
+        self.0.last().unwrap().eval(env).map_err(Unwind::into_error)
     }
 }
 
-pub fn parse(s: &str) -> Result<Parse, String> {
-    let (s, statements) = utils::sequence(statement::Statement::new, s)?;
+// parse a whole program, failing with every syntax error found in it
+pub fn parse(s: &str) -> Result<Parse, Vec<Error>> {
+    let (parse, errors) = parse_partial(s);
 
-    if s.is_empty() {
-        if statements.is_empty() {
-            Err("expected at least one statement".to_string())
-        } else {
-            Ok(Parse(statements))
-        }
+    if errors.is_empty() {
+        Ok(parse)
+    } else {
+        Err(errors)
+    }
+}
+
+// parse a program and check it for type errors before anything runs, like
+// operands an operator doesn't take, calls with the wrong arguments or
+// values that don't fit their annotation. only what is known without running
+// is checked, names bound outside the program are left alone
+pub fn check(s: &str) -> Result<Parse, Vec<Error>> {
+    check_in(s, &Env::default())
+}
+
+// `check` for code that runs in `env`, which may declare the types and
+// protocols its annotations name, like a REPL input after earlier ones
+pub fn check_in(s: &str, env: &Env) -> Result<Parse, Vec<Error>> {
+    let parse = parse(s)?;
+    let errors = checker::check(&parse.0, env);
+
+    if errors.is_empty() {
+        Ok(parse)
     } else {
-        Err("input was not consumed fully by parser".to_string())
+        Err(errors)
     }
 }
 
+// parse as much of a program as possible, for tools that want to work with
+// broken code. statements that fail to parse stay in the tree as error nodes
+// and are reported, in source order, next to it
+pub fn parse_partial(s: &str) -> (Parse, Vec<Error>) {
+    let tokens = lexer::lex_partial(s);
+    let mut tokens = tokens.as_slice();
+    let mut statements = Vec::new();
+
+    let eof = loop {
+        let (rest, parsed) = utils::sequence(
+            statement::Statement::new,
+            statement::Statement::Error,
+            tokens,
+        );
+        statements.extend(parsed);
+
+        match utils::peek(rest) {
+            lexer::TokenKind::Eof => break rest,
+            // a `}` without a block to close
+            _ => {
+                let err = utils::expected("statement", rest);
+                statements.push(statement::Statement::Error(err));
+                tokens = &rest[1..];
+            }
+        }
+    };
+
+    let mut errors = Vec::new();
+    if statements.is_empty() {
+        errors.push(utils::expected("at least one statement", eof));
+    }
+    for stmt in &statements {
+        stmt.collect_errors(&mut errors);
+    }
+
+    // tokens the lexer could not read, also the ones skipped while recovering
+    // from an earlier error in the same statement
+    for token in tokens {
+        if let lexer::TokenKind::Error(err) = &token.kind
+            && !errors.contains(err)
+        {
+            errors.push((**err).clone());
+        }
+    }
+    errors.sort_by_key(|err| err.span().start);
+
+    (Parse(statements), errors)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,4 +189,862 @@ mod tests {
         let result = parse_result.eval(&mut env).unwrap();
         assert_eq!(result, Val::Number(15));
     }
+
+    #[test]
+    fn test_unit_literal_binding() {
+        let mut env = Env::default();
+        let parse_result = parse("val nothing = (); nothing").unwrap();
+        let result = parse_result.eval(&mut env).unwrap();
+        assert_eq!(result, Val::Unit);
+    }
+
+    #[test]
+    fn test_multi_statement_with_expression_statements() {
+        let mut env = Env::default();
+        let parse_result = parse("val my_list = [1, 2]; len(my_list); len(\"abc\")").unwrap();
+        let result = parse_result.eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(3));
+    }
+
+    #[test]
+    fn test_error_in_function_quotes_source_of_definition() {
+        let mut env = Env::default();
+        parse("fn half(x) x / 0").unwrap().eval(&mut env).unwrap();
+
+        let err = parse("half(4)").unwrap().eval(&mut env).unwrap_err();
+
+        assert!(matches!(err, Error::DivisionByZero { .. }));
+        assert!(err.to_string().contains("1 | fn half(x) x / 0"));
+    }
+
+    #[test]
+    fn test_parse_reports_statement_error() {
+        let errors = parse("val x = 1; val = 2").err().unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message(), "expected identifier, found `=`");
+        assert_eq!(errors[0].span().column, 16);
+    }
+
+    #[test]
+    fn test_parse_reports_every_broken_statement() {
+        let errors = parse("val a = ;\nval b = 2\nval = 3\nb * ;\n{ 1 + ; 2 }")
+            .err()
+            .unwrap();
+        let lines: Vec<usize> = errors.iter().map(|err| err.span().line).collect();
+
+        assert_eq!(lines, vec![1, 3, 4, 5]);
+        assert_eq!(errors[2].message(), "expected expression, found `;`");
+    }
+
+    #[test]
+    fn test_parse_reports_errors_around_unreadable_characters() {
+        let (parse, errors) = parse_partial("val a = ;\nval b = é\nval c = ;\nval d = 1 + ; ^");
+        let lines: Vec<usize> = errors.iter().map(|err| err.span().line).collect();
+
+        assert_eq!(lines, vec![1, 2, 3, 4, 4]);
+        assert_eq!(errors[1].message(), "unexpected character 'é'");
+        assert_eq!(errors[4].message(), "unexpected character '^'");
+        assert_eq!(parse.0.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_reports_stray_closing_brace() {
+        let errors = parse("1 }\n2").err().unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message(), "expected statement, found `}`");
+    }
+
+    #[test]
+    fn test_parse_partial_keeps_valid_statements() {
+        let (parse, errors) = parse_partial("val a = 2;\nval b = ;\nval c = a * 3;\nc");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(parse.0.len(), 4);
+        assert!(matches!(parse.0[1], statement::Statement::Error(_)));
+
+        // evaluation stops at the broken statement
+        assert_eq!(parse.eval(&mut Env::default()), Err(errors[0].clone()));
+    }
+
+    #[test]
+    fn test_parse_empty_input() {
+        let errors = parse("  ").err().unwrap();
+
+        assert_eq!(
+            errors[0].message(),
+            "expected at least one statement, found end of input"
+        );
+    }
+
+    #[test]
+    fn test_comments_wherever_whitespace_is_allowed() {
+        let mut env = Env::default();
+        let src = "# sums a list
+fn sum(items) { # body
+    val total = 0; # unused
+    # the real work
+    len(items) * 10 # comment after expression
+}
+
+val xs = [
+    1, # first
+    2  # second
+];
+sum( # args
+    xs # the list
+) # done
+";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(20));
+    }
+
+    #[test]
+    fn test_while_loop_with_mutable_counter() {
+        let mut env = Env::default();
+        let src = "mut i = 0; mut total = 0; while i < 5 { i += 1; total += i }; total";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(15));
+    }
+
+    #[test]
+    fn test_deferred_initialization() {
+        let mut env = Env::default();
+        let result = parse("my_var: int;\nmy_var = 7;\nmy_var * 2")
+            .unwrap()
+            .eval(&mut env)
+            .unwrap();
+        assert_eq!(result, Val::Number(14));
+
+        let err = parse("my_var = 8").unwrap().eval(&mut env).unwrap_err();
+        assert!(matches!(err, Error::AssignToImmutable { .. }));
+    }
+
+    #[test]
+    fn test_read_before_initialization() {
+        let err = parse("val x: int; x + 1")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            Error::Uninitialized {
+                name: "x".to_string(),
+                span: Span {
+                    start: 12,
+                    end: 13,
+                    line: 1,
+                    column: 13,
+                    ..Span::default()
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_cannot_reassign_val() {
+        let err = parse("val x = 1;\nx = 2")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+
+        assert!(matches!(err, Error::AssignToImmutable { .. }));
+        assert_eq!(err.span().line, 2);
+    }
+
+    #[test]
+    fn test_assignment_in_block_updates_outer_binding() {
+        let mut env = Env::default();
+        let src = "mut x = 1; { val x = 10; x }; { x = x + 1 }; x";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(2));
+    }
+
+    #[test]
+    fn test_closure_captures_defining_scope() {
+        let mut env = Env::default();
+        let src = "fn make_adder(n) { fn add(x) x + n; add }
+val add5 = make_adder(5);
+add5(10)";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(15));
+    }
+
+    #[test]
+    fn test_closure_keeps_mutable_state() {
+        let mut env = Env::default();
+        let src = "fn counter() { mut count = 0; fn next() { count += 1; count }; next }
+val tick = counter();
+val other = counter();
+tick(); tick(); other();
+tick()";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(3));
+    }
+
+    #[test]
+    fn test_functions_are_lexically_scoped() {
+        let mut env = Env::default();
+        let src = "val x = 1;
+fn get() x;
+fn shadowing_caller() { val x = 2; get() }
+shadowing_caller()";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(1));
+    }
+
+    #[test]
+    fn test_shadowing_keeps_captured_binding() {
+        let mut env = Env::default();
+        let src = r#"val x = 1;
+fn g() x + 1;
+val x = "s";
+fn count(n) if n > 0 { count(n - 1) + 1 } else { 0 }
+fn count(n) if n > 0 { count(n - 1) + 2 } else { 0 }
+f"{g()} {x} {count(3)}""#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::String("2 s 6".into()));
+    }
+
+    #[test]
+    fn test_closures_stored_in_list() {
+        let mut env = Env::default();
+        let src = "fn make_adder(n) { fn add(x) x + n; add }
+mut total = 0;
+for add in [make_adder(1), make_adder(10), make_adder(100)] { total += add(1) }
+total";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(114));
+    }
+
+    #[test]
+    fn test_recursive_function() {
+        let mut env = Env::default();
+        let src = "fn fact(n) if n <= 1 { 1 } else { n * fact(n - 1) }; fact(5)";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(120));
+    }
+
+    #[test]
+    fn test_lambda_bound_and_called() {
+        let mut env = Env::default();
+        let src = "val add = (first: int, second: int) -> int: {
+    first + second
+};
+val four = () -> 1 + 3;
+mut mul = a, b -> a * b;
+add(four(), mul(2, 3))";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(10));
+    }
+
+    #[test]
+    fn test_lambda_as_argument() {
+        let mut env = Env::default();
+        let src = "fn apply_twice(f, x) f(f(x));
+apply_twice(x -> x * 3, 2)";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(18));
+    }
+
+    #[test]
+    fn test_lambda_captures_scope_without_naming_it() {
+        let mut env = Env::default();
+        let src = "fn make_adder(n) x -> x + n;
+val add5 = make_adder(5);
+add5(1)";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(6));
+
+        // the lambda did not leave any binding behind
+        let err = parse("x").unwrap().eval(&mut env).unwrap_err();
+        assert!(matches!(err, Error::UnboundName { .. }));
+    }
+
+    #[test]
+    fn test_lambda_arity_mismatch() {
+        let err = parse("val f = (a, b) -> a; f(1)")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::ArityMismatch {
+                expected: 2,
+                found: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_return_leaves_loops_and_blocks() {
+        let mut env = Env::default();
+        let src = "fn find(items, wanted) {
+    mut i = 0;
+    for item in items {
+        if item == wanted { return i }
+        i += 1
+    }
+    return -1
+}
+find([4, 8, 15], 8) * 10 + find([4], 16)";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(9));
+    }
+
+    #[test]
+    fn test_return_without_value() {
+        let mut env = Env::default();
+        let src = "mut calls = 0;
+fn bump(skip) {
+    if skip { return }
+    calls += 1
+}
+bump(true); bump(false); bump(true);
+calls";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(1));
+    }
+
+    #[test]
+    fn test_return_from_lambda() {
+        let mut env = Env::default();
+        let src = "val sign = n -> { if n < 0 { return -1 }; 1 };
+sign(-5) + sign(5)";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(0));
+    }
+
+    #[test]
+    fn test_return_outside_function() {
+        let err = parse("val x = 1;\nwhile true { return x }")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            Error::ReturnOutsideFunction {
+                span: Span {
+                    start: 24,
+                    end: 32,
+                    line: 2,
+                    column: 14,
+                    ..Span::default()
+                },
+            }
+        );
+        assert_eq!(err.message(), "`return` outside of a function");
+    }
+
+    #[test]
+    fn test_question_outside_function() {
+        let err = parse("try { none? } catch e { e }")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+
+        assert_eq!(err.kind(), "question_outside_function");
+        assert_eq!(err.message(), "`?` outside of a function");
+        assert_eq!((err.span().start, err.span().end), (6, 11));
+    }
+
+    #[test]
+    fn test_loop_breaks_with_value() {
+        let mut env = Env::default();
+        let src = "mut i = 0;
+val found = loop {
+    i += 1
+    if i * i > 50 { break i }
+};
+found";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(8));
+    }
+
+    #[test]
+    fn test_loop_with_parenthesized_body() {
+        let mut env = Env::default();
+        let src = "mut i = 0; loop({ i += 1; if i == 3 { break } })";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Unit);
+    }
+
+    #[test]
+    fn test_continue_skips_rest_of_body() {
+        let mut env = Env::default();
+        let src = "mut odd = 0;
+for n in range(10) {
+    if n / 2 * 2 == n { continue }
+    odd += n
+}
+mut i = 0;
+while true {
+    i += 1
+    if i < 4 { continue }
+    break odd + i
+}";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(29));
+    }
+
+    #[test]
+    fn test_break_ends_only_innermost_loop() {
+        let mut env = Env::default();
+        let src = "mut pairs = 0;
+for a in range(4) {
+    for b in range(4) {
+        if b > a { break }
+        pairs += 1
+    }
+}
+pairs";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(10));
+    }
+
+    #[test]
+    fn test_break_outside_loop() {
+        let err = parse("break 1")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+        assert!(matches!(err, Error::BreakOutsideLoop { .. }));
+
+        // a function called from a loop can't end it
+        let err = parse("fn stop() { continue }; loop { stop() }")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+        assert_eq!(err.message(), "`continue` outside of a loop");
+        assert_eq!(err.span().column, 13);
+    }
+
+    #[test]
+    fn test_inline_if_in_bindings_and_arguments() {
+        let mut env = Env::default();
+        let src = "fn max(a, b) if a > b ? a : b;
+val y = 3;
+val x = if y > 0 ? \"hi\" : \"bye\";
+val maybe = if y > 5 ? \"big\";
+f\"{x} {max(y, if y < 0 ? 10 : 1)} {maybe}\"";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::String("hi 3 none".into()));
+    }
+
+    #[test]
+    fn test_for_over_ranges() {
+        let mut env = Env::default();
+        let src = "mut total = 0;
+for num in 0..3 { total += num }
+for num in 1..=3 { total += num * 10 }
+for num in 100..0 step -25 { total += num * 100 }
+for num in range(1000000000000) { if num == 2 { break } }
+total";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(3 + 60 + 25000));
+    }
+
+    #[test]
+    fn test_index_assignment() {
+        let mut env = Env::default();
+        let src = "mut xs = [1, 2, 3];
+val copy = xs;
+xs[0] = 10;
+xs[-1] += 20;
+for i in 0..len(xs) { xs[i] *= 2 }
+f\"{xs} {copy} {xs[1..3]}\"";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::String("[20, 4, 46] [1, 2, 3] [4, 46]".into()));
+    }
+
+    #[test]
+    fn test_compound_index_assignment_evaluates_index_once() {
+        let mut env = Env::default();
+        let src = "mut calls = 0;
+fn next() { calls += 1; calls }
+mut xs = [10, 20, 30];
+xs[next()] += 5;
+f\"{xs} {calls}\"";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::String("[10, 25, 30] 1".into()));
+    }
+
+    #[test]
+    fn test_mutating_methods_update_binding() {
+        let mut env = Env::default();
+        let src = "mut xs = [3, 1];
+val before = xs;
+xs.push(4);
+xs.insert(0, 9);
+val last = xs.pop();
+mut grid = [[1], [2]];
+grid[1].push(20);
+xs
+    .map(n -> n * 2)
+    .join(\",\") + f\" {before} {last} {grid}\"";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(
+            result,
+            Val::String("18,6,2 [3, 1] some(4) [[1], [2, 20]]".into())
+        );
+    }
+
+    #[test]
+    fn test_mutating_methods_through_fields() {
+        let mut env = Env::default();
+        let src = r#"type Bag { items: list(int) }
+mut pair = $([1], 2)
+pair.0.push(5)
+mut bags = [Bag { items = [] }]
+bags[0].items.push(3)
+f"{pair} {bags[0].items}""#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::String("([1, 5], 2) [3]".into()));
+    }
+
+    #[test]
+    fn test_method_arguments_run_before_target_is_read() {
+        let mut env = Env::default();
+        let src = "mut xs = [1, 2]\nxs.push(xs.pop().unwrap())\nxs";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::List(vec![Val::Number(1), Val::Number(2)]));
+    }
+
+    #[test]
+    fn test_maps() {
+        let mut env = Env::default();
+        let src = "mut ages = [\"zed\": 30, \"amy\": 25];
+ages[\"bob\"] = 41;
+ages[\"zed\"] += 1;
+ages.remove(\"amy\");
+ages.insert(\"cat\", 2);
+mut lines = [];
+for (name, age) in ages { lines.push(f\"{name}={age}\") }
+mut nested = [\"xs\": [1]];
+nested[\"xs\"].push(2);
+lines.join(\" \") + f\" {len(ages)} {ages} {nested}\"";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(
+            result,
+            Val::String(
+                "zed=31 bob=41 cat=2 3 [\"zed\": 31, \"bob\": 41, \"cat\": 2] [\"xs\": [1, 2]]"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_tuples_and_destructuring() {
+        let mut env = Env::default();
+        let src = "val pair = $(\"x\", [1, 2]);
+val (label, [low, high]) = pair;
+fn span((from, to)) to - from
+val swap = (p) -> $(p.1, p.0);
+mut total = 0;
+for (i, n) in [10, 20].enumerate() { total += i * n }
+f\"{label} {low + high} {span($(3, 10))} {swap(pair)} {total} {pair.1}\"";
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(
+            result,
+            Val::String("x 3 7 ([1, 2], \"x\") 20 [1, 2]".into())
+        );
+    }
+
+    #[test]
+    fn test_destructuring_mismatch() {
+        let err = parse("val x = 1;\nval [a, b] = [1, 2, 3]")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+
+        assert_eq!(err.message(), "Pattern expects 2 items, got 3");
+        assert_eq!(err.span().line, 2);
+
+        let err = parse("for (a, b) in [1] {}")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+        assert!(matches!(err, Error::TypeMismatch { ref found, .. } if found == "int"));
+    }
+
+    #[test]
+    fn test_chars() {
+        let mut env = Env::default();
+        let src = r#"mut caps = 0;
+mut out = "";
+for c in "Nara é" {
+    if c >= 'A' && c <= 'Z' { caps += 1 }
+    if c != ' ' { out = out + c }
+}
+val word = "nara";
+f"{caps} {out} {word[0] == 'n'} {'a' in word} {['\u{e9}': 1]}""#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::String("1 Naraé true true ['é': 1]".into()));
+    }
+
+    #[test]
+    fn test_opt_and_res_propagation() {
+        let mut env = Env::default();
+        let src = r#"val checked = x -> if x > 8 ? ok(x * x) : err("whoops too small");
+fn sum_squares(a, b) {
+    val total = checked(a)? + checked(b)?;
+    ok(total)
+}
+fn first_even(xs) {
+    for x in xs { if x / 2 * 2 == x { return some(x) } }
+    none
+}
+fn double_first_even(xs) some(first_even(xs)? * 2)
+val big = if 10 > 8 ? checked(9)?;
+f"{sum_squares(9, 10)} {sum_squares(9, 2)} {double_first_even([3, 4])} {double_first_even([3])} {big}""#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(
+            result,
+            Val::String(r#"ok(181) err("whoops too small") some(8) none some(81)"#.into())
+        );
+    }
+
+    #[test]
+    fn test_propagation_before_a_block_body() {
+        let mut env = Env::default();
+        let src = r#"fn pick(a) { if a? { 1 } else { 2 } }
+fn count(flag) {
+    mut n = 0;
+    mut left = 3;
+    while flag? && left > 0 { n = n + 1; left = left - 1 }
+    ok(n)
+}
+val inline = if true ? { 3 } : { 4 };
+f"{pick(some(true))} {pick(some(false))} {pick(none)} {count(ok(true))} {inline}""#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::String("1 2 none ok(3) 3".into()));
+    }
+
+    #[test]
+    fn test_try_catch_recovers() {
+        let mut env = Env::default();
+        let src = r#"fn add(a, b) a + b
+fn first_positive(xs) {
+    for x in xs { try { if x > 0 { return x } } catch e { } }
+    0
+}
+mut log = [];
+for input in [2, 0, "x"] {
+    val line = try { f"{10 / input}" } catch error { error.kind };
+    log.push(line);
+}
+val arity = try { add(1) } catch e { e.message };
+f"{log} {arity} {first_positive([-1, 7])}""#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(
+            result,
+            Val::String(
+                r#"["5", "division_by_zero", "invalid_operands"] Function 'add' expects 2 arguments, got 1 7"#
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_match_expressions() {
+        let mut env = Env::default();
+        let src = r#"fn describe(value) {
+    match value {
+        0: "zero"
+        some(x): f"some {x}"
+        err(e): "failed: " + e
+        [first, ..rest]: f"{first} and {len(rest)} more"
+        (_, 1): "ends in one"
+        n if n < 0: "negative"
+        _: "something else"
+    }
+}
+val inputs = [0, -3, some(2), err("boom"), [1, 2, 3], $(5, 1), 4];
+mut out = [];
+for input in inputs { out.push(describe(input)) }
+out.join(", ")"#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(
+            result,
+            Val::String(
+                "zero, negative, some 2, failed: boom, 1 and 2 more, ends in one, something else"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_type_declarations() {
+        let mut env = Env::default();
+        let src = r#"type Worker {
+    @Developer
+    @NoJob
+    @Other(str)
+
+    name: str
+    age: int
+    eyes: int = 2
+}
+fn greet(worker) {
+    match worker {
+        @Developer: "Hello, developer " + worker.name
+        @NoJob: "Hello, unemployed person"
+        @Other(job): "Hello, " + job + " " + worker.name
+    }
+}
+val me = Worker@Developer {
+    name = "Kiah",
+    age = 20,
+}
+val brother = Worker@Other("home builder") { name = "Ezra", age = 23 }
+f"{greet(me)}; {greet(brother)}; {me.eyes}""#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(
+            result,
+            Val::String("Hello, developer Kiah; Hello, home builder Ezra; 2".into())
+        );
+    }
+
+    #[test]
+    fn test_named_and_default_args() {
+        let mut env = Env::default();
+        let src = r#"fn greet(name, greeting = "Hello", end = "!") f"{greeting}, {name}{end}"
+fn area(w, h = w) w * h
+[greet("Kiah"), greet("Ezra", end = "?"), greet(end = ".", name = "Ada", greeting = "Hi")]"#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(
+            result.to_string(),
+            r#"["Hello, Kiah!", "Hello, Ezra?", "Hi, Ada."]"#
+        );
+
+        let result = parse("area(3)").unwrap().eval(&mut env).unwrap();
+        assert_eq!(result, Val::Number(9));
+
+        for (src, message) in [
+            (
+                "greet(\"Kiah\", mood = 1)",
+                "Function 'greet' has no parameter 'mood'",
+            ),
+            (
+                "greet(\"Kiah\", name = \"Ezra\")",
+                "Argument 'name' of function 'greet' is given twice",
+            ),
+            (
+                "greet(end = \"?\")",
+                "Function 'greet' is missing argument 'name'",
+            ),
+        ] {
+            let err = parse(src).unwrap().eval(&mut env).unwrap_err();
+            assert_eq!(err.message(), message);
+        }
+    }
+
+    #[test]
+    fn test_protocols() {
+        let mut env = Env::default();
+        let src = r#"proto Describe {
+    type Part
+    fn describe(self): str
+    fn parts(self): list(Part)
+}
+type Point { x: int, y: int }
+impl Describe(Point) {
+    type Part = int
+    fn describe(self) f"point at {self.x}, {self.y}"
+    fn parts(self) [self.x, self.y]
+}
+impl Describe(str) {
+    type Part = char
+    fn describe(self) f"text of {self.len()} chars"
+    fn parts(self) self.chars()
+}
+fn summary(item) f"{item.describe()} with {len(item.parts())} parts"
+[summary(Point { x = 1, y = 2 }), summary("abc")]"#;
+        let result = parse(src).unwrap().eval(&mut env).unwrap();
+        assert_eq!(
+            result.to_string(),
+            r#"["point at 1, 2 with 2 parts", "text of 3 chars with 3 parts"]"#
+        );
+
+        let err = parse("impl Describe(list) { type Part = int\nfn describe(self) \"\" }")
+            .unwrap()
+            .eval(&mut env)
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Impl Describe(list) is missing method 'parts'"
+        );
+        assert_eq!(err.kind(), "missing_method");
+    }
+
+    #[test]
+    fn test_check_before_running() {
+        let src = "fn mean(xs: list(float)): float {
+    mut total = 0.0
+    for x in xs { total += x }
+    total / len(xs)
+}
+val m: str = mean([1.0, 2.0])
+mean([1, 2], 3)";
+        let errors = check(src).unwrap_err();
+        assert_eq!(
+            errors.iter().map(|err| err.message()).collect::<Vec<_>>(),
+            [
+                "Type error: cannot apply operator `/` to float and int",
+                "Type error: expected str, found float",
+                "Function 'mean' expects 1 argument, got 2",
+            ]
+        );
+        assert_eq!(errors[0].span().line, 4);
+
+        let parse = check("fn mean(xs: list(float)): float xs[0] / 2.0\nmean([3.0])").unwrap();
+        assert_eq!(parse.eval(&mut Env::default()), Ok(Val::Float(1.5)));
+    }
+
+    #[test]
+    fn test_check_reports_unknown_annotations() {
+        let errors = check("val x: itn = \"a\"\nval ys: list(int, str) = []").unwrap_err();
+        assert_eq!(
+            errors.iter().map(|err| err.message()).collect::<Vec<_>>(),
+            [
+                "Unknown type 'itn'",
+                "Type 'list' takes at most 1 argument, got 2"
+            ]
+        );
+        assert_eq!(errors[1].span().line, 2);
+
+        let src = r#"proto Describe {
+    type Part
+    fn parts(self): list(Part)
+}
+type Point { x: int, y: int }
+impl Describe(Point) {
+    type Part = int
+    fn parts(self): list(Part) [self.x, self.y]
+}
+fn first(item: Describe): opt(int) item.parts().first()"#;
+        assert!(check(src).is_ok());
+
+        let mut env = Env::default();
+        check_in(src, &env).unwrap().eval(&mut env).unwrap();
+        assert!(check("val p: Point = Point { x = 1, y = 2 }").is_err());
+        assert!(check_in("val p: Point = Point { x = 1, y = 2 }", &env).is_ok());
+    }
+
+    #[test]
+    fn test_mutating_method_on_val_binding() {
+        let err = parse("val xs = [1];\nxs.push(2)")
+            .unwrap()
+            .eval(&mut Env::default())
+            .unwrap_err();
+
+        assert!(matches!(err, Error::MutateImmutable { ref name, .. } if name == "xs"));
+        assert_eq!(err.message(), "cannot mutate immutable binding 'xs'");
+        assert_eq!(err.span().line, 2);
+    }
 }
@@ -0,0 +1,119 @@
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    expression::Expression,
+    lexer::{Span, Token, TokenKind},
+    utils,
+    val::Val,
+};
+
+// `break` or `break value`, ends the innermost loop. the loop evaluates to the
+// value, or to unit without one
+#[derive(Debug, Clone)]
+pub(crate) struct Break {
+    pub(crate) val: Option<Expression>,
+    pub(crate) span: Span,
+}
+
+// `continue`, skips the rest of the innermost loop's body
+#[derive(Debug, Clone)]
+pub(crate) struct Continue {
+    pub(crate) span: Span,
+}
+
+// spans are not part of a statement's identity, see `Expression`
+impl PartialEq for Break {
+    fn eq(&self, other: &Self) -> bool {
+        self.val == other.val
+    }
+}
+
+impl PartialEq for Continue {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Break {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let tokens = utils::tag(&TokenKind::Break, tokens)?;
+        let (tokens, val) = utils::extract_jump_value(&start[0], tokens)?;
+        let tokens = utils::extract_semicolon(tokens);
+
+        Ok((
+            tokens,
+            Self {
+                val,
+                span: utils::consumed_span(start, tokens),
+            },
+        ))
+    }
+
+    pub(crate) fn eval(&self, env: &Env) -> Result<(), Unwind> {
+        let val = match &self.val {
+            Some(val) => val.eval(env)?,
+            None => Val::Unit,
+        };
+
+        Err(Unwind::Break(val, self.span.clone()))
+    }
+}
+
+impl Continue {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let tokens = utils::tag(&TokenKind::Continue, tokens)?;
+        let tokens = utils::extract_semicolon(tokens);
+
+        Ok((
+            tokens,
+            Self {
+                span: utils::consumed_span(start, tokens),
+            },
+        ))
+    }
+
+    pub(crate) fn eval(&self) -> Result<(), Unwind> {
+        Err(Unwind::Continue(self.span.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::{ExpressionKind, Number};
+
+    #[test]
+    fn parse_break_with_value() {
+        assert_eq!(
+            utils::parse_str(Break::new, "break 42;"),
+            Ok(Break {
+                val: Some(Expression::from(ExpressionKind::Number(Number(42)))),
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_bare_break_and_continue() {
+        assert_eq!(
+            utils::parse_str(Break::new, "break"),
+            Ok(Break {
+                val: None,
+                span: Span::default(),
+            })
+        );
+        assert!(utils::parse_str(Continue::new, "continue;").is_ok());
+    }
+
+    #[test]
+    fn eval_break_unwinds_with_value() {
+        let brk = utils::parse_str(Break::new, "break 2 * 3").unwrap();
+
+        assert!(matches!(
+            brk.eval(&Env::default()),
+            Err(Unwind::Break(Val::Number(6), _))
+        ));
+    }
+}
@@ -0,0 +1,354 @@
+use crate::{
+    env::Env,
+    error::Error,
+    lexer::{Span, Token, TokenKind},
+    utils,
+    val::Val,
+};
+
+// left hand side of a binding, a parameter or a `for` loop variable, and what
+// a `match` arm compares its value with. a name, `_`, a literal, `some(p)`,
+// `ok(p)` and `err(p)`, or `(a, b)` and `[a, b]` taking a tuple or a list of
+// that many items apart. `[first, ..rest]` takes at least that many items and
+// matches the others with the rest pattern. `@Other(job)` matches a variant
+// of a user defined type and its payload. patterns nest, like `(key, [x, y])`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Pattern {
+    Name(String),
+    Wildcard,
+    Literal(Val),
+    Some(Box<Pattern>),
+    Ok(Box<Pattern>),
+    Err(Box<Pattern>),
+    Variant(String, Vec<Pattern>),
+    Tuple(Vec<Pattern>),
+    List(Vec<Pattern>, Option<Box<Pattern>>),
+}
+
+impl Pattern {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let literal = |val| Ok((&tokens[1..], Self::Literal(val)));
+
+        match utils::peek(tokens) {
+            TokenKind::LParen | TokenKind::LBracket => Self::new_items(tokens),
+            TokenKind::At => Self::new_variant(tokens),
+            TokenKind::Number(n) => literal(Val::Number(*n)),
+            TokenKind::Float(n) => literal(Val::Float(*n)),
+            TokenKind::String(s) => literal(Val::String(s.as_str().into())),
+            TokenKind::Char(c) => literal(Val::Char(*c)),
+            TokenKind::True => literal(Val::Bool(true)),
+            TokenKind::False => literal(Val::Bool(false)),
+            TokenKind::None => literal(Val::Opt(None)),
+            TokenKind::Minus => match utils::peek(&tokens[1..]) {
+                TokenKind::Number(n) => Ok((&tokens[2..], Self::Literal(Val::Number(-n)))),
+                TokenKind::Float(n) => Ok((&tokens[2..], Self::Literal(Val::Float(-n)))),
+                _ => Err(utils::expected("number", &tokens[1..])),
+            },
+            TokenKind::Ident(name) if name == "_" => Ok((&tokens[1..], Self::Wildcard)),
+            TokenKind::Ident(name)
+                if matches!(name.as_str(), "some" | "ok" | "err")
+                    && tokens[1].kind == TokenKind::LParen =>
+            {
+                let (rest, inner) = Self::new(&tokens[2..])?;
+                let rest = utils::tag(&TokenKind::RParen, rest)?;
+                let inner = Box::new(inner);
+
+                let pattern = match name.as_str() {
+                    "some" => Self::Some(inner),
+                    "ok" => Self::Ok(inner),
+                    _ => Self::Err(inner),
+                };
+                Ok((rest, pattern))
+            }
+            _ => {
+                let (tokens, name) = utils::extract_ident(tokens)?;
+                Ok((tokens, Self::Name(name)))
+            }
+        }
+    }
+
+    // `@Name`, or `@Name(a, b)` with a pattern for each item of the payload
+    fn new_variant(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (mut tokens, name) = utils::extract_ident(&tokens[1..])?;
+
+        let mut payload = Vec::new();
+        if let Ok(rest) = utils::tag(&TokenKind::LParen, tokens) {
+            tokens = rest;
+            loop {
+                let (rest, item) = Self::new(tokens)?;
+                payload.push(item);
+
+                match utils::peek(rest) {
+                    TokenKind::Comma => tokens = &rest[1..],
+                    TokenKind::RParen => {
+                        tokens = &rest[1..];
+                        break;
+                    }
+                    _ => return Err(utils::expected("',' or ')' in variant pattern", rest)),
+                }
+            }
+        }
+
+        Ok((tokens, Self::Variant(name, payload)))
+    }
+
+    // `(a, b)` or `[a, b]`, a list may end in `..rest` or `..`
+    fn new_items(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (close, what) = match utils::peek(tokens) {
+            TokenKind::LParen => (TokenKind::RParen, "',' or ')' in tuple pattern"),
+            _ => (TokenKind::RBracket, "',' or ']' in list pattern"),
+        };
+
+        let mut tokens = &tokens[1..];
+        let mut items = Vec::new();
+        let mut rest_pattern = None;
+
+        loop {
+            if let Ok(rest) = utils::tag(&close, tokens) {
+                tokens = rest;
+                break;
+            }
+
+            if close == TokenKind::RBracket
+                && let Ok(rest) = utils::tag(&TokenKind::DotDot, tokens)
+            {
+                let (rest, pattern) = match utils::peek(rest) {
+                    TokenKind::RBracket => (rest, Self::Wildcard),
+                    _ => Self::new(rest)?,
+                };
+                rest_pattern = Some(Box::new(pattern));
+
+                // nothing comes after the rest
+                tokens = utils::tag(&close, rest)?;
+                break;
+            }
+
+            let (rest, item) = Self::new(tokens)?;
+            items.push(item);
+
+            match utils::peek(rest) {
+                TokenKind::Comma => tokens = &rest[1..],
+                kind if *kind == close => {
+                    tokens = &rest[1..];
+                    break;
+                }
+                _ => return Err(utils::expected(what, rest)),
+            }
+        }
+
+        match close {
+            TokenKind::RParen => Ok((tokens, Self::Tuple(items))),
+            _ => Ok((tokens, Self::List(items, rest_pattern))),
+        }
+    }
+
+    // define the names of the pattern in the current frame of `env`. the
+    // errors have no span yet, the statement doing the binding fills it in
+    pub(crate) fn bind(&self, val: Val, env: &mut Env, mutable: bool) -> Result<(), Error> {
+        let (patterns, items) = match (self, val) {
+            (Self::Wildcard, _) => return Ok(()),
+            (Self::Name(name), val) => {
+                env.define(name.clone(), Some(val), mutable);
+                return Ok(());
+            }
+            (Self::Tuple(patterns), Val::Tuple(items)) => (patterns, items),
+            (Self::List(patterns, None), Val::List(items)) => (patterns, items),
+            (Self::Tuple(_), other) => return Err(mismatch("tuple", &other)),
+            (Self::List(..), other) if !matches!(other, Val::List(_)) => {
+                return Err(mismatch("list", &other));
+            }
+            // literals, `some(x)` and a list with a rest may not match
+            (pattern, val) => {
+                let mut bindings = Vec::new();
+                if !pattern.matches(&val, &mut bindings) {
+                    return Err(Error::NoMatch {
+                        val: val.repr(),
+                        span: Span::default(),
+                    });
+                }
+
+                for (name, val) in bindings {
+                    env.define(name, Some(val), mutable);
+                }
+                return Ok(());
+            }
+        };
+
+        if patterns.len() != items.len() {
+            return Err(Error::DestructureMismatch {
+                expected: patterns.len(),
+                found: items.len(),
+                span: Span::default(),
+            });
+        }
+
+        for (pattern, item) in patterns.iter().zip(items) {
+            pattern.bind(item, env, mutable)?;
+        }
+        Ok(())
+    }
+
+    // whether `val` has the shape of the pattern, collecting the values of
+    // the names it binds
+    pub(crate) fn matches(&self, val: &Val, bindings: &mut Vec<(String, Val)>) -> bool {
+        match (self, val) {
+            (Self::Wildcard, _) => true,
+            (Self::Name(name), val) => {
+                bindings.push((name.clone(), val.clone()));
+                true
+            }
+            (Self::Literal(literal), val) => literal == val,
+            (Self::Some(inner), Val::Opt(Some(val)))
+            | (Self::Ok(inner), Val::Res(Ok(val)))
+            | (Self::Err(inner), Val::Res(Err(val))) => inner.matches(val, bindings),
+            (Self::Variant(name, patterns), Val::Instance(instance)) => {
+                instance.variant() == Some(name.as_str())
+                    && patterns.len() == instance.payload.len()
+                    && Self::match_items(patterns, &instance.payload, bindings)
+            }
+            (Self::Tuple(patterns), Val::Tuple(items)) => {
+                patterns.len() == items.len() && Self::match_items(patterns, items, bindings)
+            }
+            (Self::List(patterns, None), Val::List(items)) => {
+                patterns.len() == items.len() && Self::match_items(patterns, items, bindings)
+            }
+            (Self::List(patterns, Some(rest)), Val::List(items)) => {
+                items.len() >= patterns.len()
+                    && Self::match_items(patterns, items, bindings)
+                    && rest.matches(&Val::List(items[patterns.len()..].to_vec()), bindings)
+            }
+            _ => false,
+        }
+    }
+
+    fn match_items(patterns: &[Self], items: &[Val], bindings: &mut Vec<(String, Val)>) -> bool {
+        patterns
+            .iter()
+            .zip(items)
+            .all(|(pattern, item)| pattern.matches(item, bindings))
+    }
+}
+
+fn mismatch(expected: &str, found: &Val) -> Error {
+    Error::TypeMismatch {
+        expected: expected.to_string(),
+        found: found.type_name().to_string(),
+        span: Span::default(),
+    }
+}
+
+// plain name, for trees built in tests
+#[cfg(test)]
+impl From<&str> for Pattern {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, val: Val) -> Option<Vec<(String, Val)>> {
+        let pattern = utils::parse_str(Pattern::new, pattern).unwrap();
+        let mut bindings = Vec::new();
+
+        pattern.matches(&val, &mut bindings).then_some(bindings)
+    }
+
+    fn list(items: &[i64]) -> Val {
+        Val::List(items.iter().copied().map(Val::Number).collect())
+    }
+
+    #[test]
+    fn parse_nested_pattern() {
+        assert_eq!(
+            utils::parse_str(Pattern::new, "(key, [x, y],)"),
+            Ok(Pattern::Tuple(vec![
+                Pattern::from("key"),
+                Pattern::List(vec![Pattern::from("x"), Pattern::from("y")], None),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_refutable_patterns() {
+        assert_eq!(
+            utils::parse_str(Pattern::new, "[-1, some(_), ..rest]"),
+            Ok(Pattern::List(
+                vec![
+                    Pattern::Literal(Val::Number(-1)),
+                    Pattern::Some(Box::new(Pattern::Wildcard)),
+                ],
+                Some(Box::new(Pattern::from("rest"))),
+            ))
+        );
+        assert!(utils::parse_str(Pattern::new, "[..rest, last]").is_err());
+    }
+
+    #[test]
+    fn parse_unclosed_pattern() {
+        let err = utils::parse_str(Pattern::new, "(a b)").unwrap_err();
+
+        assert_eq!(
+            err.message(),
+            "expected ',' or ')' in tuple pattern, found identifier `b`"
+        );
+    }
+
+    #[test]
+    fn match_list_with_rest() {
+        assert_eq!(
+            matches("[first, ..rest]", list(&[1, 2, 3])),
+            Some(vec![
+                ("first".to_string(), Val::Number(1)),
+                ("rest".to_string(), list(&[2, 3])),
+            ])
+        );
+        assert_eq!(matches("[_, ..]", list(&[])), None);
+        assert_eq!(matches("[1, ..]", list(&[1])), Some(vec![]));
+    }
+
+    #[test]
+    fn match_literals_and_variants() {
+        assert!(matches("\"a\"", Val::String("a".into())).is_some());
+        assert!(matches("none", Val::Opt(None)).is_some());
+        assert!(matches("ok(x)", Val::Res(Err(Box::new(Val::Unit)))).is_none());
+        assert_eq!(
+            matches("err(e)", Val::Res(Err(Box::new(Val::Number(1))))),
+            Some(vec![("e".to_string(), Val::Number(1))])
+        );
+    }
+
+    #[test]
+    fn bind_destructures_values() {
+        let mut env = Env::default();
+        let pattern = utils::parse_str(Pattern::new, "(a, [b, c])").unwrap();
+        let val = Val::Tuple(vec![
+            Val::Number(1),
+            Val::List(vec![Val::Number(2), Val::Number(3)]),
+        ]);
+
+        assert_eq!(pattern.bind(val, &mut env, false), Ok(()));
+        assert_eq!(env.get_binding_value_restrict("a"), Ok(Val::Number(1)));
+        assert_eq!(env.get_binding_value_restrict("c"), Ok(Val::Number(3)));
+    }
+
+    #[test]
+    fn bind_with_wrong_number_of_items() {
+        let pattern = utils::parse_str(Pattern::new, "[a, b]").unwrap();
+        let val = Val::List(vec![Val::Number(1)]);
+
+        let err = pattern.bind(val, &mut Env::default(), false).unwrap_err();
+        assert_eq!(err.message(), "Pattern expects 2 items, got 1");
+    }
+
+    #[test]
+    fn bind_refutable_pattern() {
+        let mut env = Env::default();
+        let pattern = utils::parse_str(Pattern::new, "some(x)").unwrap();
+
+        let err = pattern.bind(Val::Opt(None), &mut env, false).unwrap_err();
+        assert_eq!(err.message(), "No pattern matches none");
+    }
+}
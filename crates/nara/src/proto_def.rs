@@ -0,0 +1,152 @@
+use crate::{
+    error::Error,
+    function_def::Param,
+    lexer::{Span, Token, TokenKind},
+    type_expr::TypeExpr,
+    utils,
+};
+
+// `proto Name { type Item fn next(self): opt(Item) }`. a protocol lists the
+// associated types and the methods an `impl` of it has to provide. the
+// first parameter of a method is the value it is called on
+#[derive(Debug, Clone)]
+pub(crate) struct ProtoDef {
+    pub(crate) name: String,
+    pub(crate) types: Vec<String>,
+    pub(crate) methods: Vec<MethodSig>,
+    // not read by the interpreter yet, kept for diagnostics and tooling
+    #[allow(dead_code)]
+    pub(crate) span: Span,
+}
+
+// spans are not part of a protocol's identity, see `Expression`
+impl PartialEq for ProtoDef {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.types == other.types && self.methods == other.methods
+    }
+}
+
+// `fn name(params): ret`, a method without a body
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MethodSig {
+    pub(crate) name: String,
+    pub(crate) params: Vec<Param>,
+    pub(crate) ret: Option<TypeExpr>,
+}
+
+impl ProtoDef {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let tokens = utils::tag(&TokenKind::Proto, tokens)?;
+        let (tokens, name) = utils::extract_ident(tokens)?;
+        let mut tokens = utils::tag(&TokenKind::LBrace, tokens)?;
+
+        let mut types: Vec<String> = Vec::new();
+        let mut methods: Vec<MethodSig> = Vec::new();
+
+        // one member per line, or separated by commas
+        while *utils::peek(tokens) != TokenKind::RBrace {
+            let member = tokens;
+
+            tokens = match utils::peek(tokens) {
+                TokenKind::Type => {
+                    let (rest, name) = utils::extract_ident(&tokens[1..])?;
+                    if types.contains(&name) {
+                        return Err(utils::declared_twice("type", &name, member));
+                    }
+
+                    types.push(name);
+                    rest
+                }
+                TokenKind::Fn => {
+                    let (rest, method) = MethodSig::new(tokens)?;
+                    if methods.iter().any(|m| m.name == method.name) {
+                        return Err(utils::declared_twice("method", &method.name, member));
+                    }
+
+                    methods.push(method);
+                    rest
+                }
+                _ => return Err(utils::expected("`type` or `fn`", tokens)),
+            };
+
+            tokens = utils::tag(&TokenKind::Comma, tokens).unwrap_or(tokens);
+        }
+        let tokens = utils::tag(&TokenKind::RBrace, tokens)?;
+        let tokens = utils::extract_semicolon(tokens);
+
+        Ok((
+            tokens,
+            Self {
+                name,
+                types,
+                methods,
+                span: utils::consumed_span(start, tokens),
+            },
+        ))
+    }
+}
+
+impl MethodSig {
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let tokens = utils::tag(&TokenKind::Fn, tokens)?;
+        let (tokens, name) = utils::extract_ident(tokens)?;
+        let (tokens, params) = utils::extract_params(tokens)?;
+
+        let (tokens, ret) = match utils::tag(&TokenKind::Colon, tokens) {
+            Ok(tokens) => TypeExpr::new(tokens).map(|(tokens, ret)| (tokens, Some(ret)))?,
+            Err(_) => (tokens, None),
+        };
+
+        Ok((tokens, Self { name, params, ret }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_proto_def() {
+        assert_eq!(
+            utils::parse_str(
+                ProtoDef::new,
+                "proto Iterator {
+                    type Item
+                    fn next(self): opt(Item)
+                }"
+            ),
+            Ok(ProtoDef {
+                name: "Iterator".to_string(),
+                types: vec!["Item".to_string()],
+                methods: vec![MethodSig {
+                    name: "next".to_string(),
+                    params: vec![Param::from("self")],
+                    ret: Some(TypeExpr {
+                        name: "opt".to_string(),
+                        args: vec![TypeExpr {
+                            name: "Item".to_string(),
+                            args: Vec::new(),
+                            span: Span::default(),
+                        }],
+                        span: Span::default(),
+                    }),
+                }],
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn cannot_declare_method_twice() {
+        let err = utils::parse_str(ProtoDef::new, "proto Show { fn show(self), fn show(self) }")
+            .unwrap_err();
+
+        assert_eq!(err.message(), "method `show` is declared twice");
+    }
+
+    #[test]
+    fn methods_have_no_body() {
+        assert!(utils::parse_str(ProtoDef::new, "proto Show { fn show(self) 1 }").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    expression::Expression,
+    lexer::{Span, Token, TokenKind},
+    utils,
+    val::Val,
+};
+
+// `return` or `return value`, leaves the innermost function being called
+#[derive(Debug, Clone)]
+pub(crate) struct Return {
+    pub(crate) val: Option<Expression>,
+    pub(crate) span: Span,
+}
+
+// spans are not part of a statement's identity, see `Expression`
+impl PartialEq for Return {
+    fn eq(&self, other: &Self) -> bool {
+        self.val == other.val
+    }
+}
+
+impl Return {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let tokens = utils::tag(&TokenKind::Return, tokens)?;
+        let (tokens, val) = utils::extract_jump_value(&start[0], tokens)?;
+        let tokens = utils::extract_semicolon(tokens);
+
+        Ok((
+            tokens,
+            Self {
+                val,
+                span: utils::consumed_span(start, tokens),
+            },
+        ))
+    }
+
+    // never finishes normally, the value travels up as `Unwind::Return`
+    pub(crate) fn eval(&self, env: &Env) -> Result<(), Unwind> {
+        let val = match &self.val {
+            Some(val) => val.eval(env)?,
+            None => Val::Unit,
+        };
+
+        Err(Unwind::Return(val, self.span.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::{ExpressionKind, Number};
+
+    #[test]
+    fn parse_return_with_value() {
+        assert_eq!(
+            utils::parse_str(Return::new, "return 5;"),
+            Ok(Return {
+                val: Some(Expression::from(ExpressionKind::Number(Number(5)))),
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_bare_return() {
+        for src in ["return", "return;"] {
+            assert_eq!(
+                utils::parse_str(Return::new, src),
+                Ok(Return {
+                    val: None,
+                    span: Span::default(),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn value_on_next_line_is_not_returned() {
+        let tokens = crate::lexer::lex("return\n5").unwrap();
+        let (rest, ret) = Return::new(&tokens).unwrap();
+
+        assert_eq!(ret.val, None);
+        assert_eq!(rest[0].kind, TokenKind::Number(5));
+    }
+
+    #[test]
+    fn eval_return_unwinds_with_value() {
+        let ret = utils::parse_str(Return::new, "return 1 + 2").unwrap();
+
+        assert!(matches!(
+            ret.eval(&Env::default()),
+            Err(Unwind::Return(Val::Number(3), _))
+        ));
+    }
+}
@@ -1,44 +1,161 @@
+use crate::assignment::Assignment;
 use crate::binding_def::BindingDef;
 use crate::env::Env;
+use crate::error::{Error, Unwind};
 use crate::expression::Expression;
 use crate::function_def::FunctionDef;
+use crate::impl_def::ImplDef;
+use crate::lexer::{Token, TokenKind};
+use crate::loop_control::{Break, Continue};
+use crate::proto_def::ProtoDef;
+use crate::return_statement::Return;
+use crate::type_def::TypeDef;
+use crate::utils;
 use crate::val::Val;
 
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) enum Statement {
     BindingDef(BindingDef),
+    Assignment(Assignment),
     FunctionDef(FunctionDef),
+    TypeDef(TypeDef),
+    ProtoDef(ProtoDef),
+    ImplDef(ImplDef),
+    Return(Return),
+    Break(Break),
+    Continue(Continue),
     Expression(Expression),
+    // statement that failed to parse, kept in place so the rest of the tree
+    // survives. evaluating it raises the parse error
+    Error(Error),
 }
 
 impl Statement {
-    pub(crate) fn new(s: &str) -> Result<(&str, Self), String> {
-        BindingDef::new(s)
-            .map(|(s, binding_def)| (s, Self::BindingDef(binding_def)))
-            .or_else(|_| {
-                FunctionDef::new(s).map(|(s, function_def)| (s, Self::FunctionDef(function_def)))
-            })
-            .or_else(|_| {
-                Expression::new(s).map(|(s, expression)| (s, Self::Expression(expression)))
-            })
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        match utils::peek(tokens) {
+            TokenKind::Val | TokenKind::Mut => BindingDef::new(tokens)
+                .map(|(tokens, binding_def)| (tokens, Self::BindingDef(binding_def))),
+            TokenKind::Ident(_) if tokens[1].kind == TokenKind::Colon => BindingDef::new(tokens)
+                .map(|(tokens, binding_def)| (tokens, Self::BindingDef(binding_def))),
+            TokenKind::Ident(_) if Assignment::starts_at(tokens) => Assignment::new(tokens)
+                .map(|(tokens, assignment)| (tokens, Self::Assignment(assignment))),
+            TokenKind::Fn => FunctionDef::new(tokens)
+                .map(|(tokens, function_def)| (tokens, Self::FunctionDef(function_def))),
+            TokenKind::Type => {
+                TypeDef::new(tokens).map(|(tokens, type_def)| (tokens, Self::TypeDef(type_def)))
+            }
+            TokenKind::Proto => {
+                ProtoDef::new(tokens).map(|(tokens, proto_def)| (tokens, Self::ProtoDef(proto_def)))
+            }
+            TokenKind::Impl => {
+                ImplDef::new(tokens).map(|(tokens, impl_def)| (tokens, Self::ImplDef(impl_def)))
+            }
+            TokenKind::Return => {
+                Return::new(tokens).map(|(tokens, ret)| (tokens, Self::Return(ret)))
+            }
+            TokenKind::Break => Break::new(tokens).map(|(tokens, brk)| (tokens, Self::Break(brk))),
+            TokenKind::Continue => {
+                Continue::new(tokens).map(|(tokens, continue_)| (tokens, Self::Continue(continue_)))
+            }
+            _ => {
+                let (tokens, expression) = Expression::new_standalone(tokens)?;
+                // optional semicolon after expression statements
+                let tokens = utils::extract_semicolon(tokens);
+                Ok((tokens, Self::Expression(expression)))
+            }
+        }
     }
 
-    pub(crate) fn eval(&self, env: &mut Env) -> Result<Val, String> {
+    pub(crate) fn eval(&self, env: &mut Env) -> Result<Val, Unwind> {
         match self {
             Self::BindingDef(binding_def) => {
                 binding_def.eval(env)?;
                 Ok(Val::Unit)
             }
+            Self::Assignment(assignment) => {
+                assignment.eval(env)?;
+                Ok(Val::Unit)
+            }
+            Self::Return(ret) => {
+                ret.eval(env)?;
+                Ok(Val::Unit)
+            }
+            Self::Break(brk) => {
+                brk.eval(env)?;
+                Ok(Val::Unit)
+            }
+            Self::Continue(continue_) => {
+                continue_.eval()?;
+                Ok(Val::Unit)
+            }
             Self::Expression(expression) => expression.eval(env),
             Self::FunctionDef(function_def) => {
                 use std::rc::Rc;
+                // declared before the function captures its scope, so the body
+                // calls this function and not one it shadows
+                env.define(function_def.name.clone(), None, false);
                 let func_val = Val::Function(crate::val::Function {
                     params: function_def.params.clone(),
                     body: Rc::new(*function_def.body.clone()),
+                    env: env.clone(),
+                });
+                env.assign(&function_def.name, func_val)?;
+                Ok(Val::Unit)
+            }
+            Self::TypeDef(type_def) => {
+                let ty = Val::Type(crate::val::Type {
+                    def: std::rc::Rc::new(type_def.clone()),
+                    env: env.clone(),
                 });
-                env.store_binding(function_def.name.clone(), func_val);
+                env.store_binding(type_def.name.clone(), ty);
                 Ok(Val::Unit)
             }
+            Self::ProtoDef(proto_def) => {
+                let proto = Val::Proto(crate::val::Proto {
+                    def: std::rc::Rc::new(proto_def.clone()),
+                });
+                env.store_binding(proto_def.name.clone(), proto);
+                Ok(Val::Unit)
+            }
+            Self::ImplDef(impl_def) => {
+                impl_def.eval(env)?;
+                Ok(Val::Unit)
+            }
+            Self::Error(err) => Err(err.clone().into()),
+        }
+    }
+
+    // parse errors left in the tree, in source order
+    pub(crate) fn collect_errors(&self, errors: &mut Vec<Error>) {
+        match self {
+            Self::BindingDef(binding_def) => {
+                if let Some(val) = &binding_def.val {
+                    val.collect_errors(errors);
+                }
+            }
+            Self::Assignment(assignment) => assignment.val.collect_errors(errors),
+            Self::FunctionDef(function_def) => function_def.body.collect_errors(errors),
+            Self::TypeDef(type_def) => {
+                for field in &type_def.fields {
+                    if let Some(default) = &field.default {
+                        default.collect_errors(errors);
+                    }
+                }
+            }
+            Self::ProtoDef(_) => {}
+            Self::ImplDef(impl_def) => {
+                for method in &impl_def.methods {
+                    method.body.collect_errors(errors);
+                }
+            }
+            Self::Return(Return { val, .. }) | Self::Break(Break { val, .. }) => {
+                if let Some(val) = val {
+                    val.collect_errors(errors);
+                }
+            }
+            Self::Continue(_) => {}
+            Self::Expression(expression) => expression.collect_errors(errors),
+            Self::Error(err) => errors.push(err.clone()),
         }
     }
 }
@@ -47,21 +164,23 @@ impl Statement {
 mod tests {
     use super::*;
     use crate::{
-        expression::{BindingUsage, Block, Number, Op},
-        function_def::FunctionDef,
+        expression::{BindingUsage, Block, ExpressionKind, Number, Op},
+        function_def::{FunctionDef, Param},
+        lexer::Span,
+        pattern::Pattern,
     };
 
     #[test]
     fn parse_binding_def() {
         assert_eq!(
-            Statement::new("val x = 15;"),
-            Ok((
-                "",
-                Statement::BindingDef(BindingDef {
-                    name: "x".to_string(),
-                    val: Expression::Number(Number(15))
-                }),
-            ))
+            utils::parse_str(Statement::new, "val x = 15;"),
+            Ok(Statement::BindingDef(BindingDef {
+                pattern: Pattern::from("x"),
+                mutable: false,
+                ty: None,
+                val: Some(Expression::from(ExpressionKind::Number(Number(15)))),
+                span: Span::default(),
+            }))
         )
     }
 
@@ -69,8 +188,11 @@ mod tests {
     fn eval_binding_def() {
         assert_eq!(
             Statement::BindingDef(BindingDef {
-                name: "some_variable".to_string(),
-                val: Expression::Number(Number(10))
+                pattern: Pattern::from("some_variable"),
+                mutable: false,
+                ty: None,
+                val: Some(Expression::from(ExpressionKind::Number(Number(10)))),
+                span: Span::default(),
             })
             .eval(&mut Env::default()),
             Ok(Val::Unit)
@@ -80,22 +202,22 @@ mod tests {
     #[test]
     fn parse_expression() {
         assert_eq!(
-            Statement::new("10 + 10"),
-            Ok((
-                "",
-                Statement::Expression(Expression::Operation {
-                    lhs: Box::new(Expression::Number(Number(10))),
-                    rhs: Box::new(Expression::Number(Number(10))),
+            utils::parse_str(Statement::new, "10 + 10"),
+            Ok(Statement::Expression(Expression::from(
+                ExpressionKind::Operation {
+                    lhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
+                    rhs: Box::new(Expression::from(ExpressionKind::Number(Number(10)))),
                     op: Op::Add
-                })
-            ))
+                }
+            )))
         )
     }
 
     #[test]
     fn eval_expression() {
         assert_eq!(
-            Statement::Expression(Expression::Number(Number(5))).eval(&mut Env::default()),
+            Statement::Expression(Expression::from(ExpressionKind::Number(Number(5))))
+                .eval(&mut Env::default()),
             Ok(Val::Number(5))
         )
     }
@@ -103,104 +225,134 @@ mod tests {
     #[test]
     fn parse_nested_functions() {
         assert_eq!(
-            Statement::new(
+            utils::parse_str(
+                Statement::new,
                 "fn outer() {
                     fn inner() {3 + 2}
                     inner
                 }"
             ),
-            Ok((
-                "",
-                Statement::FunctionDef(FunctionDef {
-                    name: "outer".to_string(),
-                    params: vec![],
-                    body: Box::new(Statement::Expression(Expression::Block(Block {
+            Ok(Statement::FunctionDef(FunctionDef {
+                name: "outer".to_string(),
+                params: vec![],
+                ret: None,
+                body: Box::new(Statement::Expression(Expression::from(
+                    ExpressionKind::Block(Block {
                         statements: vec![
                             Statement::FunctionDef(FunctionDef {
                                 name: "inner".to_string(),
                                 params: vec![],
-                                body: Box::new(Statement::Expression(Expression::Block(Block {
-                                    statements: vec![Statement::Expression(
-                                        Expression::Operation {
-                                            lhs: Box::new(Expression::Number(Number(3))),
-                                            rhs: Box::new(Expression::Number(Number(2))),
-                                            op: Op::Add
-                                        }
-                                    )]
-                                }))),
+                                ret: None,
+                                body: Box::new(Statement::Expression(Expression::from(
+                                    ExpressionKind::Block(Block {
+                                        statements: vec![Statement::Expression(Expression::from(
+                                            ExpressionKind::Operation {
+                                                lhs: Box::new(Expression::from(
+                                                    ExpressionKind::Number(Number(3))
+                                                )),
+                                                rhs: Box::new(Expression::from(
+                                                    ExpressionKind::Number(Number(2))
+                                                )),
+                                                op: Op::Add
+                                            }
+                                        ))]
+                                    })
+                                ))),
+                                span: Span::default(),
                             }),
-                            Statement::Expression(Expression::BindingUsage(BindingUsage {
-                                name: "inner".to_string()
-                            }))
+                            Statement::Expression(Expression::from(ExpressionKind::BindingUsage(
+                                BindingUsage {
+                                    name: "inner".to_string()
+                                }
+                            )))
                         ],
-                    })))
-                })
-            ))
+                    })
+                ))),
+                span: Span::default(),
+            }))
         )
     }
 
-    // return statement is not yet supported in eval
     #[test]
     fn parse_function_def() {
         assert_eq!(
-            Statement::new(
+            utils::parse_str(
+                Statement::new,
                 "fn semihkedy(param1, param2) {
                     val one = 1;
                     one
                 }"
             ),
-            Ok((
-                "",
-                Statement::FunctionDef(FunctionDef {
-                    name: "semihkedy".to_string(),
-                    params: vec!["param1".to_string(), "param2".to_string()],
-                    body: Box::new(Statement::Expression(Expression::Block(Block {
+            Ok(Statement::FunctionDef(FunctionDef {
+                name: "semihkedy".to_string(),
+                params: vec![Param::from("param1"), Param::from("param2")],
+                ret: None,
+                body: Box::new(Statement::Expression(Expression::from(
+                    ExpressionKind::Block(Block {
                         statements: vec![
                             Statement::BindingDef(BindingDef {
-                                name: "one".to_string(),
-                                val: Expression::Number(Number(1)),
+                                pattern: Pattern::from("one"),
+                                mutable: false,
+                                ty: None,
+                                val: Some(Expression::from(ExpressionKind::Number(Number(1)))),
+                                span: Span::default(),
                             }),
-                            Statement::Expression(Expression::BindingUsage(BindingUsage {
-                                name: "one".to_string(),
-                            })),
+                            Statement::Expression(Expression::from(ExpressionKind::BindingUsage(
+                                BindingUsage {
+                                    name: "one".to_string(),
+                                }
+                            ))),
                         ],
-                    })))
-                })
-            ))
+                    })
+                ))),
+                span: Span::default(),
+            }))
         )
     }
 
     #[test]
     fn parse_function_def_with_operation() {
         assert_eq!(
-            Statement::new("fn operation(par1, par2) 4 + 3"),
-            Ok((
-                "",
-                Statement::FunctionDef(FunctionDef {
-                    name: "operation".to_string(),
-                    params: vec!["par1".to_string(), "par2".to_string()],
-                    body: Box::new(Statement::Expression(Expression::Operation {
-                        lhs: Box::new(Expression::Number(Number(4))),
-                        rhs: Box::new(Expression::Number(Number(3))),
+            utils::parse_str(Statement::new, "fn operation(par1, par2) 4 + 3"),
+            Ok(Statement::FunctionDef(FunctionDef {
+                name: "operation".to_string(),
+                params: vec![Param::from("par1"), Param::from("par2")],
+                ret: None,
+                body: Box::new(Statement::Expression(Expression::from(
+                    ExpressionKind::Operation {
+                        lhs: Box::new(Expression::from(ExpressionKind::Number(Number(4)))),
+                        rhs: Box::new(Expression::from(ExpressionKind::Number(Number(3)))),
                         op: Op::Add
-                    },))
-                })
-            ))
+                    }
+                ),)),
+                span: Span::default(),
+            }))
         )
     }
 
     #[test]
     fn parse_function_def_with_number() {
         assert_eq!(
-            Statement::new("fn number() 42"),
-            Ok((
-                "",
-                Statement::FunctionDef(FunctionDef {
-                    name: "number".to_string(),
-                    params: vec![],
-                    body: Box::new(Statement::Expression(Expression::Number(Number(42)))),
-                }),
-            ))
+            utils::parse_str(Statement::new, "fn number() 42"),
+            Ok(Statement::FunctionDef(FunctionDef {
+                name: "number".to_string(),
+                params: vec![],
+                ret: None,
+                body: Box::new(Statement::Expression(Expression::from(
+                    ExpressionKind::Number(Number(42))
+                ))),
+                span: Span::default(),
+            }))
         );
     }
+
+    #[test]
+    fn parse_expression_statement_with_semicolon() {
+        assert_eq!(
+            utils::parse_str(Statement::new, "10;"),
+            Ok(Statement::Expression(Expression::from(
+                ExpressionKind::Number(Number(10))
+            )))
+        )
+    }
 }
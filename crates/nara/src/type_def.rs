@@ -0,0 +1,244 @@
+use crate::{
+    error::Error,
+    expression::Expression,
+    lexer::{Span, Token, TokenKind},
+    type_expr::TypeExpr,
+    utils,
+};
+
+// `type Name { @Variant @Other(str) field: int other: str = "x" }`. a value
+// of the type is one of its variants, which may carry a payload, together
+// with every field. `$Variant` marks the variant used when none is named, a
+// type without variants has a single unnamed one
+#[derive(Debug, Clone)]
+pub(crate) struct TypeDef {
+    pub(crate) name: String,
+    pub(crate) variants: Vec<VariantDef>,
+    pub(crate) default_variant: Option<usize>,
+    pub(crate) fields: Vec<FieldDef>,
+    // not read by the interpreter yet, kept for diagnostics and tooling
+    #[allow(dead_code)]
+    pub(crate) span: Span,
+}
+
+// spans are not part of a type's identity, see `Expression`
+impl PartialEq for TypeDef {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.variants == other.variants
+            && self.default_variant == other.default_variant
+            && self.fields == other.fields
+    }
+}
+
+// `@Name` or `@Name(int, str)`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VariantDef {
+    pub(crate) name: String,
+    pub(crate) payload: Vec<TypeExpr>,
+}
+
+// `name: int`, `name: int = 1` or `name = 1`. the default value is evaluated
+// each time an instance leaving the field out is constructed
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FieldDef {
+    pub(crate) name: String,
+    pub(crate) ty: Option<TypeExpr>,
+    pub(crate) default: Option<Expression>,
+}
+
+impl TypeDef {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let tokens = utils::tag(&TokenKind::Type, tokens)?;
+        let (tokens, name) = utils::extract_ident(tokens)?;
+        let mut tokens = utils::tag(&TokenKind::LBrace, tokens)?;
+
+        let mut variants = Vec::new();
+        let mut default_variant = None;
+        let mut fields = Vec::new();
+
+        // one member per line, or separated by commas
+        while *utils::peek(tokens) != TokenKind::RBrace {
+            let member = tokens;
+
+            tokens = match utils::peek(tokens) {
+                TokenKind::At | TokenKind::Dollar => {
+                    let (rest, variant) = VariantDef::new(tokens)?;
+                    if variants.iter().any(|v: &VariantDef| v.name == variant.name) {
+                        return Err(utils::declared_twice("variant", &variant.name, member));
+                    }
+
+                    if *utils::peek(tokens) == TokenKind::Dollar {
+                        if default_variant.is_some() {
+                            return Err(utils::expected("a single `$` default variant", tokens));
+                        }
+                        default_variant = Some(variants.len());
+                    }
+
+                    variants.push(variant);
+                    rest
+                }
+                TokenKind::Ident(_) => {
+                    let (rest, field) = FieldDef::new(tokens)?;
+                    if fields.iter().any(|f: &FieldDef| f.name == field.name) {
+                        return Err(utils::declared_twice("field", &field.name, member));
+                    }
+
+                    fields.push(field);
+                    rest
+                }
+                _ => return Err(utils::expected("variant or field", tokens)),
+            };
+
+            tokens = utils::tag(&TokenKind::Comma, tokens).unwrap_or(tokens);
+        }
+        let tokens = utils::tag(&TokenKind::RBrace, tokens)?;
+        let tokens = utils::extract_semicolon(tokens);
+
+        Ok((
+            tokens,
+            Self {
+                name,
+                variants,
+                default_variant,
+                fields,
+                span: utils::consumed_span(start, tokens),
+            },
+        ))
+    }
+
+    pub(crate) fn variant(&self, name: &str) -> Option<&VariantDef> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+
+    pub(crate) fn field(&self, name: &str) -> Option<&FieldDef> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+impl VariantDef {
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (tokens, name) = utils::extract_ident(&tokens[1..])?;
+
+        let Ok(mut tokens) = utils::tag(&TokenKind::LParen, tokens) else {
+            return Ok((
+                tokens,
+                Self {
+                    name,
+                    payload: Vec::new(),
+                },
+            ));
+        };
+
+        let mut payload = Vec::new();
+        loop {
+            let (rest, ty) = TypeExpr::new(tokens)?;
+            payload.push(ty);
+
+            match utils::peek(rest) {
+                TokenKind::Comma => tokens = &rest[1..],
+                TokenKind::RParen => return Ok((&rest[1..], Self { name, payload })),
+                _ => return Err(utils::expected("',' or ')' in variant payload", rest)),
+            }
+        }
+    }
+}
+
+impl FieldDef {
+    fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let (tokens, name) = utils::extract_ident(tokens)?;
+
+        let (tokens, ty) = match utils::tag(&TokenKind::Colon, tokens) {
+            Ok(tokens) => TypeExpr::new(tokens).map(|(tokens, ty)| (tokens, Some(ty)))?,
+            Err(_) => (tokens, None),
+        };
+
+        let (tokens, default) = match utils::tag(&TokenKind::Assign, tokens) {
+            Ok(tokens) => Expression::new(tokens).map(|(tokens, val)| (tokens, Some(val)))?,
+            Err(_) if ty.is_some() => (tokens, None),
+            Err(_) => return Err(utils::expected("`:` or `=`", tokens)),
+        };
+
+        Ok((tokens, Self { name, ty, default }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::{ExpressionKind, Number};
+
+    fn named(name: &str) -> TypeExpr {
+        TypeExpr {
+            name: name.to_string(),
+            args: Vec::new(),
+            span: Span::default(),
+        }
+    }
+
+    #[test]
+    fn parse_type_def() {
+        assert_eq!(
+            utils::parse_str(
+                TypeDef::new,
+                "type Worker {
+                    @Developer
+                    $NoJob
+                    @Other(str)
+
+                    name: str
+                    eyes: int = 2
+                }"
+            ),
+            Ok(TypeDef {
+                name: "Worker".to_string(),
+                variants: vec![
+                    VariantDef {
+                        name: "Developer".to_string(),
+                        payload: Vec::new(),
+                    },
+                    VariantDef {
+                        name: "NoJob".to_string(),
+                        payload: Vec::new(),
+                    },
+                    VariantDef {
+                        name: "Other".to_string(),
+                        payload: vec![named("str")],
+                    },
+                ],
+                default_variant: Some(1),
+                fields: vec![
+                    FieldDef {
+                        name: "name".to_string(),
+                        ty: Some(named("str")),
+                        default: None,
+                    },
+                    FieldDef {
+                        name: "eyes".to_string(),
+                        ty: Some(named("int")),
+                        default: Some(Expression::from(ExpressionKind::Number(Number(2)))),
+                    },
+                ],
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn cannot_declare_field_twice() {
+        let err = utils::parse_str(TypeDef::new, "type Point { x: int, x: int }").unwrap_err();
+
+        assert_eq!(err.message(), "field `x` is declared twice");
+    }
+
+    #[test]
+    fn cannot_mark_two_default_variants() {
+        let err = utils::parse_str(TypeDef::new, "type Dir { $Up $Down }").unwrap_err();
+
+        assert_eq!(
+            err.message(),
+            "expected a single `$` default variant, found `$`"
+        );
+    }
+}
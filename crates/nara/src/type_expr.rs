@@ -0,0 +1,97 @@
+use crate::{
+    error::Error,
+    lexer::{Span, Token, TokenKind},
+    utils,
+};
+
+// type annotation as written in the source, eg. `int`, `list(str)` or
+// `map(str, int)`. annotations are checked by `nara::check`, evaluation
+// ignores them
+#[derive(Debug, Clone)]
+pub(crate) struct TypeExpr {
+    pub(crate) name: String,
+    pub(crate) args: Vec<TypeExpr>,
+    pub(crate) span: Span,
+}
+
+// spans are not part of an annotation's identity, see `Expression`
+impl PartialEq for TypeExpr {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.args == other.args
+    }
+}
+
+impl TypeExpr {
+    pub(crate) fn new(tokens: &[Token]) -> Result<(&[Token], Self), Error> {
+        let start = tokens;
+        let (tokens, name) = utils::extract_ident(tokens)?;
+
+        let Ok(mut tokens) = utils::tag(&TokenKind::LParen, tokens) else {
+            return Ok((
+                tokens,
+                Self {
+                    name,
+                    args: Vec::new(),
+                    span: utils::consumed_span(start, tokens),
+                },
+            ));
+        };
+
+        let mut args = Vec::new();
+        loop {
+            let (rest, arg) = Self::new(tokens)?;
+            args.push(arg);
+
+            match utils::peek(rest) {
+                TokenKind::Comma => tokens = &rest[1..],
+                TokenKind::RParen => {
+                    let span = utils::consumed_span(start, &rest[1..]);
+                    return Ok((&rest[1..], Self { name, args, span }));
+                }
+                _ => return Err(utils::expected("',' or ')' in type arguments", rest)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str) -> TypeExpr {
+        TypeExpr {
+            name: name.to_string(),
+            args: Vec::new(),
+            span: Span::default(),
+        }
+    }
+
+    #[test]
+    fn parse_simple_type() {
+        assert_eq!(utils::parse_str(TypeExpr::new, "int"), Ok(named("int")));
+    }
+
+    #[test]
+    fn parse_type_with_arguments() {
+        assert_eq!(
+            utils::parse_str(TypeExpr::new, "map(str, list(int))"),
+            Ok(TypeExpr {
+                name: "map".to_string(),
+                args: vec![
+                    named("str"),
+                    TypeExpr {
+                        name: "list".to_string(),
+                        args: vec![named("int")],
+                        span: Span::default(),
+                    },
+                ],
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn cannot_parse_empty_type_arguments() {
+        assert!(utils::parse_str(TypeExpr::new, "list()").is_err());
+    }
+}
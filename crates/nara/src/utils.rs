@@ -1,407 +1,380 @@
-// extraction utils
+// parsing utils
 //
 //
 //
-// iterators for extracting tokens from a string
-// I used Nom parser combinator solutions in these methods
-fn take_while(accept: impl Fn(char) -> bool, s: &str) -> (&str, &str) {
-    let extracted_end = s
-        .char_indices()
-        .find_map(|(idx, c)| if accept(c) { None } else { Some(idx) })
-        .unwrap_or(s.len());
-
-    let extracted = &s[..extracted_end];
-    let remainder = &s[extracted_end..];
-
-    (remainder, extracted)
+// combinators over the token stream produced by the lexer. every parser takes
+// the remaining tokens and returns the tokens left after it together with the
+// parsed item. the stream always ends with an `Eof` token, so looking at the
+// first token never fails
+use crate::{
+    error::Error,
+    expression::Expression,
+    function_def::Param,
+    lexer::{Span, Token, TokenKind},
+};
+
+pub(crate) fn peek(tokens: &[Token]) -> &TokenKind {
+    &tokens[0].kind
 }
 
-fn take_while_restrict(
-    accept: impl Fn(char) -> bool,
-    s: &str,
-    error_msg: String,
-) -> Result<(&str, &str), String> {
-    let (remainder, extracted) = take_while(accept, s);
-
-    if extracted.is_empty() {
-        Err(error_msg)
-    } else {
-        Ok((remainder, extracted))
+pub(crate) fn expected(what: &str, tokens: &[Token]) -> Error {
+    // a token the lexer could not read is reported as what is wrong with it
+    if let TokenKind::Error(err) = peek(tokens) {
+        return (**err).clone();
     }
-}
-
-pub(crate) fn extract_whitespace(s: &str) -> (&str, &str) {
-    take_while(|c| c.is_whitespace(), s)
-}
 
-pub(crate) fn extract_whitespace_restrict(s: &str) -> Result<(&str, &str), String> {
-    take_while_restrict(
-        |c| c == ' ' || c == '\t',
-        s,
-        "expected whitespace".to_string(),
-    )
+    Error::Syntax {
+        message: format!("expected {}, found {}", what, tokens[0].kind),
+        span: tokens[0].span.clone(),
+    }
 }
 
-pub(crate) fn extract_paranthesis(s: &str) -> Result<(&str, &str), String> {
-    s.chars()
-        .next()
-        .filter(|&c| c == '(')
-        .map(|_| {
-            let (remainder, extracted) = take_while(|c| c != ')', &s[1..]);
-            if let Some(after_paren) = remainder.strip_prefix(')') {
-                Ok((after_paren.trim_start(), extracted))
-            } else {
-                Err("expected closing parenthesis".to_string())
-            }
-        })
-        .unwrap_or_else(|| Err("expected opening parenthesis".to_string()))
-}
+// span from the first token of `before` to the last token consumed before `after`
+pub(crate) fn consumed_span(before: &[Token], after: &[Token]) -> Span {
+    let consumed = before.len() - after.len();
 
-pub(crate) fn extract_semicolon(s: &str) -> (&str, &str) {
-    if let Some(after_semi) = s.strip_prefix(';') {
-        (after_semi.trim_start(), ";")
+    if consumed == 0 {
+        Span {
+            end: before[0].span.start,
+            ..before[0].span.clone()
+        }
     } else {
-        (s, "")
+        before[0].span.to(&before[consumed - 1].span)
     }
 }
 
-// didn't use this function in the code, but it might be useful later if I change the way
-// semicolons are handled
-#[allow(dead_code)]
-pub(crate) fn extract_semicolon_restrict(s: &str) -> Result<(&str, &str), String> {
-    if let Some(after_semi) = s.strip_prefix(';') {
-        Ok((after_semi.trim_start(), ";"))
-    } else {
-        Err("expected semicolon".to_string())
+pub(crate) fn extract_semicolon(tokens: &[Token]) -> &[Token] {
+    match peek(tokens) {
+        TokenKind::Semicolon => &tokens[1..],
+        _ => tokens,
     }
 }
 
-// block expression statements extraction
+// statements of a block or a whole program, up to a closing `}` or the end of
+// input. an item that fails to parse is kept as `on_error(err)` and parsing
+// picks up again at the next statement boundary, so one typo does not hide
+// the problems after it
 pub(crate) fn sequence<T>(
-    parser: impl Fn(&str) -> Result<(&str, T), String>,
-    mut s: &str,
-) -> Result<(&str, Vec<T>), String> {
+    parser: impl Fn(&[Token]) -> Result<(&[Token], T), Error>,
+    on_error: impl Fn(Error) -> T,
+    mut tokens: &[Token],
+) -> (&[Token], Vec<T>) {
     let mut items = Vec::new();
 
-    while let Ok((new_s, item)) = parser(s) {
-        s = new_s;
-        items.push(item);
-
-        let (new_s, _) = extract_whitespace(s);
-        s = new_s;
+    while !matches!(peek(tokens), TokenKind::RBrace | TokenKind::Eof) {
+        match parser(tokens) {
+            Ok((rest, item)) => {
+                tokens = rest;
+                items.push(item);
+            }
+            Err(err) => {
+                tokens = recover(tokens, &err);
+                items.push(on_error(err));
+            }
+        }
     }
 
-    Ok((s, items))
+    (tokens, items)
 }
 
-pub(crate) fn extract_params(s: &str) -> Result<(&str, Vec<String>), String> {
-    let (s, params) = extract_paranthesis(s)?;
-
-    let params = params
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<String>>();
+// skip the rest of a statement that failed with `err`. parsing resumes after
+// a `;`, at a `}` closing the enclosing block or at the first token of a new
+// line, whichever comes first at or after the error. brackets opened on the
+// way are skipped as a whole. always skips at least one token
+pub(crate) fn recover<'a>(tokens: &'a [Token], err: &Error) -> &'a [Token] {
+    let err_start = err.span().start;
+    let mut depth = 0usize;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let at_error = token.span.start >= err_start;
+
+        match token.kind {
+            TokenKind::Eof => return &tokens[i..],
+            TokenKind::Semicolon if depth == 0 && at_error => return &tokens[i + 1..],
+            _ if i == 0 || depth > 0 || !at_error => {}
+            TokenKind::RBrace => return &tokens[i..],
+            _ if token.span.line > tokens[i - 1].span.line => return &tokens[i..],
+            _ => {}
+        }
 
-    Ok((s, params))
-}
+        match token.kind {
+            TokenKind::LParen | TokenKind::LBrace | TokenKind::LBracket => depth += 1,
+            TokenKind::RParen | TokenKind::RBrace | TokenKind::RBracket => {
+                depth = depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+    }
 
-// extract related tokens (eg. fn, val, mut)
-pub(crate) fn tag<'b>(starting_text: &str, s: &'b str) -> Result<&'b str, String> {
-    s.strip_prefix(starting_text)
-        .ok_or_else(|| format!("expected {}", starting_text))
+    unreachable!("token stream always ends with `Eof`")
 }
 
-pub(crate) fn extract_ident(s: &str) -> Result<(&str, &str), String> {
-    let input_starts_with_alphabetic = s
-        .chars()
-        .next()
-        .map(|c| c.is_ascii_alphabetic())
-        .unwrap_or(false);
-
-    if input_starts_with_alphabetic {
-        Ok(take_while(|c| c.is_ascii_alphanumeric(), s))
-    } else {
-        Err("expected identifier".to_string())
+// `tokens` start at an opening bracket, returns what follows its closing one
+pub(crate) fn skip_group(tokens: &[Token]) -> &[Token] {
+    let mut depth = 0usize;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::LParen | TokenKind::LBrace | TokenKind::LBracket => depth += 1,
+            TokenKind::RParen | TokenKind::RBrace | TokenKind::RBracket => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return &tokens[i + 1..];
+                }
+            }
+            TokenKind::Eof => return &tokens[i..],
+            _ => {}
+        }
     }
-}
 
-pub(crate) fn extract_digits(s: &str) -> Result<(&str, &str), String> {
-    take_while_restrict(|c| c.is_ascii_digit(), s, "expected digits".to_string())
+    unreachable!("token stream always ends with `Eof`")
 }
 
-// there should be a better solution
-pub(crate) fn extract_float(s: &str) -> Result<(&str, String), String> {
-    let (s, integer_part) = extract_digits(s)?;
+// run a parser that has to consume everything up to the end of input
+pub(crate) fn all_consuming<T>(
+    parser: impl Fn(&[Token]) -> Result<(&[Token], T), Error>,
+    tokens: &[Token],
+) -> Result<T, Error> {
+    let (tokens, item) = parser(tokens)?;
 
-    if let Some(after_dot) = s.strip_prefix('.') {
-        let (s, fractional_part) = take_while_restrict(
-            |c| c.is_ascii_digit(),
-            after_dot,
-            "expected digits after decimal point".to_string(),
-        )?;
+    match peek(tokens) {
+        TokenKind::Eof => Ok(item),
+        _ => Err(expected("end of input", tokens)),
+    }
+}
 
-        let float_str = format!("{}.{}", integer_part, fractional_part);
-        Ok((s, float_str))
+// optional value after `return` or `break`, left out when the statement ends
+// right after the keyword. the value has to start on the keyword's line, so a
+// bare keyword at the end of a line doesn't swallow the next statement
+pub(crate) fn extract_jump_value<'a>(
+    keyword: &Token,
+    tokens: &'a [Token],
+) -> Result<(&'a [Token], Option<Expression>), Error> {
+    let bare = matches!(
+        peek(tokens),
+        TokenKind::Semicolon | TokenKind::RBrace | TokenKind::RParen | TokenKind::Eof
+    ) || tokens[0].span.line > keyword.span.line;
+
+    if bare {
+        Ok((tokens, None))
     } else {
-        Err("expected decimal point".to_string())
+        Expression::new_standalone(tokens).map(|(tokens, val)| (tokens, Some(val)))
     }
 }
 
-#[allow(clippy::while_let_on_iterator)]
-pub(crate) fn extract_string_literal(s: &str) -> Result<(&str, String), String> {
-    // Check if string starts with double quote
-    if !s.starts_with('"') {
-        return Err("expected opening double quote".to_string());
-    }
+// parenthesized parameter list, `(a, b: int)`. only parameters listed in
+// parentheses can have a default value, `(a, b: int = 1)`
+pub(crate) fn extract_params(tokens: &[Token]) -> Result<(&[Token], Vec<Param>), Error> {
+    let mut tokens = tag(&TokenKind::LParen, tokens)?;
+    let mut params = Vec::new();
 
-    let mut chars = s[1..].chars();
-    let mut result = String::new();
-    let mut consumed = 1; // for opening quote
-
-    // We need while let here because we call chars.next() again for escape sequences
-    while let Some(ch) = chars.next() {
-        consumed += ch.len_utf8();
-        match ch {
-            '"' => {
-                // Found closing quote
-                return Ok((&s[consumed..], result));
-            }
-            '\\' => {
-                // Handle escape sequences
-                if let Some(escaped) = chars.next() {
-                    consumed += escaped.len_utf8();
-                    match escaped {
-                        'n' => result.push('\n'),
-                        't' => result.push('\t'),
-                        'r' => result.push('\r'),
-                        '\\' => result.push('\\'),
-                        '"' => result.push('"'),
-                        _ => {
-                            result.push('\\');
-                            result.push(escaped);
-                        }
-                    }
-                } else {
-                    return Err("unexpected end of string after backslash".to_string());
-                }
-            }
-            _ => result.push(ch),
+    loop {
+        if let Ok(rest) = tag(&TokenKind::RParen, tokens) {
+            return Ok((rest, params));
         }
-    }
 
-    Err("unclosed string literal".to_string())
-}
+        let (mut rest, mut param) = Param::new(tokens)?;
+        if let Ok(after) = tag(&TokenKind::Assign, rest) {
+            let (after, default) = Expression::new(after)?;
+            param.default = Some(default);
+            rest = after;
+        }
+        params.push(param);
 
-#[derive(Debug, PartialEq, Clone)]
-pub(crate) enum FStringPart {
-    Text(String),
-    Interpolation(String),
+        match peek(rest) {
+            TokenKind::Comma => tokens = &rest[1..],
+            TokenKind::RParen => return Ok((&rest[1..], params)),
+            _ => return Err(expected("',' or ')' in parameters", rest)),
+        }
+    }
 }
 
-#[allow(clippy::while_let_on_iterator)]
-pub(crate) fn extract_fstring(s: &str) -> Result<(&str, Vec<FStringPart>), String> {
-    // Check if string starts with f"
-    if !s.starts_with("f\"") {
-        return Err("expected f-string starting with f\"".to_string());
+// error for a member given twice in a declaration, `tokens` start at the
+// second one
+pub(crate) fn declared_twice(what: &str, name: &str, tokens: &[Token]) -> Error {
+    Error::Syntax {
+        message: format!("{} `{}` is declared twice", what, name),
+        span: tokens[0].span.clone(),
     }
+}
 
-    let mut chars = s[2..].chars();
-    let mut parts = Vec::new();
-    let mut current_text = String::new();
-    let mut consumed = 2; // for 'f"'
-
-    // We need while let here because we call chars.next() multiple times for escape sequences and interpolations
-    while let Some(ch) = chars.next() {
-        consumed += ch.len_utf8();
-        match ch {
-            '"' => {
-                // End of f-string
-                if !current_text.is_empty() {
-                    parts.push(FStringPart::Text(current_text));
-                }
-                return Ok((&s[consumed..], parts));
-            }
-            '{' => {
-                // Start of interpolation
-                if !current_text.is_empty() {
-                    parts.push(FStringPart::Text(current_text.clone()));
-                    current_text.clear();
-                }
-
-                // Find matching }
-                let mut expr = String::new();
-                let mut depth = 1;
-                while let Some(ch) = chars.next() {
-                    consumed += ch.len_utf8();
-                    if ch == '{' {
-                        depth += 1;
-                        expr.push(ch);
-                    } else if ch == '}' {
-                        depth -= 1;
-                        if depth == 0 {
-                            break;
-                        }
-                        expr.push(ch);
-                    } else {
-                        expr.push(ch);
-                    }
-                }
-
-                if depth != 0 {
-                    return Err("unclosed interpolation in f-string".to_string());
-                }
+// extract related tokens (eg. fn, val, =)
+pub(crate) fn tag<'a>(kind: &TokenKind, tokens: &'a [Token]) -> Result<&'a [Token], Error> {
+    if peek(tokens) == kind {
+        Ok(&tokens[1..])
+    } else {
+        Err(expected(&kind.to_string(), tokens))
+    }
+}
 
-                parts.push(FStringPart::Interpolation(expr));
-            }
-            '\\' => {
-                // Handle escape sequences
-                if let Some(escaped) = chars.next() {
-                    consumed += escaped.len_utf8();
-                    match escaped {
-                        'n' => current_text.push('\n'),
-                        't' => current_text.push('\t'),
-                        'r' => current_text.push('\r'),
-                        '\\' => current_text.push('\\'),
-                        '"' => current_text.push('"'),
-                        '{' => current_text.push('{'),
-                        '}' => current_text.push('}'),
-                        _ => {
-                            current_text.push('\\');
-                            current_text.push(escaped);
-                        }
-                    }
-                } else {
-                    return Err("unexpected end of f-string after backslash".to_string());
-                }
-            }
-            _ => current_text.push(ch),
-        }
+pub(crate) fn extract_ident(tokens: &[Token]) -> Result<(&[Token], String), Error> {
+    match peek(tokens) {
+        TokenKind::Ident(name) => Ok((&tokens[1..], name.clone())),
+        _ => Err(expected("identifier", tokens)),
     }
+}
 
-    Err("unclosed f-string literal".to_string())
+// lex and parse a whole string, for tests that start from source code
+#[cfg(test)]
+pub(crate) fn parse_str<T>(
+    parser: impl Fn(&[Token]) -> Result<(&[Token], T), Error>,
+    s: &str,
+) -> Result<T, Error> {
+    all_consuming(parser, &crate::lexer::lex(s)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lexer::lex;
 
     #[test]
-    fn extract_spaces() {
-        assert_eq!(extract_whitespace("\n   \n  1"), ("1", "\n   \n  "))
+    fn extract_alphabetic_ident() {
+        let tokens = lex("abcdEFGH stop").unwrap();
+        let (rest, ident) = extract_ident(&tokens).unwrap();
+
+        assert_eq!(ident, "abcdEFGH");
+        assert_eq!(rest[0].kind, TokenKind::Ident("stop".to_string()));
     }
 
     #[test]
-    fn extract_spaces_restrict() {
-        assert_eq!(extract_whitespace_restrict(" x"), Ok(("x", " ")))
+    fn cannot_extract_ident_beginning_with_number() {
+        assert_eq!(
+            extract_ident(&lex("123abc").unwrap()),
+            Err(Error::Syntax {
+                message: "expected identifier, found number `123`".to_string(),
+                span: Span {
+                    start: 0,
+                    end: 3,
+                    line: 1,
+                    column: 1,
+                    ..Span::default()
+                },
+            }),
+        );
     }
 
     #[test]
-    fn extract_alphabetic_ident() {
-        assert_eq!(extract_ident("abcdEFGH stop"), Ok((" stop", "abcdEFGH")))
+    fn tag_word() {
+        let tokens = lex("val x").unwrap();
+        let rest = tag(&TokenKind::Val, &tokens).unwrap();
+
+        assert_eq!(rest[0].kind, TokenKind::Ident("x".to_string()));
     }
 
     #[test]
-    fn extract_alphanumeric_ident() {
-        assert_eq!(extract_ident("foobar()"), Ok(("()", "foobar")))
+    fn cannot_tag_other_word() {
+        let err = tag(&TokenKind::Val, &lex("fn").unwrap()).unwrap_err();
+
+        assert_eq!(err.message(), "expected `val`, found `fn`");
+        assert_eq!((err.span().line, err.span().column), (1, 1));
     }
 
     #[test]
-    fn cannot_extract_ident_beginning_with_number() {
-        assert_eq!(
-            extract_ident("123abc"),
-            Err("expected identifier".to_string()),
-        );
+    fn extract_optional_semicolon() {
+        let tokens = lex("; x").unwrap();
+
+        assert_eq!(extract_semicolon(&tokens).len(), 2);
+        assert_eq!(extract_semicolon(&tokens[1..]).len(), 2);
     }
 
     #[test]
-    fn tag_word() {
-        assert_eq!(tag("val", "val x"), Ok(" x"))
+    fn extract_params_with_defaults() {
+        let (_, params) = extract_params(&lex("(a, b: int = 1)").unwrap()).unwrap();
+
+        assert_eq!(params[0].name(), Some("a"));
+        assert!(params[0].default.is_none());
+        assert_eq!(params[1].name(), Some("b"));
+        assert!(params[1].default.is_some());
     }
 
-    #[test]
-    fn extract_one_digit() {
-        assert_eq!(extract_digits("1+2"), Ok(("+2", "1")));
+    fn recovered(s: &str) -> TokenKind {
+        let tokens = lex(s).unwrap();
+        let err = Error::Syntax {
+            message: String::new(),
+            span: tokens
+                .iter()
+                .find(|t| t.kind == TokenKind::Bang)
+                .unwrap()
+                .span
+                .clone(),
+        };
+
+        recover(&tokens, &err)[0].kind.clone()
     }
 
     #[test]
-    fn extract_multiple_digits() {
-        assert_eq!(extract_digits("10-20"), Ok(("-20", "10")));
+    fn recover_after_semicolon() {
+        assert_eq!(
+            recovered("val x = ! 1; y"),
+            TokenKind::Ident("y".to_string())
+        );
     }
 
     #[test]
-    fn do_not_extract_digits_when_input_is_invalid() {
-        assert_eq!(extract_digits("abcd"), Err("expected digits".to_string()));
+    fn recover_at_closing_brace() {
+        assert_eq!(recovered("1 + ! } y"), TokenKind::RBrace);
     }
 
     #[test]
-    fn extract_digits_with_no_remainder() {
-        assert_eq!(extract_digits("100"), Ok(("", "100")));
+    fn recover_at_new_line() {
+        assert_eq!(recovered("val x = !\ny"), TokenKind::Ident("y".to_string()));
     }
 
     #[test]
-    fn do_not_extract_spaces1_when_input_does_not_start_with_them() {
+    fn recover_skips_brackets() {
         assert_eq!(
-            extract_whitespace_restrict("blah"),
-            Err("expected whitespace".to_string()),
+            recovered("f(!\n1, [2;\n3]); y"),
+            TokenKind::Ident("y".to_string())
         );
     }
 
     #[test]
-    fn extract_simple_fstring() {
-        assert_eq!(
-            extract_fstring("f\"hello\""),
-            Ok(("", vec![FStringPart::Text("hello".to_string())]))
-        );
+    fn recover_always_makes_progress() {
+        assert_eq!(recovered("!\ny"), TokenKind::Ident("y".to_string()));
     }
 
     #[test]
-    fn extract_fstring_with_interpolation() {
-        assert_eq!(
-            extract_fstring("f\"Hello {name}!\""),
-            Ok((
-                "",
-                vec![
-                    FStringPart::Text("Hello ".to_string()),
-                    FStringPart::Interpolation("name".to_string()),
-                    FStringPart::Text("!".to_string()),
-                ]
-            ))
-        );
+    fn sequence_keeps_going_after_errors() {
+        let tokens = lex("a; 1 +; b").unwrap();
+        let (rest, items) = sequence(extract_ident, |_| "error".to_string(), &tokens);
+
+        assert_eq!(items, vec!["a", "error", "error", "b"]);
+        assert_eq!(rest[0].kind, TokenKind::Eof);
     }
 
     #[test]
-    fn extract_fstring_with_expression() {
+    fn extract_param_list() {
         assert_eq!(
-            extract_fstring("f\"Result: {10 + 20}\""),
-            Ok((
-                "",
-                vec![
-                    FStringPart::Text("Result: ".to_string()),
-                    FStringPart::Interpolation("10 + 20".to_string()),
-                ]
-            ))
+            parse_str(extract_params, "(a, b)"),
+            Ok(vec![Param::from("a"), Param::from("b")])
         );
     }
 
     #[test]
-    fn extract_fstring_with_escaped_braces() {
+    fn consumed_span_covers_consumed_tokens() {
+        let tokens = lex("foo + bar baz").unwrap();
+
         assert_eq!(
-            extract_fstring("f\"test \\{not_interpolated\\}\""),
-            Ok((
-                "",
-                vec![FStringPart::Text("test {not_interpolated}".to_string())]
-            ))
+            consumed_span(&tokens, &tokens[3..]),
+            Span {
+                start: 0,
+                end: 9,
+                line: 1,
+                column: 1,
+                ..Span::default()
+            }
         );
     }
 
     #[test]
-    fn extract_fstring_unclosed() {
-        assert!(extract_fstring("f\"hello").is_err());
-    }
+    fn all_consuming_rejects_leftover_tokens() {
+        let err = parse_str(extract_ident, "foo bar").unwrap_err();
 
-    #[test]
-    fn extract_fstring_unclosed_interpolation() {
-        assert!(extract_fstring("f\"hello {name\"").is_err());
+        assert_eq!(
+            err.message(),
+            "expected end of input, found identifier `bar`"
+        );
+        assert_eq!((err.span().line, err.span().column), (1, 5));
     }
 }
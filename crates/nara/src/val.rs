@@ -1,9 +1,415 @@
-use std::rc::Rc;
+use crate::{
+    env::Env,
+    error::{Error, Unwind},
+    function_def::Param,
+    lexer::Span,
+    proto_def::ProtoDef,
+    type_def::TypeDef,
+};
+use std::{collections::HashMap, fmt, rc::Rc};
 
-#[derive(Debug, Clone, PartialEq)]
+// a function value closes over the scope it was created in. a function stored
+// in that scope is part of a reference cycle and is never freed
+#[derive(Clone)]
 pub struct Function {
-    pub(crate) params: Vec<String>,
+    pub(crate) params: Vec<Param>,
     pub(crate) body: Rc<crate::statement::Statement>,
+    pub(crate) env: Env,
+}
+
+impl Function {
+    // whether the function can be called with `found` arguments by position,
+    // the ones after them need a default value
+    pub(crate) fn check_arity(&self, name: &str, found: usize, span: &Span) -> Result<(), Error> {
+        let required = self
+            .params
+            .iter()
+            .rposition(|param| param.default.is_none())
+            .map_or(0, |last| last + 1);
+
+        if (required..=self.params.len()).contains(&found) {
+            Ok(())
+        } else {
+            Err(Error::ArityMismatch {
+                name: name.to_string(),
+                expected: if found < required {
+                    required
+                } else {
+                    self.params.len()
+                },
+                found,
+                span: span.clone(),
+            })
+        }
+    }
+
+    // run the body with the arguments bound to the parameters, `args` by
+    // position and then `named` by name. parameters left out get their
+    // default value. errors binding the arguments have no span, the caller
+    // fills in its own
+    pub(crate) fn call(
+        &self,
+        name: &str,
+        args: Vec<Val>,
+        named: Vec<(String, Val)>,
+    ) -> Result<Val, Unwind> {
+        // without names the number of arguments tells what is wrong, with
+        // names a parameter left out is reported by its name
+        if named.is_empty() || args.len() > self.params.len() {
+            self.check_arity(name, args.len(), &Span::default())?;
+        }
+
+        let mut slots: Vec<Option<Val>> = args.into_iter().map(Some).collect();
+        slots.resize(self.params.len(), None);
+
+        for (param, val) in named {
+            let position = self.params.iter().position(|p| p.name() == Some(&param));
+            let Some(slot) = position else {
+                return Err(Error::UnknownParam {
+                    name: name.to_string(),
+                    param,
+                    span: Span::default(),
+                }
+                .into());
+            };
+
+            if slots[slot].is_some() {
+                return Err(Error::DuplicateArg {
+                    name: name.to_string(),
+                    param,
+                    span: Span::default(),
+                }
+                .into());
+            }
+            slots[slot] = Some(val);
+        }
+
+        // the body runs in a child of the scope the function was defined in,
+        // not of the caller's scope
+        let mut env = self.env.create_child();
+
+        for (param, slot) in self.params.iter().zip(slots) {
+            let val = match (slot, &param.default) {
+                (Some(val), _) => val,
+                (None, Some(default)) => default.eval(&env)?,
+                (None, None) => {
+                    return Err(Error::MissingArg {
+                        name: name.to_string(),
+                        param: param.name().unwrap_or("_").to_string(),
+                        span: Span::default(),
+                    }
+                    .into());
+                }
+            };
+            param.pattern.bind(val, &mut env, false)?;
+        }
+
+        // a `return` or `?` ends the call with its value
+        match self.body.eval(&mut env) {
+            Err(Unwind::Return(val, _) | Unwind::Propagate(val, _)) => Ok(val),
+            // loops don't reach into the functions they call
+            Err(jump @ (Unwind::Break(..) | Unwind::Continue(_))) => Err(jump.into_error().into()),
+            result => result,
+        }
+    }
+}
+
+// the captured scope is left out, it usually contains the function itself
+impl fmt::Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Function")
+            .field("params", &self.params)
+            .field("body", &self.body)
+            .finish_non_exhaustive()
+    }
+}
+
+// functions are equal when they have the same code and captured the same scope
+impl PartialEq for Function {
+    fn eq(&self, other: &Self) -> bool {
+        self.params == other.params && self.body == other.body && self.env.same_frame(&other.env)
+    }
+}
+
+// integers from `start` towards `end` counting by `step`, produced on demand.
+// `end` is left out unless the range is inclusive
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    pub(crate) start: i64,
+    pub(crate) end: i64,
+    pub(crate) step: i64,
+    pub(crate) inclusive: bool,
+}
+
+impl Range {
+    // `step` must not be zero
+    pub(crate) fn new(start: i64, end: i64, step: i64, inclusive: bool) -> Self {
+        debug_assert!(step != 0);
+        Self {
+            start,
+            end,
+            step,
+            inclusive,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        let (start, end, step) = (self.start as i128, self.end as i128, self.step as i128);
+        // distance to the last value the range may reach, negative when empty
+        let last = if self.inclusive {
+            end
+        } else {
+            end - step.signum()
+        };
+        let distance = (last - start) * step.signum();
+
+        // `i64::MIN..=i64::MAX` has one value more than fits a 64 bit usize
+        if distance < 0 {
+            0
+        } else {
+            usize::try_from(distance / step.abs() + 1).unwrap_or(usize::MAX)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<i64> {
+        (index < self.len()).then(|| self.start + index as i64 * self.step)
+    }
+
+    pub fn contains(&self, n: i64) -> bool {
+        let offset = n as i128 - self.start as i128;
+
+        offset % self.step as i128 == 0
+            && usize::try_from(offset / self.step as i128).is_ok_and(|index| index < self.len())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = i64> + use<> {
+        let range = self.clone();
+        (0..range.len()).map(move |index| range.start + index as i64 * range.step)
+    }
+}
+
+// what a value is looked up by when used as a map key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Number(i64),
+    String(Rc<str>),
+    Char(char),
+    Bool(bool),
+}
+
+impl Key {
+    fn new(val: &Val) -> Option<Self> {
+        match val {
+            Val::Number(n) => Some(Self::Number(*n)),
+            Val::String(s) => Some(Self::String(s.clone())),
+            Val::Char(c) => Some(Self::Char(*c)),
+            Val::Bool(b) => Some(Self::Bool(*b)),
+            _ => None,
+        }
+    }
+}
+
+// map keeping its entries in insertion order, so iterating and printing it
+// gives the same result on every run
+#[derive(Debug, Clone, Default)]
+pub struct Map {
+    entries: Vec<(Val, Val)>,
+    positions: HashMap<Key, usize>,
+}
+
+impl Map {
+    // types of the values that can be keys, for error messages
+    pub(crate) const KEY_TYPES: &str = "int, str, char or bool";
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &Val) -> Option<&Val> {
+        let position = self.positions.get(&Key::new(key)?)?;
+        Some(&self.entries[*position].1)
+    }
+
+    pub(crate) fn is_key(val: &Val) -> bool {
+        Key::new(val).is_some()
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &Val) -> Option<&mut Val> {
+        let position = self.positions.get(&Key::new(key)?)?;
+        Some(&mut self.entries[*position].1)
+    }
+
+    pub fn contains_key(&self, key: &Val) -> bool {
+        self.get(key).is_some()
+    }
+
+    // replaces the value of an existing key without moving it. fails with the
+    // key when its type can't be a key
+    pub(crate) fn insert(&mut self, key: Val, val: Val) -> Result<Option<Val>, Val> {
+        let Some(map_key) = Key::new(&key) else {
+            return Err(key);
+        };
+
+        match self.positions.get(&map_key) {
+            Some(position) => Ok(Some(std::mem::replace(&mut self.entries[*position].1, val))),
+            None => {
+                self.positions.insert(map_key, self.entries.len());
+                self.entries.push((key, val));
+                Ok(None)
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &Val) -> Option<Val> {
+        let position = self.positions.remove(&Key::new(key)?)?;
+        let (_, val) = self.entries.remove(position);
+
+        // entries after the removed one moved one place to the front
+        for later in self.positions.values_mut() {
+            if *later > position {
+                *later -= 1;
+            }
+        }
+
+        Some(val)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Val, &Val)> {
+        self.entries.iter().map(|(key, val)| (key, val))
+    }
+
+    pub(crate) fn into_entries(self) -> impl Iterator<Item = (Val, Val)> {
+        self.entries.into_iter()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Val> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Val> {
+        self.entries.iter().map(|(_, val)| val)
+    }
+}
+
+// maps with the same entries are equal whatever their order
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, val)| other.get(key) == Some(val))
+    }
+}
+
+// error bound by `catch`, a runtime error or the payload of an `err`. nara
+// code reads it through the `kind` and `message` fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caught {
+    pub(crate) kind: Rc<str>,
+    pub(crate) message: Rc<str>,
+}
+
+impl Caught {
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+// what a `type` statement binds the name of the type to. default field values
+// are evaluated in the scope the type was declared in
+#[derive(Clone)]
+pub struct Type {
+    pub(crate) def: Rc<TypeDef>,
+    pub(crate) env: Env,
+}
+
+impl Type {
+    pub fn name(&self) -> &str {
+        &self.def.name
+    }
+}
+
+// the captured scope is left out, like for functions
+impl fmt::Debug for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Type")
+            .field("def", &self.def)
+            .finish_non_exhaustive()
+    }
+}
+
+// a type is only equal to itself, not to another declaration of the same code
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.def, &other.def)
+    }
+}
+
+// what a `proto` statement binds the name of the protocol to
+#[derive(Debug, Clone)]
+pub struct Proto {
+    pub(crate) def: Rc<ProtoDef>,
+}
+
+impl Proto {
+    pub fn name(&self) -> &str {
+        &self.def.name
+    }
+}
+
+// like types, a protocol is only equal to itself
+impl PartialEq for Proto {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.def, &other.def)
+    }
+}
+
+// value of a user defined type, `Worker@Other("cook") { name = "Ezra" }`. the
+// variant is left out for types that don't declare any. fields keep the
+// order of the declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    pub(crate) ty: Rc<str>,
+    pub(crate) variant: Option<Rc<str>>,
+    pub(crate) payload: Vec<Val>,
+    pub(crate) fields: Vec<(Rc<str>, Val)>,
+}
+
+impl Instance {
+    pub fn type_name(&self) -> &str {
+        &self.ty
+    }
+
+    pub fn variant(&self) -> Option<&str> {
+        self.variant.as_deref()
+    }
+
+    pub fn payload(&self) -> &[Val] {
+        &self.payload
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Val> {
+        self.fields
+            .iter()
+            .find(|(field, _)| **field == *name)
+            .map(|(_, val)| val)
+    }
+
+    pub(crate) fn field_mut(&mut self, name: &str) -> Option<&mut Val> {
+        self.fields
+            .iter_mut()
+            .find(|(field, _)| **field == *name)
+            .map(|(_, val)| val)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -11,9 +417,22 @@ pub enum Val {
     Number(i64),
     Float(f64),
     String(Rc<str>),
+    Char(char),
     Bool(bool),
     Function(Function),
     List(Vec<Val>),
+    // fixed number of values, `$(a, b)`
+    Tuple(Vec<Val>),
+    // `opt(T)`, `none` or `some(value)`
+    Opt(Option<Box<Val>>),
+    // `res(T, E)`, `ok(value)` or `err(error)`
+    Res(Result<Box<Val>, Box<Val>>),
+    Range(Range),
+    Map(Map),
+    Error(Caught),
+    Type(Type),
+    Proto(Proto),
+    Instance(Box<Instance>),
     Unit,
 }
 
@@ -24,9 +443,260 @@ impl Val {
             Val::Number(n) => *n != 0,
             Val::Float(f) => *f != 0.0,
             Val::String(s) => !s.is_empty(),
+            Val::Char(_) => true,
             Val::Function(_) => true,
-            Val::List(items) => !items.is_empty(),
+            Val::List(items) | Val::Tuple(items) => !items.is_empty(),
+            Val::Opt(val) => val.is_some(),
+            Val::Res(val) => val.is_ok(),
+            Val::Range(range) => !range.is_empty(),
+            Val::Map(map) => !map.is_empty(),
+            Val::Error(_) | Val::Type(_) | Val::Proto(_) | Val::Instance(_) => true,
             Val::Unit => false,
         }
     }
+
+    // name of the value's type as written in nara source
+    pub fn type_name(&self) -> &str {
+        match self {
+            Val::Number(_) => "int",
+            Val::Float(_) => "float",
+            Val::String(_) => "str",
+            Val::Char(_) => "char",
+            Val::Bool(_) => "bool",
+            Val::Function(_) => "fn",
+            Val::List(_) => "list",
+            Val::Tuple(_) => "tuple",
+            Val::Opt(_) => "opt",
+            Val::Res(_) => "res",
+            Val::Range(_) => "range",
+            Val::Map(_) => "map",
+            Val::Error(_) => "error",
+            Val::Type(_) => "type",
+            Val::Proto(_) => "proto",
+            Val::Instance(instance) => &instance.ty,
+            Val::Unit => "nil",
+        }
+    }
+
+    // rendering inside an error message, where strings and chars need quotes
+    // too
+    pub(crate) fn repr(&self) -> String {
+        match self {
+            Val::String(s) => format!("\"{}\"", s),
+            Val::Char(c) => format!("'{}'", c),
+            other => other.to_string(),
+        }
+    }
+
+    // how the value looks inside another one, strings and chars are quoted
+    fn fmt_nested(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Val::String(s) => write!(f, "\"{}\"", s),
+            Val::Char(c) => write!(f, "'{}'", c),
+            other => write!(f, "{}", other),
+        }
+    }
+}
+
+// rendering used by `print` and f-strings
+impl fmt::Display for Val {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Val::Number(n) => write!(f, "{}", n),
+            Val::Float(n) => write!(f, "{}", n),
+            Val::String(s) => write!(f, "{}", s),
+            Val::Char(c) => write!(f, "{}", c),
+            Val::Bool(b) => write!(f, "{}", b),
+            Val::Function(_) => write!(f, "<function>"),
+            Val::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.fmt_nested(f)?;
+                }
+                write!(f, "]")
+            }
+            // a single item keeps its comma, `(1,)`, so it doesn't read as a
+            // parenthesized value
+            Val::Tuple(items) => {
+                write!(f, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.fmt_nested(f)?;
+                }
+                if items.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Val::Opt(Some(val)) => {
+                write!(f, "some(")?;
+                val.fmt_nested(f)?;
+                write!(f, ")")
+            }
+            Val::Opt(None) => write!(f, "none"),
+            Val::Error(caught) => write!(f, "{}: {}", caught.kind, caught.message),
+            Val::Res(Ok(val)) => {
+                write!(f, "ok(")?;
+                val.fmt_nested(f)?;
+                write!(f, ")")
+            }
+            Val::Res(Err(err)) => {
+                write!(f, "err(")?;
+                err.fmt_nested(f)?;
+                write!(f, ")")
+            }
+            Val::Map(map) if map.is_empty() => write!(f, "[:]"),
+            Val::Map(map) => {
+                write!(f, "[")?;
+                for (i, (key, val)) in map.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    key.fmt_nested(f)?;
+                    write!(f, ": ")?;
+                    val.fmt_nested(f)?;
+                }
+                write!(f, "]")
+            }
+            Val::Range(range) => {
+                let dots = if range.inclusive { "..=" } else { ".." };
+                write!(f, "{}{}{}", range.start, dots, range.end)?;
+                if range.step != 1 {
+                    write!(f, " step {}", range.step)?;
+                }
+                Ok(())
+            }
+            Val::Type(ty) => write!(f, "<type {}>", ty.name()),
+            Val::Proto(proto) => write!(f, "<proto {}>", proto.name()),
+            Val::Instance(instance) => {
+                write!(f, "{}", instance.ty)?;
+                if let Some(variant) = &instance.variant {
+                    write!(f, "@{}", variant)?;
+                }
+
+                if !instance.payload.is_empty() {
+                    write!(f, "(")?;
+                    for (i, item) in instance.payload.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        item.fmt_nested(f)?;
+                    }
+                    write!(f, ")")?;
+                }
+
+                if !instance.fields.is_empty() {
+                    write!(f, " {{ ")?;
+                    for (i, (name, val)) in instance.fields.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{} = ", name)?;
+                        val.fmt_nested(f)?;
+                    }
+                    write!(f, " }}")?;
+                }
+                Ok(())
+            }
+            Val::Unit => write!(f, "()"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_nested_values() {
+        let val = Val::List(vec![
+            Val::Number(1),
+            Val::String("two".into()),
+            Val::List(vec![Val::Opt(Some(Box::new(Val::String("x".into()))))]),
+            Val::Opt(None),
+        ]);
+
+        assert_eq!(val.to_string(), r#"[1, "two", [some("x")], none]"#);
+        assert_eq!(Val::String("top".into()).to_string(), "top");
+        assert_eq!(
+            Val::Range(Range::new(0, 10, 2, true)).to_string(),
+            "0..=10 step 2"
+        );
+        assert_eq!(
+            Val::Tuple(vec![Val::String("a".into()), Val::Number(1)]).to_string(),
+            r#"("a", 1)"#
+        );
+        assert_eq!(Val::Tuple(vec![Val::Number(1)]).to_string(), "(1,)");
+        assert_eq!(
+            Val::List(vec![Val::Char('a'), Val::Char('\n')]).to_string(),
+            "['a', '\n']"
+        );
+        assert_eq!(Val::Char('a').to_string(), "a");
+        assert_eq!(
+            Val::Res(Err(Box::new(Val::String("bad".into())))).to_string(),
+            r#"err("bad")"#
+        );
+    }
+
+    #[test]
+    fn map_keeps_insertion_order() {
+        let mut map = Map::default();
+        for (key, val) in [("b", 1), ("a", 2), ("c", 3)] {
+            assert_eq!(
+                map.insert(Val::String(key.into()), Val::Number(val)),
+                Ok(None)
+            );
+        }
+
+        assert_eq!(
+            map.insert(Val::String("b".into()), Val::Number(10)),
+            Ok(Some(Val::Number(1)))
+        );
+        assert_eq!(map.remove(&Val::String("a".into())), Some(Val::Number(2)));
+        assert_eq!(map.get(&Val::String("c".into())), Some(&Val::Number(3)));
+        assert_eq!(Val::Map(map).to_string(), r#"["b": 10, "c": 3]"#);
+        assert_eq!(Val::Map(Map::default()).to_string(), "[:]");
+    }
+
+    #[test]
+    fn map_rejects_unhashable_keys() {
+        let mut map = Map::default();
+
+        assert_eq!(map.insert(Val::Float(1.5), Val::Unit), Err(Val::Float(1.5)));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn range_len_and_items() {
+        let items = |range: Range| range.iter().collect::<Vec<_>>();
+
+        assert_eq!(items(Range::new(0, 3, 1, false)), [0, 1, 2]);
+        assert_eq!(items(Range::new(0, 3, 1, true)), [0, 1, 2, 3]);
+        assert_eq!(items(Range::new(0, 10, 4, false)), [0, 4, 8]);
+        assert_eq!(items(Range::new(5, 0, -2, true)), [5, 3, 1]);
+        assert!(Range::new(3, 3, 1, false).is_empty());
+        assert!(Range::new(3, 0, 1, true).is_empty());
+        assert_eq!(
+            Range::new(0, 1_000_000_000_000, 1, false).len(),
+            1_000_000_000_000
+        );
+    }
+
+    #[test]
+    fn range_get_and_contains() {
+        let range = Range::new(1, 10, 3, true);
+
+        assert_eq!(range.get(0), Some(1));
+        assert_eq!(range.get(3), Some(10));
+        assert_eq!(range.get(4), None);
+        assert!(range.contains(7));
+        assert!(!range.contains(8));
+        assert!(!range.contains(13));
+        assert!(!range.contains(-2));
+    }
 }